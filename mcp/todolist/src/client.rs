@@ -0,0 +1,108 @@
+//! REST client for the todolist-sqlite `/v2/todo` routes
+
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+
+use crate::models::{Todo, TodoBody, TodoList, ValidationErrorResponse};
+
+/// Errors are messages meant for the assistant, so it can correct the call and retry
+#[derive(Clone)]
+pub struct TodoClient {
+    http: Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl TodoClient {
+    /// `base_url` includes the server's `app.prefix`, e.g. `http://localhost:8080/api`
+    pub fn new(base_url: &str, api_key: Option<String>) -> Self {
+        Self {
+            http: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+
+    pub async fn list(&self, completed: Option<bool>, search: Option<&str>) -> Result<TodoList, String> {
+        let mut request = self.http.get(self.url("/v2/todo"));
+        if let Some(completed) = completed {
+            request = request.query(&[("completed", completed)]);
+        }
+        if let Some(search) = search {
+            request = request.query(&[("search", search)]);
+        }
+        self.send(request).await
+    }
+
+    pub async fn get(&self, id: i64) -> Result<Todo, String> {
+        self.send(self.http.get(self.url(&format!("/v2/todo/{}", id)))).await
+    }
+
+    pub async fn create(&self, todo: &TodoBody) -> Result<Todo, String> {
+        self.send(self.http.post(self.url("/v2/todo")).json(todo)).await
+    }
+
+    pub async fn update(&self, id: i64, todo: &TodoBody) -> Result<Todo, String> {
+        self.send(self.http.put(self.url(&format!("/v2/todo/{}", id))).json(todo)).await
+    }
+
+    pub async fn delete(&self, id: i64) -> Result<(), String> {
+        let request = self.http.delete(self.url(&format!("/v2/todo/{}", id)));
+        self.execute(request).await.map(|_| ())
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, String> {
+        self.execute(request)
+            .await?
+            .json::<T>()
+            .await
+            .map_err(|e| format!("Unexpected response from the todo API: {}", e))
+    }
+
+    async fn execute(&self, mut request: RequestBuilder) -> Result<Response, String> {
+        if let Some(api_key) = &self.api_key {
+            request = request.header("x-api-key", api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Could not reach the todo API at {}: {}", self.base_url, e))?;
+
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Err(describe_error(status, &body))
+    }
+}
+
+fn describe_error(status: StatusCode, body: &str) -> String {
+    if status == StatusCode::NOT_FOUND {
+        return "Todo not found".to_string();
+    }
+
+    if status == StatusCode::UNPROCESSABLE_ENTITY
+        && let Ok(validation) = serde_json::from_str::<ValidationErrorResponse>(body)
+    {
+        let fields = validation
+            .errors
+            .iter()
+            .map(|error| format!("- {}: {}", error.field, error.message))
+            .collect::<Vec<String>>()
+            .join("\n");
+        return format!("{}:\n{}", validation.message, fields);
+    }
+
+    if body.is_empty() {
+        format!("Todo API responded {}", status)
+    } else {
+        format!("Todo API responded {}: {}", status, body)
+    }
+}
@@ -0,0 +1,54 @@
+use std::net::SocketAddr;
+
+use anyhow::Result;
+use rmcp::transport::{StreamableHttpService, streamable_http_server::session::local::LocalSessionManager};
+
+mod client;
+mod models;
+mod request;
+mod resources;
+mod server;
+
+use client::TodoClient;
+use server::TodoService;
+
+const DEFAULT_TODO_URL: &str = "http://localhost:8080/api";
+const DEFAULT_ADDR: &str = "127.0.0.1:8767";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let addr: SocketAddr = std::env::var("MCP_ADDR")
+        .unwrap_or_else(|_| DEFAULT_ADDR.to_string())
+        .parse()?;
+
+    // Same variables as the `todo` CLI
+    let todo_url = std::env::var("TODO_URL").unwrap_or_else(|_| DEFAULT_TODO_URL.to_string());
+    let api_key = std::env::var("TODO_API_KEY").ok();
+
+    let todo_service = TodoService::new(TodoClient::new(&todo_url, api_key));
+
+    let service = StreamableHttpService::new(
+        move || Ok(todo_service.clone()),
+        LocalSessionManager::default().into(),
+        Default::default(),
+    );
+
+    let router = axum::Router::new()
+        .nest_service("/mcp", service);
+
+    println!("Todo list MCP server listening on {}", addr);
+    println!("Endpoint: http://{}/mcp", addr);
+    println!("Todo API: {}", todo_url);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c()
+                .await
+                .expect("failed to listen for ctrl-c")
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// A todo item as returned by `/v2/todo`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Todo {
+    pub id: i64,
+    pub title: String,
+    pub completed: bool,
+    pub due_at: Option<String>,
+}
+
+/// A page of todo items
+#[derive(Debug, Deserialize, Clone)]
+pub struct TodoList {
+    pub items: Vec<Todo>,
+    pub total: usize,
+}
+
+/// Body of a create or update; the API replaces every field on update
+#[derive(Debug, Serialize, Clone)]
+pub struct TodoBody {
+    pub title: String,
+    pub completed: bool,
+    pub due_at: Option<String>,
+}
+
+impl From<Todo> for TodoBody {
+    fn from(todo: Todo) -> Self {
+        Self {
+            title: todo.title,
+            completed: todo.completed,
+            due_at: todo.due_at,
+        }
+    }
+}
+
+/// Body of a 422 response
+#[derive(Debug, Deserialize)]
+pub struct ValidationErrorResponse {
+    pub message: String,
+    pub errors: Vec<FieldError>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
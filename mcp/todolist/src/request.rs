@@ -0,0 +1,50 @@
+use rmcp::schemars;
+
+/// Parameters for the create_todo tool
+#[derive(serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct CreateTodoRequest {
+    /// Title of the task, 1 to 200 characters without leading or trailing whitespace
+    pub title: String,
+
+    /// Deadline in RFC3339 format (e.g., "2026-11-01T17:00:00Z")
+    #[serde(default)]
+    pub due_at: Option<String>,
+}
+
+/// Parameters for the list_todos tool
+#[derive(serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct ListTodosRequest {
+    /// Only return completed (true) or open (false) todos
+    #[serde(default)]
+    pub completed: Option<bool>,
+
+    /// Only return todos whose title contains this text, ignoring case
+    #[serde(default)]
+    pub search: Option<String>,
+}
+
+/// Parameters for the tools acting on a single todo
+#[derive(serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct TodoIdRequest {
+    /// Id of the todo
+    pub id: i64,
+}
+
+/// Parameters for the update_todo tool; omitted fields keep their value
+#[derive(serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct UpdateTodoRequest {
+    /// Id of the todo
+    pub id: i64,
+
+    /// New title
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Mark the todo completed (true) or open again (false)
+    #[serde(default)]
+    pub completed: Option<bool>,
+
+    /// New deadline in RFC3339 format, or an empty string to remove it
+    #[serde(default)]
+    pub due_at: Option<String>,
+}
@@ -0,0 +1,60 @@
+//! Todos exposed as MCP resources
+
+use crate::models::Todo;
+
+/// Whole list of todos
+pub const LIST_URI: &str = "todo://todos";
+
+/// A single todo
+pub const ITEM_TEMPLATE: &str = "todo://todos/{id}";
+
+pub enum TodoUri {
+    List,
+    Item(i64),
+}
+
+pub fn item_uri(id: i64) -> String {
+    format!("{}/{}", LIST_URI, id)
+}
+
+pub fn parse(uri: &str) -> Option<TodoUri> {
+    let rest = uri.strip_prefix(LIST_URI)?;
+    if rest.is_empty() {
+        return Some(TodoUri::List);
+    }
+    rest.strip_prefix('/')?.parse().ok().map(TodoUri::Item)
+}
+
+/// Short human readable line for a todo, as shown in tool results
+pub fn describe(todo: &Todo) -> String {
+    let status = if todo.completed { "x" } else { " " };
+    match &todo.due_at {
+        Some(due_at) => format!("[{}] #{} {} (due {})", status, todo.id, todo.title, due_at),
+        None => format!("[{}] #{} {}", status, todo.id, todo.title),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_uris() {
+        assert!(matches!(parse("todo://todos"), Some(TodoUri::List)));
+        assert!(matches!(parse(&item_uri(42)), Some(TodoUri::Item(42))));
+        assert!(parse("todo://todos/abc").is_none());
+        assert!(parse("todo://todosx").is_none());
+        assert!(parse("file:///etc/passwd").is_none());
+    }
+
+    #[test]
+    fn test_describe() {
+        let todo = Todo {
+            id: 7,
+            title: "Pay rent".to_string(),
+            completed: false,
+            due_at: Some("2026-11-01T09:00:00Z".to_string()),
+        };
+        assert_eq!(describe(&todo), "[ ] #7 Pay rent (due 2026-11-01T09:00:00Z)");
+    }
+}
@@ -0,0 +1,220 @@
+use rmcp::{
+    ErrorData as McpError, RoleServer, ServerHandler,
+    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    model::*,
+    service::RequestContext,
+    tool, tool_handler, tool_router,
+};
+use serde::Serialize;
+
+use crate::client::TodoClient;
+use crate::models::{Todo, TodoBody};
+use crate::request::{CreateTodoRequest, ListTodosRequest, TodoIdRequest, UpdateTodoRequest};
+use crate::resources::{self, ITEM_TEMPLATE, LIST_URI, TodoUri};
+
+/// Todo list MCP Service
+#[derive(Clone)]
+pub struct TodoService {
+    tool_router: ToolRouter<Self>,
+    client: TodoClient,
+}
+
+#[tool_router]
+impl TodoService {
+    pub fn new(client: TodoClient) -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+            client,
+        }
+    }
+
+    #[tool(description = "Create a todo in the team backlog, optionally with a deadline.")]
+    async fn create_todo(
+        &self,
+        Parameters(req): Parameters<CreateTodoRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let body = TodoBody {
+            title: req.title,
+            completed: false,
+            due_at: req.due_at.filter(|due_at| !due_at.is_empty()),
+        };
+        Ok(outcome("Created", self.client.create(&body).await))
+    }
+
+    #[tool(description = "List todos in backlog order. Filter by completion state or by text in the title.")]
+    async fn list_todos(
+        &self,
+        Parameters(req): Parameters<ListTodosRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let list = match self.client.list(req.completed, req.search.as_deref()).await {
+            Ok(list) => list,
+            Err(message) => return Ok(CallToolResult::error(vec![Content::text(message)])),
+        };
+
+        let mut lines = vec![format!("{} todo(s)", list.total)];
+        lines.extend(list.items.iter().map(resources::describe));
+        Ok(CallToolResult::success(vec![Content::text(lines.join("\n"))]))
+    }
+
+    #[tool(description = "Mark a todo as completed.")]
+    async fn complete_todo(
+        &self,
+        Parameters(TodoIdRequest { id }): Parameters<TodoIdRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = match self.client.get(id).await {
+            Ok(todo) => {
+                let body = TodoBody { completed: true, ..todo.into() };
+                self.client.update(id, &body).await
+            }
+            Err(message) => Err(message),
+        };
+        Ok(outcome("Completed", result))
+    }
+
+    #[tool(description = "Change the title, completion state or deadline of a todo. Omitted fields keep their value; an empty due_at removes the deadline.")]
+    async fn update_todo(
+        &self,
+        Parameters(req): Parameters<UpdateTodoRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        // The API replaces the whole item, so start from its current state
+        let result = match self.client.get(req.id).await {
+            Ok(todo) => {
+                let mut body = TodoBody::from(todo);
+                if let Some(title) = req.title {
+                    body.title = title;
+                }
+                if let Some(completed) = req.completed {
+                    body.completed = completed;
+                }
+                if let Some(due_at) = req.due_at {
+                    body.due_at = Some(due_at).filter(|due_at| !due_at.is_empty());
+                }
+                self.client.update(req.id, &body).await
+            }
+            Err(message) => Err(message),
+        };
+        Ok(outcome("Updated", result))
+    }
+
+    #[tool(description = "Delete a todo together with its comments and attachments.")]
+    async fn delete_todo(
+        &self,
+        Parameters(TodoIdRequest { id }): Parameters<TodoIdRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match self.client.delete(id).await {
+            Ok(()) => Ok(CallToolResult::success(vec![Content::text(format!("Deleted todo #{}", id))])),
+            Err(message) => Ok(CallToolResult::error(vec![Content::text(message)])),
+        }
+    }
+}
+
+/// API errors go back to the assistant as tool errors so it can fix the call
+fn outcome(action: &str, result: Result<Todo, String>) -> CallToolResult {
+    match result {
+        Ok(todo) => CallToolResult::success(vec![Content::text(format!(
+            "{} todo:\n{}",
+            action,
+            resources::describe(&todo)
+        ))]),
+        Err(message) => CallToolResult::error(vec![Content::text(message)]),
+    }
+}
+
+fn json_contents<T: Serialize>(uri: &str, value: &T) -> Result<ReadResourceResult, McpError> {
+    let text = serde_json::to_string_pretty(value)
+        .map_err(|e| McpError::internal_error(format!("Failed to serialize todo: {}", e), None))?;
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some("application/json".to_string()),
+            text,
+            meta: None,
+        }],
+    })
+}
+
+#[tool_handler]
+impl ServerHandler for TodoService {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .build(),
+            server_info: Implementation::from_build_env(),
+            instructions: Some(
+                "Manages the team backlog kept by the todolist-sqlite API. Use list_todos to find \
+                todo ids, then create_todo, complete_todo, update_todo or delete_todo. Each todo \
+                is also readable as the todo://todos/{id} resource."
+                    .to_string(),
+            ),
+        }
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let list = self
+            .client
+            .list(None, None)
+            .await
+            .map_err(|message| McpError::internal_error(message, None))?;
+
+        let mut all = RawResource::new(LIST_URI, "todos");
+        all.description = Some("Every todo in backlog order".to_string());
+        all.mime_type = Some("application/json".to_string());
+
+        let mut resources = vec![all.no_annotation()];
+        resources.extend(list.items.iter().map(|todo| {
+            let mut resource = RawResource::new(resources::item_uri(todo.id), format!("todo-{}", todo.id));
+            resource.title = Some(todo.title.clone());
+            resource.mime_type = Some("application/json".to_string());
+            resource.no_annotation()
+        }));
+        Ok(ListResourcesResult::with_all_items(resources))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        let template = RawResourceTemplate {
+            uri_template: ITEM_TEMPLATE.to_string(),
+            name: "todo".to_string(),
+            title: None,
+            description: Some("A single todo by id".to_string()),
+            mime_type: Some("application/json".to_string()),
+        };
+        Ok(ListResourceTemplatesResult::with_all_items(vec![template.no_annotation()]))
+    }
+
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParam { uri }: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        match resources::parse(&uri) {
+            Some(TodoUri::List) => {
+                let list = self
+                    .client
+                    .list(None, None)
+                    .await
+                    .map_err(|message| McpError::internal_error(message, None))?;
+                json_contents(&uri, &list.items)
+            }
+            Some(TodoUri::Item(id)) => {
+                let todo = self
+                    .client
+                    .get(id)
+                    .await
+                    .map_err(|message| McpError::resource_not_found(message, None))?;
+                json_contents(&uri, &todo)
+            }
+            None => Err(McpError::resource_not_found(format!("Unknown resource {}", uri), None)),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use std::{env, path::PathBuf};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use the vendored protoc so building doesn't need protobuf installed
+    let protoc = protoc_bin_vendored::protoc_bin_path()?;
+    // SAFETY: build scripts are single-threaded
+    unsafe { env::set_var("PROTOC", protoc) };
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+    tonic_prost_build::configure()
+        .file_descriptor_set_path(out_dir.join("todo_descriptor.bin"))
+        .compile_protos(&["proto/todo.proto"], &["proto"])?;
+    Ok(())
+}
@@ -1,29 +1,124 @@
+pub mod tls;
+
+use std::net::SocketAddr;
+
 use axum::Router;
+use axum_server::Handle;
+use tokio::{signal, time::Instant};
+
+use crate::{
+    config::{app_info::AppInfo, settings::AppSettings},
+    worker::Workers,
+};
+
+/// Serves `app` over HTTP, or HTTPS with HTTP/2 when `server.tls.enabled`, until
+/// SIGINT/SIGTERM is received. It then stops accepting new connections and waits up to
+/// `server.shutdown_timeout_secs` for in-flight requests to complete.
+pub async fn start_server(
+    app: Router,
+    app_settings: &AppSettings,
+    app_info: &AppInfo,
+    workers: &Workers,
+) {
+    let address: SocketAddr = app_settings
+        .server_address()
+        .parse()
+        .expect("Invalid server address");
+    let service = app.into_make_service_with_connect_info::<SocketAddr>();
+    let handle = Handle::new();
+    let tls = &app_settings.server.tls;
+
+    let mut server = if tls.enabled {
+        let config = tls::load_config(tls).await;
+
+        let (watched, tls_settings) = (config.clone(), tls.clone());
+        workers.spawn("tls-reload", move |shutdown| {
+            tls::watch_certificates(watched, tls_settings, shutdown)
+        });
 
-use crate::config::{app_info::AppInfo, settings::AppSettings};
+        if let Some(http_port) = tls.redirect_http_port {
+            let redirect_address = SocketAddr::new(address.ip(), http_port);
+            let https_port = app_settings.server.port;
+            workers.spawn("https-redirect", move |shutdown| {
+                tls::serve_redirect(redirect_address, https_port, shutdown)
+            });
+        }
 
-pub async fn start_server(app: Router, app_settings: &AppSettings, app_info: &AppInfo) {
-    let address = app_settings.server_address();
+        tokio::spawn(
+            axum_server::bind_rustls(address, config)
+                .handle(handle.clone())
+                .serve(service),
+        )
+    } else {
+        tokio::spawn(
+            axum_server::bind(address)
+                .handle(handle.clone())
+                .serve(service),
+        )
+    };
 
-    let listener: tokio::net::TcpListener =
-        tokio::net::TcpListener::bind(&address)
+    if handle.listening().await.is_some() {
+        print_startup_banner(&app_settings.public_url(), app_info, &app_settings.app.prefix);
+    }
+
+    tokio::select! {
+        result = &mut server => {
+            if let Ok(Err(e)) = result {
+                eprintln!("❌ Server error on {}: {}", address, e);
+            }
+            return;
+        }
+        _ = shutdown_signal() => {}
+    }
+
+    println!("🛑 Shutdown signal received, draining in-flight requests...");
+    let deadline = app_settings.shutdown_timeout();
+    let draining_since = Instant::now();
+    handle.graceful_shutdown(Some(deadline));
+    let _ = server.await;
+
+    if draining_since.elapsed() < deadline {
+        println!("✅ All connections drained");
+    } else {
+        eprintln!(
+            "⚠️  Drain deadline of {}s exceeded, closing remaining connections",
+            app_settings.server.shutdown_timeout_secs
+        );
+    }
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
             .await
-            .unwrap();
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
 
-    axum::serve(listener, app).await.unwrap();
-    
-    print_startup_banner(&address, app_info, &app_settings.app.prefix);
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
 
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 }
 
-fn print_startup_banner(address: &str, app_info: &AppInfo, prefix: &str) {
+fn print_startup_banner(base_url: &str, app_info: &AppInfo, prefix: &str) {
     println!("\n╔═══════════════════════════════════════════════════╗");
     println!("║  🚀 {} v{}", app_info.name, app_info.version);
     println!("║  📝 {}", app_info.description);
     println!("╠═══════════════════════════════════════════════════╣");
-    println!("║  🌐 Server:  http://{}", address);
-    println!("║  📚 Swagger: http://{}{}/swagger-ui", address, prefix);
-    println!("║  📄 OpenAPI: http://{}{}/api-docs/openapi.json", address, prefix);
-    println!("║  🔗 API:     http://{}{}/todo", address, prefix);
+    println!("║  🌐 Server:  {}", base_url);
+    println!("║  📚 Swagger: {}{}/swagger-ui", base_url, prefix);
+    println!("║  📄 OpenAPI: {}{}/api-docs/v2/openapi.json", base_url, prefix);
+    println!("║  🔗 API:     {}{}/v2/todo", base_url, prefix);
     println!("╚═══════════════════════════════════════════════════╝\n");
 }
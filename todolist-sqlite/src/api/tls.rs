@@ -0,0 +1,106 @@
+use std::{
+    net::SocketAddr,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use axum::{
+    Router,
+    extract::Request,
+    http::{StatusCode, header, uri::Authority},
+    response::{IntoResponse, Redirect, Response},
+};
+use axum_server::{Handle, tls_rustls::RustlsConfig};
+
+use crate::{config::settings::TlsConfig, worker::Shutdown};
+
+/// Loads the certificate chain and private key. The server advertises `h2` and
+/// `http/1.1` through ALPN so HTTP/2 is negotiated during the handshake.
+pub async fn load_config(tls: &TlsConfig) -> RustlsConfig {
+    // Both ring and aws-lc-rs may be linked in; pin the process-wide provider to ring
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+        .await
+        .expect("Failed to load TLS certificate and key")
+}
+
+/// Background worker that reloads the certificate whenever the PEM files change on disk,
+/// so renewed certificates are picked up without a restart.
+pub async fn watch_certificates(config: RustlsConfig, tls: TlsConfig, mut shutdown: Shutdown) {
+    let mut ticker = tokio::time::interval(Duration::from_secs(tls.reload_interval_secs));
+    let mut last_modified = modified_at(&tls);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown.requested() => break,
+        }
+
+        let modified = modified_at(&tls);
+        if modified == last_modified {
+            continue;
+        }
+
+        match config.reload_from_pem_file(&tls.cert_path, &tls.key_path).await {
+            Ok(()) => {
+                println!("🔐 TLS certificate reloaded from {}", tls.cert_path);
+                last_modified = modified;
+            }
+            // A renewal may still be writing the files, retry on the next tick
+            Err(e) => eprintln!("⚠️  Failed to reload TLS certificate: {}", e),
+        }
+    }
+}
+
+fn modified_at(tls: &TlsConfig) -> (Option<SystemTime>, Option<SystemTime>) {
+    let modified = |path: &str| Path::new(path).metadata().and_then(|m| m.modified()).ok();
+    (modified(&tls.cert_path), modified(&tls.key_path))
+}
+
+/// Background worker answering plain HTTP on `address` with a permanent redirect to HTTPS.
+pub async fn serve_redirect(address: SocketAddr, https_port: u16, mut shutdown: Shutdown) {
+    let app = Router::new().fallback(move |req: Request| async move {
+        redirect_to_https(req, https_port)
+    });
+
+    let handle = Handle::new();
+    let server = axum_server::bind(address)
+        .handle(handle.clone())
+        .serve(app.into_make_service());
+
+    tokio::select! {
+        result = server => {
+            if let Err(e) = result {
+                eprintln!("⚠️  HTTP redirect listener on {} failed: {}", address, e);
+            }
+        }
+        _ = shutdown.requested() => handle.shutdown(),
+    }
+}
+
+fn redirect_to_https(req: Request, https_port: u16) -> Response {
+    let host = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .and_then(|host| host.parse::<Authority>().ok());
+
+    let Some(host) = host else {
+        return (StatusCode::BAD_REQUEST, "Missing Host header").into_response();
+    };
+
+    let path_and_query = req
+        .uri()
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or("/");
+
+    let location = if https_port == 443 {
+        format!("https://{}{}", host.host(), path_and_query)
+    } else {
+        format!("https://{}:{}{}", host.host(), https_port, path_and_query)
+    };
+
+    Redirect::permanent(&location).into_response()
+}
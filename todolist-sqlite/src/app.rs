@@ -0,0 +1,99 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    config::settings::AppSettings,
+    db,
+    events::EventBus,
+    grpc,
+    service::{
+        attachment_service, backup_service, comment_service, idempotency_service, quick_add_service,
+        smart_list_service, tag_service, time_service, todo_service,
+    },
+    types::{AppState, Db},
+    worker::Workers,
+};
+
+/// Wires the services on top of an already opened database
+pub fn build_state(app_settings: &AppSettings, db: Db) -> AppState {
+    let events = EventBus::new(app_settings.graphql.event_buffer);
+    let attachment_service = Arc::new(attachment_service::AttachmentServiceImpl::new(
+        app_settings.attachments.clone(),
+    ));
+    let comment_service = Arc::new(comment_service::CommentServiceImpl::new(
+        app_settings.comments.clone(),
+    ));
+    let todo_service = Arc::new(todo_service::TodoServiceImpl::new(
+        events.clone(),
+        attachment_service.clone(),
+        comment_service.clone(),
+        app_settings.workflow.clone(),
+    ));
+    todo_service::align_states(&db.lock().unwrap(), &app_settings.workflow);
+    let idempotency_service = Arc::new(idempotency_service::IdempotencyServiceImpl::new(
+        Duration::from_secs(app_settings.idempotency.ttl_secs),
+    ));
+    let backup_service = Arc::new(backup_service::BackupServiceImpl::new(
+        app_settings.backup.clone(),
+    ));
+    let quick_add_service = Arc::new(quick_add_service::QuickAddServiceImpl::new(
+        app_settings.quick_add.clone(),
+    ));
+    let tag_service = Arc::new(tag_service::TagServiceImpl);
+    let smart_list_service = Arc::new(smart_list_service::SmartListServiceImpl);
+    let time_service = Arc::new(time_service::TimeServiceImpl);
+
+    AppState {
+        db,
+        todo_service,
+        idempotency_service,
+        attachment_service,
+        comment_service,
+        backup_service,
+        quick_add_service,
+        tag_service,
+        smart_list_service,
+        time_service,
+        events,
+    }
+}
+
+/// Starts the background workers that run next to the HTTP server
+pub fn spawn_workers(app_settings: &AppSettings, app_state: &AppState) -> Workers {
+    let workers = Workers::new();
+
+    let db = app_state.db.clone();
+    let every = Duration::from_secs(app_settings.database.checkpoint_interval_secs);
+    workers.spawn("wal-checkpoint", move |shutdown| db::run_checkpoints(db, every, shutdown));
+
+    let db = app_state.db.clone();
+    let every = Duration::from_secs(app_settings.database.rebalance_interval_secs);
+    workers.spawn("position-rebalance", move |shutdown| {
+        db::ordering::run_rebalance(db, every, shutdown)
+    });
+
+    let (db, service) = (app_state.db.clone(), app_state.idempotency_service.clone());
+    let every = Duration::from_secs(app_settings.idempotency.purge_interval_secs);
+    workers.spawn("idempotency-purge", move |shutdown| {
+        idempotency_service::run_purge(service, db, every, shutdown)
+    });
+
+    if app_settings.backup.enabled {
+        let (db, service) = (app_state.db.clone(), app_state.backup_service.clone());
+        let every = Duration::from_secs(app_settings.backup.interval_secs);
+        workers.spawn("scheduled-backup", move |shutdown| {
+            backup_service::run_backups(service, db, every, shutdown)
+        });
+    }
+
+    if app_settings.grpc.enabled {
+        let addr = format!("{}:{}", app_settings.server.host, app_settings.grpc.port);
+        let (app_state, reflection) = (app_state.clone(), app_settings.grpc.reflection);
+        workers.spawn("grpc-server", move |shutdown| async move {
+            if let Err(e) = grpc::serve(addr, app_state, reflection, shutdown).await {
+                eprintln!("❌ {}", e);
+            }
+        });
+    }
+
+    workers
+}
@@ -0,0 +1,125 @@
+use axum_tutorial::model::{
+    backup_response::{BackupResponse, RestoreResponse},
+    todo_item_request::TodoItemRequest, todo_item_response::TodoItemResponse,
+    validation_error_response::ValidationErrorResponse,
+};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+
+use crate::config::ClientConfig;
+
+/// Thin REST client for the `/v1/todo` and `/v2/admin` routes
+pub struct TodoClient {
+    http: Client,
+    base_url: String,
+    api_key: Option<String>,
+    admin_token: Option<String>,
+}
+
+impl TodoClient {
+    pub fn new(config: ClientConfig) -> Self {
+        Self {
+            http: Client::new(),
+            base_url: config.url.trim_end_matches('/').to_string(),
+            api_key: config.api_key,
+            admin_token: config.admin_token,
+        }
+    }
+
+    pub async fn list(&self, completed: Option<bool>) -> Result<Vec<TodoItemResponse>, String> {
+        let mut request = self.http.get(self.url("/v1/todo"));
+        if let Some(completed) = completed {
+            request = request.query(&[("completed", completed)]);
+        }
+        self.send(request).await
+    }
+
+    pub async fn get(&self, id: i64) -> Result<TodoItemResponse, String> {
+        self.send(self.http.get(self.url(&format!("/v1/todo/{}", id)))).await
+    }
+
+    pub async fn create(&self, todo: &TodoItemRequest) -> Result<TodoItemResponse, String> {
+        self.send(self.http.post(self.url("/v1/todo")).json(todo)).await
+    }
+
+    pub async fn update(&self, id: i64, todo: &TodoItemRequest) -> Result<TodoItemResponse, String> {
+        self.send(self.http.put(self.url(&format!("/v1/todo/{}", id))).json(todo)).await
+    }
+
+    pub async fn delete(&self, id: i64) -> Result<(), String> {
+        let request = self.http.delete(self.url(&format!("/v1/todo/{}", id)));
+        self.execute(request).await.map(|_| ())
+    }
+
+    pub async fn list_backups(&self) -> Result<Vec<BackupResponse>, String> {
+        self.send(self.admin(self.http.get(self.url("/v2/admin/backups")))?).await
+    }
+
+    pub async fn create_backup(&self) -> Result<BackupResponse, String> {
+        self.send(self.admin(self.http.post(self.url("/v2/admin/backups")))?).await
+    }
+
+    pub async fn restore_backup(&self, name: &str) -> Result<RestoreResponse, String> {
+        let url = self.url(&format!("/v2/admin/backups/{}/restore", name));
+        self.send(self.admin(self.http.post(url))?).await
+    }
+
+    fn admin(&self, request: RequestBuilder) -> Result<RequestBuilder, String> {
+        let token = self
+            .admin_token
+            .as_ref()
+            .ok_or("Set TODO_ADMIN_TOKEN or admin_token in the config file to manage backups")?;
+        Ok(request.bearer_auth(token))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, String> {
+        self.execute(request)
+            .await?
+            .json::<T>()
+            .await
+            .map_err(|e| format!("Unexpected response from server: {}", e))
+    }
+
+    async fn execute(&self, mut request: RequestBuilder) -> Result<Response, String> {
+        if let Some(api_key) = &self.api_key {
+            request = request.header("x-api-key", api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Could not reach {}: {}", self.base_url, e))?;
+
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Err(describe_error(status, &body))
+    }
+}
+
+fn describe_error(status: StatusCode, body: &str) -> String {
+    if status == StatusCode::UNPROCESSABLE_ENTITY
+        && let Ok(validation) = serde_json::from_str::<ValidationErrorResponse>(body)
+    {
+        let fields = validation
+            .errors
+            .iter()
+            .map(|error| format!("  - {}: {}", error.field, error.message))
+            .collect::<Vec<String>>()
+            .join("\n");
+        return format!("{}:\n{}", validation.message, fields);
+    }
+
+    if body.is_empty() {
+        format!("Server responded {}", status)
+    } else {
+        format!("Server responded {}: {}", status, body)
+    }
+}
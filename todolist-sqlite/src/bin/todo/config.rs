@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+const DEFAULT_URL: &str = "http://localhost:8080/api";
+
+/// Connection settings for the todo CLI, read from `~/.config/todo/config.yaml`
+/// (or the file named by `TODO_CONFIG`) and overridden by `TODO_URL` / `TODO_API_KEY` /
+/// `TODO_ADMIN_TOKEN`.
+#[derive(Debug, Deserialize)]
+pub struct ClientConfig {
+    /// Base URL of the API including `app.prefix`, e.g. `http://localhost:8080/api`
+    pub url: String,
+    /// Sent as `x-api-key` so requests are rate limited per identity
+    pub api_key: Option<String>,
+    /// Server's `backup.admin_token`, needed by the `backup` commands
+    pub admin_token: Option<String>,
+}
+
+impl ClientConfig {
+    pub fn load() -> Result<Self, String> {
+        let mut builder = config::Config::builder().set_default("url", DEFAULT_URL).unwrap();
+
+        if let Some(path) = config_file() {
+            builder = builder.add_source(config::File::from(path).required(false));
+        }
+
+        builder
+            .add_source(config::Environment::with_prefix("TODO"))
+            .build()
+            .and_then(|config| config.try_deserialize())
+            .map_err(|e| format!("Invalid configuration: {}", e))
+    }
+}
+
+fn config_file() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("TODO_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| std::env::var("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .ok()?;
+    Some(config_home.join("todo").join("config.yaml"))
+}
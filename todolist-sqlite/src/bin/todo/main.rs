@@ -0,0 +1,192 @@
+mod client;
+mod config;
+mod output;
+
+use std::{path::PathBuf, process::ExitCode};
+
+use axum_tutorial::model::{
+    todo_item_request::TodoItemRequest, todo_item_response::TodoItemResponse,
+};
+use clap::{Parser, Subcommand};
+
+use crate::{
+    client::TodoClient,
+    config::ClientConfig,
+    output::{ExportFormat, OutputFormat},
+};
+
+/// Command-line client for the todo API
+#[derive(Parser)]
+#[command(name = "todo", version)]
+struct Cli {
+    /// Output format
+    #[arg(short, long, value_enum, global = true, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+
+    /// API base URL, overrides the config file and TODO_URL
+    #[arg(long, global = true)]
+    url: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a todo
+    Add {
+        /// Title of the todo, words are joined with spaces
+        #[arg(required = true)]
+        title: Vec<String>,
+    },
+    /// List todos
+    List {
+        /// Only show todos that are not completed
+        #[arg(long, conflicts_with = "done")]
+        open: bool,
+        /// Only show completed todos
+        #[arg(long)]
+        done: bool,
+    },
+    /// Mark a todo as completed
+    Done { id: i64 },
+    /// Change the title or completion state of a todo
+    Edit {
+        id: i64,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        completed: Option<bool>,
+    },
+    /// Delete a todo
+    Rm { id: i64 },
+    /// Export every todo to stdout or a file
+    Export {
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Snapshot or restore the server's database (needs the admin token)
+    #[command(subcommand)]
+    Backup(BackupCommand),
+}
+
+#[derive(Subcommand)]
+enum BackupCommand {
+    /// Take a snapshot of the running database
+    Create,
+    /// List snapshots, newest first
+    List,
+    /// Replace the database with a snapshot, after snapshotting the current one
+    Restore {
+        /// Snapshot name, as shown by `todo backup list`
+        name: String,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), String> {
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let mut config = ClientConfig::load()?;
+    if let Some(url) = cli.url {
+        config.url = url;
+    }
+    let client = TodoClient::new(config);
+
+    match cli.command {
+        Command::Add { title } => {
+            let todo = TodoItemRequest {
+                id: None,
+                title: title.join(" "),
+                completed: false,
+                due_at: None,
+            };
+            output::print_todo(&client.create(&todo).await?, cli.output);
+        }
+        Command::List { open, done } => {
+            let completed = match (open, done) {
+                (true, _) => Some(false),
+                (_, true) => Some(true),
+                _ => None,
+            };
+            output::print_todos(&client.list(completed).await?, cli.output);
+        }
+        Command::Done { id } => {
+            let updated = edit(&client, id, None, Some(true)).await?;
+            output::print_todo(&updated, cli.output);
+        }
+        Command::Edit { id, title, completed } => {
+            if title.is_none() && completed.is_none() {
+                return Err("Nothing to change, pass --title and/or --completed".to_string());
+            }
+            let updated = edit(&client, id, title, completed).await?;
+            output::print_todo(&updated, cli.output);
+        }
+        Command::Rm { id } => {
+            client.delete(id).await?;
+            if cli.output == OutputFormat::Table {
+                println!("Deleted todo {}", id);
+            }
+        }
+        Command::Export { format, file } => {
+            let exported = output::export(&client.list(None).await?, format);
+            match file {
+                Some(path) => std::fs::write(&path, exported)
+                    .map_err(|e| format!("Could not write {}: {}", path.display(), e))?,
+                None => print!("{}", exported),
+            }
+        }
+        Command::Backup(BackupCommand::Create) => {
+            output::print_backup(&client.create_backup().await?, cli.output);
+        }
+        Command::Backup(BackupCommand::List) => {
+            output::print_backups(&client.list_backups().await?, cli.output);
+        }
+        Command::Backup(BackupCommand::Restore { name }) => {
+            let restored = client.restore_backup(&name).await?;
+            match cli.output {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&restored).unwrap())
+                }
+                OutputFormat::Table => println!(
+                    "Restored {} (schema version {}); the previous database was saved as {}",
+                    restored.restored.name, restored.schema_version, restored.previous.name
+                ),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The API replaces the whole item on update, so unchanged fields are read first
+async fn edit(
+    client: &TodoClient,
+    id: i64,
+    title: Option<String>,
+    completed: Option<bool>,
+) -> Result<TodoItemResponse, String> {
+    let current = client.get(id).await?;
+    let todo = TodoItemRequest {
+        id: Some(id),
+        title: title.unwrap_or(current.title),
+        completed: completed.unwrap_or(current.completed),
+        due_at: Some(current.due_at),
+    };
+    client.update(id, &todo).await
+}
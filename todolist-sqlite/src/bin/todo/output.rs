@@ -0,0 +1,105 @@
+use axum_tutorial::model::{backup_response::BackupResponse, todo_item_response::TodoItemResponse};
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+pub fn print_todos(todos: &[TodoItemResponse], format: OutputFormat) {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(todos).unwrap()),
+        OutputFormat::Table => print!("{}", table(todos)),
+    }
+}
+
+pub fn print_todo(todo: &TodoItemResponse, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(todo).unwrap()),
+        OutputFormat::Table => print!("{}", table(std::slice::from_ref(todo))),
+    }
+}
+
+fn table(todos: &[TodoItemResponse]) -> String {
+    if todos.is_empty() {
+        return "No todos found\n".to_string();
+    }
+
+    let ids = todos
+        .iter()
+        .map(|todo| todo.id.map(|id| id.to_string()).unwrap_or_default())
+        .collect::<Vec<String>>();
+    let id_width = ids.iter().map(String::len).max().unwrap_or(0).max("ID".len());
+
+    let mut out = format!("{:>id_width$}  {:<4}  {}\n", "ID", "DONE", "TITLE");
+    for (id, todo) in ids.iter().zip(todos) {
+        let done = if todo.completed { "[x]" } else { "[ ]" };
+        out.push_str(&format!("{:>id_width$}  {:<4}  {}\n", id, done, todo.title));
+    }
+    out
+}
+
+pub fn print_backups(backups: &[BackupResponse], format: OutputFormat) {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(backups).unwrap()),
+        OutputFormat::Table => print!("{}", backup_table(backups)),
+    }
+}
+
+pub fn print_backup(backup: &BackupResponse, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(backup).unwrap()),
+        OutputFormat::Table => print!("{}", backup_table(std::slice::from_ref(backup))),
+    }
+}
+
+fn backup_table(backups: &[BackupResponse]) -> String {
+    if backups.is_empty() {
+        return "No backups found\n".to_string();
+    }
+
+    let name_width = backups.iter().map(|backup| backup.name.len()).max().unwrap_or(0);
+    let mut out = format!("{:<name_width$}  {:>10}  {}\n", "NAME", "SIZE", "CREATED");
+    for backup in backups {
+        out.push_str(&format!(
+            "{:<name_width$}  {:>10}  {}\n",
+            backup.name,
+            backup.size_bytes,
+            backup.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+        ));
+    }
+    out
+}
+
+pub fn export(todos: &[TodoItemResponse], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(todos).unwrap() + "\n",
+        ExportFormat::Csv => {
+            let mut out = String::from("id,title,completed\n");
+            for todo in todos {
+                out.push_str(&format!(
+                    "{},{},{}\n",
+                    todo.id.map(|id| id.to_string()).unwrap_or_default(),
+                    csv_field(&todo.title),
+                    todo.completed
+                ));
+            }
+            out
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,39 @@
+use crate::config::settings::{ApiVersionConfig, AppSettings};
+
+/// Versions of the REST API, each served under `{app.prefix}/{name}` with its own
+/// OpenAPI document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    /// Every version, oldest first
+    pub const ALL: [ApiVersion; 2] = [ApiVersion::V1, ApiVersion::V2];
+
+    /// Also served at the unversioned paths, for clients that predate versioning
+    pub const LEGACY: ApiVersion = ApiVersion::V1;
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+            ApiVersion::V2 => "v2",
+        }
+    }
+
+    /// The version clients of this one should move to
+    pub fn successor(self) -> Option<ApiVersion> {
+        match self {
+            ApiVersion::V1 => Some(ApiVersion::V2),
+            ApiVersion::V2 => None,
+        }
+    }
+
+    pub fn config(self, app_settings: &AppSettings) -> &ApiVersionConfig {
+        match self {
+            ApiVersion::V1 => &app_settings.app.versions.v1,
+            ApiVersion::V2 => &app_settings.app.versions.v2,
+        }
+    }
+}
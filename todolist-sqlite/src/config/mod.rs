@@ -1,3 +1,4 @@
 pub mod settings;
 pub mod open_api;
-pub mod app_info;
\ No newline at end of file
+pub mod app_info;
+pub mod api_version;
\ No newline at end of file
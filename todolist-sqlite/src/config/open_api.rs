@@ -1,28 +1,107 @@
+use utoipa::{OpenApi, openapi::Deprecated};
 
-use utoipa::{OpenApi};
-
-use crate::config::{app_info::AppInfo, settings::AppSettings};
+use crate::config::{api_version::ApiVersion, app_info::AppInfo, settings::AppSettings};
 
 pub const TAG_TODO: &str = "Todo";
 pub const TAG_TODO_DESC: &str = "Todo management endpoints";
+pub const TAG_ATTACHMENT: &str = "Attachment";
+pub const TAG_ATTACHMENT_DESC: &str = "Files attached to todo items";
+pub const TAG_COMMENT: &str = "Comment";
+pub const TAG_COMMENT_DESC: &str = "Comments and activity on todo items";
+pub const TAG_STATS: &str = "Stats";
+pub const TAG_STATS_DESC: &str = "Productivity reports over the todo list";
+pub const TAG_SYNC: &str = "Sync";
+pub const TAG_SYNC_DESC: &str = "Delta sync for offline-first clients";
+pub const TAG_TAG: &str = "Tag";
+pub const TAG_TAG_DESC: &str = "Tags on todo items, with renames and merges";
+pub const TAG_QUICK_ADD: &str = "Quick add";
+pub const TAG_QUICK_ADD_DESC: &str = "Todo items read from a line of text";
+pub const TAG_WORKFLOW: &str = "Workflow";
+pub const TAG_WORKFLOW_DESC: &str = "Workflow states, transitions and the board";
+pub const TAG_SMART_LIST: &str = "Smart list";
+pub const TAG_SMART_LIST_DESC: &str = "Saved search queries over the todo list";
+pub const TAG_DEPENDENCY: &str = "Dependencies";
+pub const TAG_DEPENDENCY_DESC: &str = "Todos blocked by other todos, and a plan in dependency order";
+pub const TAG_TIME: &str = "Time tracking";
+pub const TAG_TIME_DESC: &str = "Per-user timers, manual time entries and time reports";
+pub const TAG_ADMIN: &str = "Admin";
+pub const TAG_ADMIN_DESC: &str = "Database backups, behind the admin token";
 
 #[derive(OpenApi)]
 #[openapi(
     tags(
-        (name = TAG_TODO, description = TAG_TODO_DESC)
+        (name = TAG_TODO, description = TAG_TODO_DESC),
+        (name = TAG_ATTACHMENT, description = TAG_ATTACHMENT_DESC),
+        (name = TAG_COMMENT, description = TAG_COMMENT_DESC),
+        (name = TAG_STATS, description = TAG_STATS_DESC),
+        (name = TAG_SYNC, description = TAG_SYNC_DESC),
+        (name = TAG_TAG, description = TAG_TAG_DESC),
+        (name = TAG_QUICK_ADD, description = TAG_QUICK_ADD_DESC),
+        (name = TAG_WORKFLOW, description = TAG_WORKFLOW_DESC),
+        (name = TAG_SMART_LIST, description = TAG_SMART_LIST_DESC),
+        (name = TAG_DEPENDENCY, description = TAG_DEPENDENCY_DESC),
+        (name = TAG_TIME, description = TAG_TIME_DESC),
+        (name = TAG_ADMIN, description = TAG_ADMIN_DESC)
     )
 )]
 pub struct ApiDoc;
 
-pub fn configure_openapi(app_info: &AppInfo, app_settings: &AppSettings) ->  utoipa::openapi::OpenApi {
+pub fn configure_openapi(
+    app_info: &AppInfo,
+    app_settings: &AppSettings,
+    version: ApiVersion,
+) -> utoipa::openapi::OpenApi {
     let mut doc = ApiDoc::openapi();
 
     let info = app_info.clone();
-    doc.info.title = info.name;
+    doc.info.title = format!("{} {}", info.name, version.name());
     doc.info.version = info.version;
     doc.info.description = Some(info.description);
     doc.servers = Some(vec![utoipa::openapi::ServerBuilder::new()
-        .url(format!("http://localhost:{}{}", app_settings.server.port, app_settings.app.prefix))
+        .url(format!(
+            "{}{}/{}",
+            app_settings.public_url(),
+            app_settings.app.prefix,
+            version.name()
+        ))
         .build()]);
     doc
-}
\ No newline at end of file
+}
+
+/// Flags every operation of a deprecated version, and says until when it is served
+pub fn mark_deprecated(doc: &mut utoipa::openapi::OpenApi, app_settings: &AppSettings, version: ApiVersion) {
+    let config = version.config(app_settings);
+    let Some(deprecated_at) = config.deprecated_at else {
+        return;
+    };
+
+    let mut notice = format!("Deprecated since {}", deprecated_at.date_naive());
+    if let Some(sunset_at) = config.sunset_at {
+        notice.push_str(&format!(", removed on {}", sunset_at.date_naive()));
+    }
+    if let Some(successor) = version.successor() {
+        notice.push_str(&format!(". Use {} instead", successor.name()));
+    }
+    doc.info.description = Some(match doc.info.description.take() {
+        Some(description) => format!("{}\n\n{}.", description, notice),
+        None => format!("{}.", notice),
+    });
+
+    for path_item in doc.paths.paths.values_mut() {
+        for operation in [
+            &mut path_item.get,
+            &mut path_item.put,
+            &mut path_item.post,
+            &mut path_item.delete,
+            &mut path_item.options,
+            &mut path_item.head,
+            &mut path_item.patch,
+            &mut path_item.trace,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            operation.deprecated = Some(Deprecated::True);
+        }
+    }
+}
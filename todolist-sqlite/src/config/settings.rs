@@ -1,35 +1,536 @@
 use core::str;
+use std::{str::FromStr, time::Duration};
 
+use axum::http::{HeaderName, HeaderValue, Method, Uri};
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
 use serde::Deserialize;
 
+use crate::config::api_version::ApiVersion;
+
+#[derive(Debug, Deserialize)]
+struct RawTlsConfig {
+    enabled: bool,
+    cert_path: String,
+    key_path: String,
+    reload_interval_secs: u64,
+    redirect_http_port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawTlsConfig")]
+pub struct TlsConfig {
+    pub enabled: bool,
+    pub cert_path: String,
+    pub key_path: String,
+    pub reload_interval_secs: u64,
+    pub redirect_http_port: Option<u16>,
+}
+
+impl TryFrom<RawTlsConfig> for TlsConfig {
+    type Error = String;
+
+    fn try_from(raw: RawTlsConfig) -> Result<Self, String> {
+        // Certificates are only watched while TLS is enabled
+        let reload_interval_secs = if raw.enabled {
+            interval_secs("server.tls.reload_interval_secs", raw.reload_interval_secs)?
+        } else {
+            raw.reload_interval_secs
+        };
+        Ok(TlsConfig {
+            enabled: raw.enabled,
+            cert_path: raw.cert_path,
+            key_path: raw.key_path,
+            reload_interval_secs,
+            redirect_http_port: raw.redirect_http_port,
+        })
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    pub public_host: String,
+    pub shutdown_timeout_secs: u64,
+    pub tls: TlsConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiVersionConfig {
+    /// Set once the version is deprecated, which makes it send `Deprecation` headers
+    pub deprecated_at: Option<DateTime<Utc>>,
+    /// When the version will be removed, sent as the `Sunset` header
+    pub sunset_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiVersionsConfig {
+    pub v1: ApiVersionConfig,
+    pub v2: ApiVersionConfig,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct AppConfig {
     pub prefix: String,
+    pub versions: ApiVersionsConfig,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct OpenApiConfig {
     pub ui_path: String,
+    /// Path of each version's document, with `{version}` replaced by the version name
     pub json_path: String,
 }
 
+impl OpenApiConfig {
+    pub fn json_path_for(&self, version: ApiVersion) -> String {
+        self.json_path.replace("{version}", version.name())
+    }
+}
+
+/// Checks the period of a background worker, which can't tick every 0 seconds
+fn interval_secs(name: &str, secs: u64) -> Result<u64, String> {
+    if secs == 0 {
+        return Err(format!("{} must be at least 1", name));
+    }
+    Ok(secs)
+}
+
+#[derive(Debug, Deserialize)]
+struct RawDatabaseConfig {
+    path: String,
+    checkpoint_interval_secs: u64,
+    rebalance_interval_secs: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "RawDatabaseConfig")]
+pub struct DatabaseConfig {
+    pub path: String,
+    pub checkpoint_interval_secs: u64,
+    /// How often crowded todo positions are spread out again
+    pub rebalance_interval_secs: u64,
+}
+
+impl TryFrom<RawDatabaseConfig> for DatabaseConfig {
+    type Error = String;
+
+    fn try_from(raw: RawDatabaseConfig) -> Result<Self, String> {
+        let checkpoint = interval_secs("database.checkpoint_interval_secs", raw.checkpoint_interval_secs)?;
+        let rebalance = interval_secs("database.rebalance_interval_secs", raw.rebalance_interval_secs)?;
+        Ok(DatabaseConfig {
+            path: raw.path,
+            checkpoint_interval_secs: checkpoint,
+            rebalance_interval_secs: rebalance,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRateLimitConfig {
+    enabled: bool,
+    burst: u64,
+    refill_per_sec: f64,
+    identity_header: String,
+    #[serde(default)]
+    api_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawRateLimitConfig")]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub burst: u64,
+    pub refill_per_sec: f64,
+    pub identity_header: String,
+    /// Identities that get a bucket of their own; clients sending any other value are
+    /// limited per IP, so made-up keys can't dodge the limit
+    pub api_keys: Vec<String>,
+}
+
+impl TryFrom<RawRateLimitConfig> for RateLimitConfig {
+    type Error = String;
+
+    fn try_from(raw: RawRateLimitConfig) -> Result<Self, String> {
+        if raw.burst == 0 {
+            return Err("rate_limit.burst must be at least 1".to_string());
+        }
+        if !(raw.refill_per_sec.is_finite() && raw.refill_per_sec > 0.0) {
+            return Err(format!(
+                "rate_limit.refill_per_sec must be above 0, not {}",
+                raw.refill_per_sec
+            ));
+        }
+        Ok(RateLimitConfig {
+            enabled: raw.enabled,
+            burst: raw.burst,
+            refill_per_sec: raw.refill_per_sec,
+            identity_header: raw.identity_header,
+            api_keys: raw.api_keys,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LimitsConfig {
+    pub body_max_bytes: usize,
+    pub max_in_flight: usize,
+    pub rate_limit: RateLimitConfig,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCorsConfig {
+    enabled: bool,
+    allowed_origins: Vec<String>,
+    allowed_methods: Vec<String>,
+    allowed_headers: Vec<String>,
+    allow_credentials: bool,
+}
+
+/// CORS rules, parsed when the config is loaded so building the layer can't fail
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "RawCorsConfig")]
+pub struct CorsConfig {
+    pub enabled: bool,
+    /// `None` allows any origin, written as `"*"`
+    pub allowed_origins: Option<Vec<HeaderValue>>,
+    pub allowed_methods: Vec<Method>,
+    pub allowed_headers: Vec<HeaderName>,
+    pub allow_credentials: bool,
+}
+
+impl TryFrom<RawCorsConfig> for CorsConfig {
+    type Error = String;
+
+    fn try_from(raw: RawCorsConfig) -> Result<Self, String> {
+        // Browsers refuse credentials with wildcards, and so does the CORS layer
+        if raw.allow_credentials {
+            for (name, values) in [
+                ("allowed_origins", &raw.allowed_origins),
+                ("allowed_methods", &raw.allowed_methods),
+                ("allowed_headers", &raw.allowed_headers),
+            ] {
+                if values.iter().any(|value| value == "*") {
+                    return Err(format!(
+                        "middleware.cors.allow_credentials needs {} listed, not \"*\"",
+                        name
+                    ));
+                }
+            }
+        }
+
+        let allowed_origins = if raw.allowed_origins.iter().any(|origin| origin == "*") {
+            None
+        } else {
+            Some(parse_all("allowed_origins", &raw.allowed_origins, parse_origin)?)
+        };
+        let allowed_methods = parse_all("allowed_methods", &raw.allowed_methods, Method::from_str)?;
+        let allowed_headers = parse_all("allowed_headers", &raw.allowed_headers, HeaderName::from_str)?;
+        Ok(CorsConfig {
+            enabled: raw.enabled,
+            allowed_origins,
+            allowed_methods,
+            allowed_headers,
+            allow_credentials: raw.allow_credentials,
+        })
+    }
+}
+
+fn parse_all<T, E>(
+    name: &str,
+    values: &[String],
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<Vec<T>, String> {
+    values
+        .iter()
+        .map(|value| {
+            parse(value).map_err(|_| format!("middleware.cors.{} has an invalid entry {:?}", name, value))
+        })
+        .collect()
+}
+
+/// Origins are a scheme and an authority, exactly as browsers send them
+fn parse_origin(origin: &str) -> Result<HeaderValue, ()> {
+    let uri = origin.parse::<Uri>().map_err(|_| ())?;
+    match (uri.scheme(), uri.authority()) {
+        (Some(scheme), Some(authority)) if format!("{}://{}", scheme, authority) == origin => {
+            HeaderValue::from_str(origin).map_err(|_| ())
+        }
+        _ => Err(()),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompressionConfig {
+    pub enabled: bool,
+    pub gzip: bool,
+    pub brotli: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SecurityHeadersConfig {
+    pub enabled: bool,
+    pub hsts_max_age_secs: u64,
+    pub content_security_policy: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MiddlewareConfig {
+    pub cors: CorsConfig,
+    pub compression: CompressionConfig,
+    pub security_headers: SecurityHeadersConfig,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawIdempotencyConfig {
+    ttl_secs: u64,
+    purge_interval_secs: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "RawIdempotencyConfig")]
+pub struct IdempotencyConfig {
+    pub ttl_secs: u64,
+    pub purge_interval_secs: u64,
+}
+
+impl TryFrom<RawIdempotencyConfig> for IdempotencyConfig {
+    type Error = String;
+
+    fn try_from(raw: RawIdempotencyConfig) -> Result<Self, String> {
+        let purge = interval_secs("idempotency.purge_interval_secs", raw.purge_interval_secs)?;
+        Ok(IdempotencyConfig {
+            ttl_secs: raw.ttl_secs,
+            purge_interval_secs: purge,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AttachmentsConfig {
+    /// Directory holding the uploaded files, named by the SHA-256 of their content
+    pub dir: String,
+    pub max_file_bytes: u64,
+    /// Body limit for upload requests, which may carry several files
+    pub max_request_bytes: usize,
+    /// Accepted MIME types; `type/*` accepts every subtype
+    pub allowed_mime_types: Vec<String>,
+}
+
+impl AttachmentsConfig {
+    pub fn allows(&self, content_type: &str) -> bool {
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        self.allowed_mime_types.iter().any(|allowed| match allowed.strip_suffix("/*") {
+            Some(top_level) => essence
+                .split_once('/')
+                .is_some_and(|(kind, _)| kind.eq_ignore_ascii_case(top_level)),
+            None => allowed.eq_ignore_ascii_case(essence),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommentsConfig {
+    /// Header carrying the username of whoever writes a comment; only they may edit or delete it
+    pub author_header: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawBackupConfig {
+    enabled: bool,
+    dir: String,
+    interval_secs: u64,
+    keep: usize,
+    admin_token: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawBackupConfig")]
+pub struct BackupConfig {
+    /// Take a snapshot every `interval_secs` while the server runs
+    pub enabled: bool,
+    /// Directory holding the snapshots, named after the time they were taken
+    pub dir: String,
+    pub interval_secs: u64,
+    /// Snapshots kept when a new one is taken, oldest deleted first; 0 keeps them all
+    pub keep: usize,
+    /// Bearer token required by the admin endpoints; they are disabled while it is empty
+    pub admin_token: String,
+}
+
+impl TryFrom<RawBackupConfig> for BackupConfig {
+    type Error = String;
+
+    fn try_from(raw: RawBackupConfig) -> Result<Self, String> {
+        // Only scheduled while backups are enabled
+        let interval_secs = if raw.enabled {
+            interval_secs("backup.interval_secs", raw.interval_secs)?
+        } else {
+            raw.interval_secs
+        };
+        Ok(BackupConfig {
+            enabled: raw.enabled,
+            dir: raw.dir,
+            interval_secs,
+            keep: raw.keep,
+            admin_token: raw.admin_token,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GraphqlConfig {
+    pub enabled: bool,
+    pub path: String,
+    pub ws_path: String,
+    pub playground_path: String,
+    /// Changes buffered per subscriber before slow subscribers start missing events
+    pub event_buffer: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GrpcConfig {
+    pub enabled: bool,
+    /// Served on its own port, next to the HTTP server
+    pub port: u16,
+    /// Expose the gRPC reflection service so tools like grpcurl can discover the API
+    pub reflection: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebConfig {
+    /// Serve the HTML interface at `/`, next to the API
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuickAddConfig {
+    /// IANA timezone quick-add dates are read in unless the request names one
+    pub timezone: Tz,
+    /// Time of day of due dates written without a time
+    pub default_time: NaiveTime,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowStateConfig {
+    /// Identifier used by the API, e.g. `in_review`
+    pub name: String,
+    /// Column heading on the board
+    pub label: String,
+    /// Items in this state count as completed
+    #[serde(default)]
+    pub completed: bool,
+    /// States items may move to from this one
+    #[serde(default)]
+    pub to: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawWorkflowConfig {
+    states: Vec<WorkflowStateConfig>,
+}
+
+/// Workflow states, in board order. New open items start in the first open state, and items
+/// completed or reopened through `completed` move to the first state matching it that their
+/// state may move to.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawWorkflowConfig")]
+pub struct WorkflowConfig {
+    pub states: Vec<WorkflowStateConfig>,
+}
+
+impl TryFrom<RawWorkflowConfig> for WorkflowConfig {
+    type Error = String;
+
+    fn try_from(raw: RawWorkflowConfig) -> Result<Self, String> {
+        let workflow = WorkflowConfig { states: raw.states };
+        for (index, state) in workflow.states.iter().enumerate() {
+            if state.name.is_empty() || workflow.states[..index].iter().any(|other| other.name == state.name) {
+                return Err(format!("workflow state names must be unique, not empty: {:?}", state.name));
+            }
+            if let Some(to) = state.to.iter().find(|to| workflow.state(to).is_none()) {
+                return Err(format!("workflow state {} moves to unknown state {}", state.name, to));
+            }
+        }
+        for completed in [false, true] {
+            if !workflow.states.iter().any(|state| state.completed == completed) {
+                return Err(format!("workflow needs a state with completed: {}", completed));
+            }
+        }
+        Ok(workflow)
+    }
+}
+
+impl WorkflowConfig {
+    pub fn state(&self, name: &str) -> Option<&WorkflowStateConfig> {
+        self.states.iter().find(|state| state.name == name)
+    }
+
+    /// First state that is completed or not, where `completed` writes move items
+    pub fn default_state(&self, completed: bool) -> &str {
+        self.states
+            .iter()
+            .find(|state| state.completed == completed)
+            .map(|state| state.name.as_str())
+            .unwrap_or_default()
+    }
+
+    /// The state a `completed` write moves an item in `state` to: `state` itself when it
+    /// agrees, otherwise the first state of that kind it may move to. `None` when it may
+    /// move to none; items in a state that isn't configured go to the default.
+    pub fn follow<'a>(&'a self, state: &'a str, completed: bool) -> Option<&'a str> {
+        let Some(current) = self.state(state) else {
+            return Some(self.default_state(completed));
+        };
+        if current.completed == completed {
+            return Some(state);
+        }
+        self.states
+            .iter()
+            .find(|next| next.completed == completed && current.to.contains(&next.name))
+            .map(|next| next.name.as_str())
+    }
+
+    /// The state `completed` agrees with: `state` itself, or the default when it isn't
+    /// configured or is on the other side of `completed`
+    pub fn align<'a>(&'a self, state: &'a str, completed: bool) -> &'a str {
+        match self.state(state) {
+            Some(configured) if configured.completed == completed => state,
+            _ => self.default_state(completed),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AppSettings {
     pub server: ServerConfig,
     pub app: AppConfig,
     pub openapi: OpenApiConfig,
+    pub database: DatabaseConfig,
+    pub limits: LimitsConfig,
+    pub middleware: MiddlewareConfig,
+    pub idempotency: IdempotencyConfig,
+    pub attachments: AttachmentsConfig,
+    pub comments: CommentsConfig,
+    pub backup: BackupConfig,
+    pub graphql: GraphqlConfig,
+    pub grpc: GrpcConfig,
+    pub web: WebConfig,
+    pub quick_add: QuickAddConfig,
+    pub workflow: WorkflowConfig,
 }
 
 impl AppSettings {
     pub fn new() -> Result<Self, config::ConfigError> {
         config::Config::builder()
             .add_source(config::File::with_name("src/properties/application.yaml"))
+            // e.g. APP_BACKUP__ADMIN_TOKEN, for secrets that don't belong in the file
+            .add_source(
+                config::Environment::with_prefix("APP")
+                    .prefix_separator("_")
+                    .separator("__"),
+            )
             .build()?
             .try_deserialize()
     }
@@ -41,4 +542,19 @@ impl AppSettings {
             self.server.port
         )
     }
+
+    /// Externally reachable base URL, e.g. `https://localhost:8443`
+    pub fn public_url(&self) -> String {
+        let scheme = if self.server.tls.enabled { "https" } else { "http" };
+        format!(
+            "{}://{}:{}",
+            scheme,
+            self.server.public_host,
+            self.server.port
+        )
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.server.shutdown_timeout_secs)
+    }
 }
\ No newline at end of file
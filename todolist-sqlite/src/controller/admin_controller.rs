@@ -0,0 +1,142 @@
+use crate::{
+    config::open_api::TAG_ADMIN,
+    model::backup_response::{BackupResponse, RestoreResponse},
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::{FromRequestParts, Path, State},
+    http::{StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+pub struct AdminController;
+impl AdminController {
+    /// Routes relative to the version router
+    pub fn router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new()
+            .routes(routes!(list_backups, create_backup))
+            .routes(routes!(restore_backup))
+    }
+}
+
+/// Proof that the caller sent `Authorization: Bearer <backup.admin_token>`
+pub struct Admin;
+
+impl FromRequestParts<AppState> for Admin {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Response> {
+        let expected = &state.backup_service.config().admin_token;
+        if expected.is_empty() {
+            return Err((
+                StatusCode::FORBIDDEN,
+                "Admin endpoints are disabled until backup.admin_token is set",
+            )
+                .into_response());
+        }
+
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        match token {
+            Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => Ok(Admin),
+            _ => Err((StatusCode::UNAUTHORIZED, "Missing or invalid admin token").into_response()),
+        }
+    }
+}
+
+/// Compares without stopping at the first difference, so timing doesn't reveal the token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[utoipa::path(
+        get,
+        path = "/admin/backups",
+        tag = TAG_ADMIN,
+        responses(
+            (status = 200, description = "Snapshots of the database, newest first", body = [BackupResponse]),
+            (status = 401, description = "The admin token is missing or wrong"),
+            (status = 403, description = "Admin endpoints are disabled"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("Authorization" = String, Header, description = "`Bearer` followed by `backup.admin_token`")
+        )
+    )]
+pub async fn list_backups(State(app_state): State<AppState>, _: Admin) -> Response {
+    app_state
+        .backup_service
+        .list()
+        .await
+        .map(|backups| (StatusCode::OK, Json(backups)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        post,
+        path = "/admin/backups",
+        tag = TAG_ADMIN,
+        responses(
+            (status = 201, description = "Consistent snapshot of the running database; the oldest beyond `backup.keep` are deleted", body = BackupResponse),
+            (status = 401, description = "The admin token is missing or wrong"),
+            (status = 403, description = "Admin endpoints are disabled"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("Authorization" = String, Header, description = "`Bearer` followed by `backup.admin_token`")
+        )
+    )]
+pub async fn create_backup(State(app_state): State<AppState>, _: Admin) -> Response {
+    app_state
+        .backup_service
+        .create(&app_state.db)
+        .await
+        .map(|backup| (StatusCode::CREATED, Json(backup)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        post,
+        path = "/admin/backups/{name}/restore",
+        tag = TAG_ADMIN,
+        responses(
+            (status = 200, description = "The snapshot replaced the live database, which was snapshotted first", body = RestoreResponse),
+            (status = 401, description = "The admin token is missing or wrong"),
+            (status = 403, description = "Admin endpoints are disabled"),
+            (status = 404, description = "Snapshot not found"),
+            (status = 422, description = "The snapshot is corrupt or has a schema version this server doesn't support"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("name" = String, Path, description = "Name of the snapshot to restore"),
+            ("Authorization" = String, Header, description = "`Bearer` followed by `backup.admin_token`")
+        )
+    )]
+pub async fn restore_backup(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+    _: Admin,
+) -> Response {
+    let backups = &app_state.backup_service;
+    if backups.find(&name).await.is_none() {
+        return (StatusCode::NOT_FOUND, format!("Snapshot {} not found", name)).into_response();
+    }
+    if let Err(e) = backups.validate(&name).await {
+        return (StatusCode::UNPROCESSABLE_ENTITY, e).into_response();
+    }
+
+    let restored = match backups.restore(&app_state.db, &name).await {
+        Ok(restored) => restored,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    };
+    // The snapshot may predate the workflow states configured now
+    if let Err(e) = app_state.todo_service.align_states(&app_state.db).await {
+        return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response();
+    }
+    (StatusCode::OK, Json(restored)).into_response()
+}
@@ -0,0 +1,283 @@
+use crate::{
+    config::{open_api::TAG_ATTACHMENT, settings::AttachmentsConfig},
+    model::{attachment_response::AttachmentResponse, attachment_upload::AttachmentUpload},
+    service::attachment_service::StagedUpload,
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::{DefaultBodyLimit, Multipart, Path, Request, State, multipart::Field},
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use tower::ServiceExt;
+use tower_http::services::ServeFile;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+pub struct AttachmentController;
+impl AttachmentController {
+    /// Routes relative to the todo router, with uploads allowed past the global body limit
+    pub fn router(config: &AttachmentsConfig) -> OpenApiRouter<AppState> {
+        OpenApiRouter::new()
+            .routes(routes!(upload_attachments, list_attachments))
+            .routes(routes!(download_attachment, delete_attachment))
+            .layer(DefaultBodyLimit::max(config.max_request_bytes))
+    }
+}
+
+#[utoipa::path(
+        post,
+        path = "/{id}/attachments",
+        tag = TAG_ATTACHMENT,
+        request_body(content = AttachmentUpload, content_type = "multipart/form-data"),
+        responses(
+            (status = 201, description = "Files attached", body = [AttachmentResponse]),
+            (status = 400, description = "Malformed multipart body or no `file` part"),
+            (status = 404, description = "Todo item not found"),
+            (status = 413, description = "A file or the whole request is too large"),
+            (status = 415, description = "A file has a MIME type that isn't allowed"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item to attach the files to")
+        )
+    )]
+pub async fn upload_attachments(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+    mut multipart: Multipart,
+) -> Response {
+    // Checked again when saving, in case the item is deleted while the files stream in
+    if let Err(e) = app_state.todo_service.get_by_id(&app_state.db, id).await {
+        return (StatusCode::NOT_FOUND, e).into_response();
+    }
+
+    // Stage every file before recording any, so a rejected part attaches nothing
+    let mut staged = Vec::new();
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return e.into_response(),
+        };
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        match stage(&app_state, field).await {
+            Ok(file) => staged.push(file),
+            Err(response) => return response,
+        }
+    }
+    if staged.is_empty() {
+        return (StatusCode::BAD_REQUEST, "Expected at least one `file` part").into_response();
+    }
+
+    let service = &app_state.attachment_service;
+    let mut attachments = Vec::with_capacity(staged.len());
+    for (file_name, content_type, upload) in staged {
+        match service
+            .save(&app_state.db, id, file_name, content_type, upload)
+            .await
+        {
+            Ok(Some(attachment)) => attachments.push(attachment),
+            Ok(None) => {
+                let message = format!("Todo item with id {} not found", id);
+                return (StatusCode::NOT_FOUND, message).into_response();
+            }
+            Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+        }
+    }
+
+    (StatusCode::CREATED, Json(attachments)).into_response()
+}
+
+/// Streams one `file` part to disk, enforcing the size and MIME type limits
+async fn stage(
+    app_state: &AppState,
+    mut field: Field<'_>,
+) -> Result<(String, String, StagedUpload), Response> {
+    let service = &app_state.attachment_service;
+    let limits = service.limits();
+
+    let file_name = sanitize_file_name(field.file_name().unwrap_or_default());
+    let content_type = field
+        .content_type()
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            mime_guess::from_path(&file_name)
+                .first_or_octet_stream()
+                .to_string()
+        });
+    if !limits.allows(&content_type) {
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!("{} files are not allowed", content_type),
+        )
+            .into_response());
+    }
+
+    let mut upload = service
+        .stage()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())?;
+    loop {
+        let chunk = match field.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => return Err(e.into_response()),
+        };
+        if upload.size() + chunk.len() as u64 > limits.max_file_bytes {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("{} is larger than {} bytes", file_name, limits.max_file_bytes),
+            )
+                .into_response());
+        }
+        upload
+            .write(&chunk)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())?;
+    }
+
+    Ok((file_name, content_type, upload))
+}
+
+#[utoipa::path(
+        get,
+        path = "/{id}/attachments",
+        tag = TAG_ATTACHMENT,
+        responses(
+            (status = 200, description = "Files attached to the todo item", body = [AttachmentResponse]),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item")
+        )
+    )]
+pub async fn list_attachments(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Response {
+    app_state
+        .attachment_service
+        .list(&app_state.db, id)
+        .await
+        .map(|attachments| (StatusCode::OK, Json(attachments)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        get,
+        path = "/{id}/attachments/{attachment_id}",
+        tag = TAG_ATTACHMENT,
+        responses(
+            (status = 200, description = "File content", content_type = "application/octet-stream"),
+            (status = 206, description = "Requested byte range of the file", content_type = "application/octet-stream"),
+            (status = 304, description = "Not modified since `If-Modified-Since`"),
+            (status = 404, description = "Attachment not found"),
+            (status = 416, description = "Requested range is not satisfiable")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item"),
+            ("attachment_id" = i64, Path, description = "ID of the attachment to download"),
+            ("Range" = Option<String>, Header, description = "Single byte range to download, e.g. `bytes=0-1023`")
+        )
+    )]
+pub async fn download_attachment(
+    State(app_state): State<AppState>,
+    Path((id, attachment_id)): Path<(i64, i64)>,
+    request: Request,
+) -> Response {
+    let stored = match app_state
+        .attachment_service
+        .get(&app_state.db, id, attachment_id)
+        .await
+    {
+        Ok(stored) => stored,
+        Err(e) => return (StatusCode::NOT_FOUND, e).into_response(),
+    };
+
+    // ServeFile answers Range and If-Modified-Since requests
+    let mut response = match ServeFile::new(&stored.path).oneshot(request).await {
+        Ok(response) => response.into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    if response.status().is_success() {
+        let metadata = &stored.metadata;
+        let headers = response.headers_mut();
+        if let Ok(content_type) = HeaderValue::from_str(&metadata.content_type) {
+            headers.insert(header::CONTENT_TYPE, content_type);
+        }
+        if let Ok(disposition) = HeaderValue::from_str(&content_disposition(&metadata.file_name)) {
+            headers.insert(header::CONTENT_DISPOSITION, disposition);
+        }
+        if let Ok(etag) = HeaderValue::from_str(&format!("\"{}\"", metadata.sha256)) {
+            headers.insert(header::ETAG, etag);
+        }
+    }
+    response
+}
+
+#[utoipa::path(
+        delete,
+        path = "/{id}/attachments/{attachment_id}",
+        tag = TAG_ATTACHMENT,
+        responses(
+            (status = 204, description = "Attachment deleted"),
+            (status = 404, description = "Attachment not found"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item"),
+            ("attachment_id" = i64, Path, description = "ID of the attachment to delete")
+        )
+    )]
+pub async fn delete_attachment(
+    State(app_state): State<AppState>,
+    Path((id, attachment_id)): Path<(i64, i64)>,
+) -> Response {
+    match app_state
+        .attachment_service
+        .delete(&app_state.db, id, attachment_id)
+        .await
+    {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+/// Keeps only the final path component and drops characters that could break headers
+fn sanitize_file_name(file_name: &str) -> String {
+    let base_name = file_name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned = base_name
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(255)
+        .collect::<String>();
+
+    match cleaned.trim() {
+        "" | "." | ".." => "file".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// `attachment` disposition with an ASCII fallback name and the exact name per RFC 6266
+fn content_disposition(file_name: &str) -> String {
+    let fallback = file_name
+        .chars()
+        .map(|c| if c.is_ascii() { c } else { '_' })
+        .collect::<String>();
+    let encoded = file_name
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect::<String>();
+
+    format!("attachment; filename=\"{}\"; filename*=UTF-8''{}", fallback, encoded)
+}
@@ -0,0 +1,220 @@
+use crate::{
+    config::open_api::TAG_COMMENT,
+    controller::todo_controller::validation_failed,
+    model::{
+        comment_request::CommentRequest, comment_response::CommentResponse,
+        thread_entry::ThreadEntry, validation_error_response::ValidationErrorResponse,
+    },
+    service::comment_service,
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::{FromRequestParts, Path, State},
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+use validator::Validate;
+
+pub struct CommentController;
+impl CommentController {
+    /// Routes relative to the todo router
+    pub fn router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new()
+            .routes(routes!(get_thread, create_comment))
+            .routes(routes!(update_comment, delete_comment))
+    }
+}
+
+/// Lowercased username of the caller, sent in the `comments.author_header` header
+pub struct Author(pub String);
+
+impl FromRequestParts<AppState> for Author {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Response> {
+        let header = &state.comment_service.config().author_header;
+        let Some(value) = parts.headers.get(header.as_str()) else {
+            return Err((StatusCode::UNAUTHORIZED, format!("Missing {} header", header))
+                .into_response());
+        };
+
+        match value.to_str() {
+            Ok(username) if comment_service::is_username(username) => {
+                Ok(Author(username.to_lowercase()))
+            }
+            _ => Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "{} must be a username of letters, digits, '_', '.' and '-'",
+                    header
+                ),
+            )
+                .into_response()),
+        }
+    }
+}
+
+#[utoipa::path(
+        get,
+        path = "/{id}/comments",
+        tag = TAG_COMMENT,
+        responses(
+            (status = 200, description = "Comments and activity of the todo item, oldest first", body = [ThreadEntry]),
+            (status = 404, description = "Todo item not found"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item")
+        )
+    )]
+pub async fn get_thread(State(app_state): State<AppState>, Path(id): Path<i64>) -> Response {
+    if let Err(e) = app_state.todo_service.get_by_id(&app_state.db, id).await {
+        return (StatusCode::NOT_FOUND, e).into_response();
+    }
+
+    app_state
+        .comment_service
+        .thread(&app_state.db, id)
+        .await
+        .map(|thread| (StatusCode::OK, Json(thread)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        post,
+        path = "/{id}/comments",
+        tag = TAG_COMMENT,
+        request_body = CommentRequest,
+        responses(
+            (status = 201, description = "Comment added", body = CommentResponse),
+            (status = 400, description = "The author header is not a valid username"),
+            (status = 401, description = "The author header is missing"),
+            (status = 404, description = "Todo item not found"),
+            (status = 422, description = "Request failed validation", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item to comment on"),
+            ("X-User" = String, Header, description = "Username of the author (header name set by `comments.author_header`)")
+        )
+    )]
+pub async fn create_comment(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+    Author(author): Author,
+    Json(payload): Json<CommentRequest>,
+) -> Response {
+    if let Err(errors) = payload.validate() {
+        return validation_failed(errors);
+    }
+
+    match app_state.comment_service.create(&app_state.db, id, &author, payload.body).await {
+        Ok(Some(comment)) => (StatusCode::CREATED, Json(comment)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, format!("Todo item with id {} not found", id)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+#[utoipa::path(
+        put,
+        path = "/{id}/comments/{comment_id}",
+        tag = TAG_COMMENT,
+        request_body = CommentRequest,
+        responses(
+            (status = 200, description = "Comment edited", body = CommentResponse),
+            (status = 400, description = "The author header is not a valid username"),
+            (status = 401, description = "The author header is missing"),
+            (status = 403, description = "Only the author may edit the comment"),
+            (status = 404, description = "Comment not found"),
+            (status = 422, description = "Request failed validation", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item"),
+            ("comment_id" = i64, Path, description = "ID of the comment to edit"),
+            ("X-User" = String, Header, description = "Username of the author (header name set by `comments.author_header`)")
+        )
+    )]
+pub async fn update_comment(
+    State(app_state): State<AppState>,
+    Path((id, comment_id)): Path<(i64, i64)>,
+    Author(author): Author,
+    Json(payload): Json<CommentRequest>,
+) -> Response {
+    if let Err(errors) = payload.validate() {
+        return validation_failed(errors);
+    }
+    if let Err(response) = check_author(&app_state, id, comment_id, &author).await {
+        return response;
+    }
+
+    app_state
+        .comment_service
+        .update(&app_state.db, id, comment_id, payload.body)
+        .await
+        .map(|comment| (StatusCode::OK, Json(comment)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        delete,
+        path = "/{id}/comments/{comment_id}",
+        tag = TAG_COMMENT,
+        responses(
+            (status = 204, description = "Comment deleted"),
+            (status = 400, description = "The author header is not a valid username"),
+            (status = 401, description = "The author header is missing"),
+            (status = 403, description = "Only the author may delete the comment"),
+            (status = 404, description = "Comment not found"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item"),
+            ("comment_id" = i64, Path, description = "ID of the comment to delete"),
+            ("X-User" = String, Header, description = "Username of the author (header name set by `comments.author_header`)")
+        )
+    )]
+pub async fn delete_comment(
+    State(app_state): State<AppState>,
+    Path((id, comment_id)): Path<(i64, i64)>,
+    Author(author): Author,
+) -> Response {
+    if let Err(response) = check_author(&app_state, id, comment_id, &author).await {
+        return response;
+    }
+
+    match app_state
+        .comment_service
+        .delete(&app_state.db, id, comment_id)
+        .await
+    {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+/// Comments never change author, so checking before writing can't race
+async fn check_author(
+    app_state: &AppState,
+    id: i64,
+    comment_id: i64,
+    author: &str,
+) -> Result<(), Response> {
+    let comment = app_state
+        .comment_service
+        .get(&app_state.db, id, comment_id)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e).into_response())?;
+
+    if comment.author != author {
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!("Comment {} can only be changed by {}", comment_id, comment.author),
+        )
+            .into_response());
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use crate::{
+    config::open_api::TAG_DEPENDENCY,
+    controller::todo_controller::validation_failed,
+    model::{
+        dependency::{DependenciesResponse, DependencyError, DependencyRequest},
+        v2::todo_response::TodoResponse,
+        validation_error_response::ValidationErrorResponse,
+    },
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+pub struct DependencyController;
+impl DependencyController {
+    /// Routes relative to the todo router
+    pub fn router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new()
+            .routes(routes!(get_plan))
+            .routes(routes!(get_dependencies, add_dependency))
+            .routes(routes!(remove_dependency))
+    }
+}
+
+#[utoipa::path(
+        get,
+        path = "/plan",
+        tag = TAG_DEPENDENCY,
+        responses(
+            (status = 200, description = "Open todo items, each after the items blocking it and otherwise in manual order", body = [TodoResponse]),
+            (status = 500, description = "Internal server error")
+        )
+    )]
+pub async fn get_plan(State(app_state): State<AppState>) -> Response {
+    app_state
+        .todo_service
+        .plan(&app_state.db)
+        .await
+        .map(|todos| {
+            let todos: Vec<TodoResponse> = todos.into_iter().map(TodoResponse::from).collect();
+            (StatusCode::OK, Json(todos)).into_response()
+        })
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        get,
+        path = "/{id}/dependencies",
+        tag = TAG_DEPENDENCY,
+        responses(
+            (status = 200, description = "Todo items this one is blocked by, and those it blocks", body = DependenciesResponse),
+            (status = 404, description = "Todo item not found"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item")
+        )
+    )]
+pub async fn get_dependencies(State(app_state): State<AppState>, Path(id): Path<i64>) -> Response {
+    if let Err(e) = app_state.todo_service.get_by_id(&app_state.db, id).await {
+        return (StatusCode::NOT_FOUND, e).into_response();
+    }
+
+    app_state
+        .todo_service
+        .dependencies(&app_state.db, id)
+        .await
+        .map(|dependencies| (StatusCode::OK, Json(dependencies)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        post,
+        path = "/{id}/dependencies",
+        tag = TAG_DEPENDENCY,
+        request_body = DependencyRequest,
+        responses(
+            (status = 200, description = "Todo item blocked by the other one until it is completed", body = DependenciesResponse),
+            (status = 404, description = "Todo item not found"),
+            (status = 422, description = "The blocking todo item doesn't exist, or the dependency would close a cycle, reported with its `path`", body = ValidationErrorResponse)
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item to block")
+        )
+    )]
+pub async fn add_dependency(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(payload): Json<DependencyRequest>,
+) -> Response {
+    let service = &app_state.todo_service;
+    if let Err(e) = service.get_by_id(&app_state.db, id).await {
+        return (StatusCode::NOT_FOUND, e).into_response();
+    }
+    if service.get_by_id(&app_state.db, payload.blocked_by).await.is_err() {
+        return validation_failed(payload.unknown());
+    }
+
+    match service.add_dependency(&app_state.db, id, payload.blocked_by).await {
+        Ok(dependencies) => (StatusCode::OK, Json(dependencies)).into_response(),
+        Err(DependencyError::Cycle(path)) => validation_failed(payload.cycle(&path)),
+    }
+}
+
+#[utoipa::path(
+        delete,
+        path = "/{id}/dependencies/{blocked_by}",
+        tag = TAG_DEPENDENCY,
+        responses(
+            (status = 200, description = "Todo item no longer blocked by the other one", body = DependenciesResponse),
+            (status = 404, description = "Todo item not found, or it isn't blocked by the other one"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the blocked todo item"),
+            ("blocked_by" = i64, Path, description = "ID of the todo item blocking it")
+        )
+    )]
+pub async fn remove_dependency(
+    State(app_state): State<AppState>,
+    Path((id, blocked_by)): Path<(i64, i64)>,
+) -> Response {
+    if let Err(e) = app_state.todo_service.get_by_id(&app_state.db, id).await {
+        return (StatusCode::NOT_FOUND, e).into_response();
+    }
+
+    match app_state
+        .todo_service
+        .remove_dependency(&app_state.db, id, blocked_by)
+        .await
+    {
+        Ok(Some(dependencies)) => (StatusCode::OK, Json(dependencies)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            format!("Todo item with id {} isn't blocked by {}", id, blocked_by),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
@@ -1 +1,14 @@
-pub mod todo_controller;
\ No newline at end of file
+pub mod admin_controller;
+pub mod attachment_controller;
+pub mod comment_controller;
+pub mod dependency_controller;
+pub mod quick_add_controller;
+pub mod smart_list_controller;
+pub mod stats_controller;
+pub mod sync_controller;
+pub mod tag_controller;
+pub mod time_controller;
+pub mod todo_controller;
+pub mod v1;
+pub mod v2;
+pub mod workflow_controller;
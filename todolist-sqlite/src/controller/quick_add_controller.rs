@@ -0,0 +1,45 @@
+use crate::{
+    config::open_api::TAG_QUICK_ADD,
+    controller::todo_controller::validation_failed,
+    model::{
+        quick_add::{QuickAddRequest, QuickAddResponse},
+        validation_error_response::ValidationErrorResponse,
+    },
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+pub struct QuickAddController;
+impl QuickAddController {
+    /// Routes relative to the todo router
+    pub fn router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new().routes(routes!(quick_add))
+    }
+}
+
+#[utoipa::path(
+        post,
+        path = "/quick",
+        tag = TAG_QUICK_ADD,
+        request_body = QuickAddRequest,
+        responses(
+            (status = 200, description = "What the text says, for confirmation; nothing is saved", body = QuickAddResponse),
+            (status = 422, description = "The text is too long, leaves no title or names an unknown timezone", body = ValidationErrorResponse),
+        )
+    )]
+pub async fn quick_add(
+    State(app_state): State<AppState>,
+    Json(payload): Json<QuickAddRequest>,
+) -> Response {
+    match app_state.quick_add_service.parse(&payload, Utc::now()) {
+        Ok(parsed) => (StatusCode::OK, Json(parsed)).into_response(),
+        Err(errors) => validation_failed(errors),
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{
+    config::open_api::TAG_SMART_LIST,
+    controller::todo_controller::validation_failed,
+    model::{
+        smart_list::{SmartListRequest, SmartListResponse, name_taken},
+        todo_filter::TodoFilter,
+        v2::todo_list_response::TodoListResponse,
+        validation_error_response::ValidationErrorResponse,
+    },
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+use validator::Validate;
+
+pub struct SmartListController;
+impl SmartListController {
+    pub fn router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new()
+            .routes(routes!(get_smart_lists, create_smart_list))
+            .routes(routes!(get_smart_list, update_smart_list, delete_smart_list))
+            .routes(routes!(get_smart_list_todos))
+    }
+}
+
+#[utoipa::path(
+        get,
+        path = "/smart-lists",
+        tag = TAG_SMART_LIST,
+        responses(
+            (status = 200, description = "Every smart list, by name", body = [SmartListResponse]),
+            (status = 500, description = "Internal server error")
+        )
+    )]
+pub async fn get_smart_lists(State(app_state): State<AppState>) -> Response {
+    app_state
+        .smart_list_service
+        .list(&app_state.db)
+        .await
+        .map(|lists| (StatusCode::OK, Json(lists)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        post,
+        path = "/smart-lists",
+        tag = TAG_SMART_LIST,
+        request_body = SmartListRequest,
+        responses(
+            (status = 201, description = "Smart list created", body = SmartListResponse),
+            (status = 422, description = "Request failed validation, the query has a syntax error, or another list has the name", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        )
+    )]
+pub async fn create_smart_list(
+    State(app_state): State<AppState>,
+    Json(payload): Json<SmartListRequest>,
+) -> Response {
+    if let Err(errors) = payload.validate() {
+        return validation_failed(errors);
+    }
+    if let Some(existing) = app_state.smart_list_service.find_by_name(&app_state.db, &payload.name).await {
+        return validation_failed(name_taken(&existing.name));
+    }
+
+    app_state
+        .smart_list_service
+        .create(&app_state.db, payload)
+        .await
+        .map(|list| (StatusCode::CREATED, Json(list)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        get,
+        path = "/smart-lists/{list_id}",
+        tag = TAG_SMART_LIST,
+        responses(
+            (status = 200, description = "Smart list found", body = SmartListResponse),
+            (status = 404, description = "Smart list not found")
+        ),
+        params(
+            ("list_id" = i64, Path, description = "ID of the smart list")
+        )
+    )]
+pub async fn get_smart_list(State(app_state): State<AppState>, Path(list_id): Path<i64>) -> Response {
+    app_state
+        .smart_list_service
+        .get(&app_state.db, list_id)
+        .await
+        .map(|list| (StatusCode::OK, Json(list)).into_response())
+        .unwrap_or_else(|e| (StatusCode::NOT_FOUND, e).into_response())
+}
+
+#[utoipa::path(
+        put,
+        path = "/smart-lists/{list_id}",
+        tag = TAG_SMART_LIST,
+        request_body = SmartListRequest,
+        responses(
+            (status = 200, description = "Smart list renamed or given a new query", body = SmartListResponse),
+            (status = 404, description = "Smart list not found"),
+            (status = 422, description = "Request failed validation, the query has a syntax error, or another list has the name", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("list_id" = i64, Path, description = "ID of the smart list to update")
+        )
+    )]
+pub async fn update_smart_list(
+    State(app_state): State<AppState>,
+    Path(list_id): Path<i64>,
+    Json(payload): Json<SmartListRequest>,
+) -> Response {
+    if let Err(errors) = payload.validate() {
+        return validation_failed(errors);
+    }
+    let service = &app_state.smart_list_service;
+    if let Err(e) = service.get(&app_state.db, list_id).await {
+        return (StatusCode::NOT_FOUND, e).into_response();
+    }
+    if let Some(existing) = service.find_by_name(&app_state.db, &payload.name).await
+        && existing.id != list_id
+    {
+        return validation_failed(name_taken(&existing.name));
+    }
+
+    service
+        .update(&app_state.db, list_id, payload)
+        .await
+        .map(|list| (StatusCode::OK, Json(list)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        delete,
+        path = "/smart-lists/{list_id}",
+        tag = TAG_SMART_LIST,
+        responses(
+            (status = 204, description = "Smart list deleted; its todo items are kept"),
+            (status = 404, description = "Smart list not found"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("list_id" = i64, Path, description = "ID of the smart list to delete")
+        )
+    )]
+pub async fn delete_smart_list(State(app_state): State<AppState>, Path(list_id): Path<i64>) -> Response {
+    match app_state.smart_list_service.delete(&app_state.db, list_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            format!("Smart list with id {} not found", list_id),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+#[utoipa::path(
+        get,
+        path = "/smart-lists/{list_id}/todos",
+        tag = TAG_SMART_LIST,
+        responses(
+            (status = 200, description = "Todo items matching the query of the smart list now", body = TodoListResponse),
+            (status = 404, description = "Smart list not found"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("list_id" = i64, Path, description = "ID of the smart list")
+        )
+    )]
+pub async fn get_smart_list_todos(State(app_state): State<AppState>, Path(list_id): Path<i64>) -> Response {
+    let list = match app_state.smart_list_service.get(&app_state.db, list_id).await {
+        Ok(list) => list,
+        Err(e) => return (StatusCode::NOT_FOUND, e).into_response(),
+    };
+    let filter = TodoFilter {
+        q: Some(list.query),
+        ..Default::default()
+    };
+
+    app_state
+        .todo_service
+        .get_all(&app_state.db, filter)
+        .await
+        .map(|todos| (StatusCode::OK, Json(TodoListResponse::from(todos))).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
@@ -0,0 +1,72 @@
+use crate::{
+    config::open_api::TAG_STATS,
+    controller::todo_controller::validation_failed,
+    model::{
+        todo_stats::{StatsFormat, StatsQuery, TodoStatsResponse},
+        validation_error_response::ValidationErrorResponse,
+    },
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+pub struct StatsController;
+impl StatsController {
+    /// Routes relative to the todo router
+    pub fn router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new().routes(routes!(get_stats))
+    }
+}
+
+#[utoipa::path(
+        get,
+        path = "/stats",
+        tag = TAG_STATS,
+        params(StatsQuery),
+        responses(
+            (status = 200, description = "Productivity report, or its periods as CSV with `format=csv`", content(
+                (TodoStatsResponse = "application/json"),
+                (String = "text/csv", example = "period_start,created,completed\n2026-10-12,4,3\n")
+            )),
+            (status = 422, description = "The range is reversed or spans too many periods", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        )
+    )]
+pub async fn get_stats(
+    State(app_state): State<AppState>,
+    Query(query): Query<StatsQuery>,
+) -> Response {
+    let range = match query.range(Utc::now().date_naive()) {
+        Ok(range) => range,
+        Err(errors) => return validation_failed(errors),
+    };
+
+    let stats = match app_state.todo_service.stats(&app_state.db, range).await {
+        Ok(stats) => stats,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    };
+    match query.format.unwrap_or_default() {
+        StatsFormat::Json => (StatusCode::OK, Json(stats)).into_response(),
+        StatsFormat::Csv => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"todo-stats-{}-{}.csv\"",
+                        stats.from, stats.to
+                    ),
+                ),
+            ],
+            stats.to_csv(),
+        )
+            .into_response(),
+    }
+}
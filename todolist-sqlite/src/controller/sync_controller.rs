@@ -0,0 +1,85 @@
+use crate::{
+    config::open_api::TAG_SYNC,
+    controller::todo_controller::validation_failed,
+    model::{
+        todo_sync::{SyncPushResponse, SyncQuery, SyncRequest, SyncResponse},
+        validation_error_response::ValidationErrorResponse,
+    },
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+use validator::Validate;
+
+pub struct SyncController;
+impl SyncController {
+    /// Routes relative to the version router
+    pub fn router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new().routes(routes!(pull, push))
+    }
+}
+
+#[utoipa::path(
+        get,
+        path = "/sync",
+        tag = TAG_SYNC,
+        params(SyncQuery),
+        responses(
+            (status = 200, description = "Items changed and deleted since the token, oldest change first", body = SyncResponse),
+            (status = 410, description = "The token is from a newer history than the server's, e.g. after a restore; pull everything again without `since`"),
+            (status = 422, description = "The token or limit is invalid", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        )
+    )]
+pub async fn pull(State(app_state): State<AppState>, Query(query): Query<SyncQuery>) -> Response {
+    let (since, limit) = match query.position() {
+        Ok(position) => position,
+        Err(errors) => return validation_failed(errors),
+    };
+
+    match app_state
+        .todo_service
+        .changes_since(&app_state.db, since, limit)
+        .await
+    {
+        Ok(Some(changes)) => (StatusCode::OK, Json(changes)).into_response(),
+        Ok(None) => (
+            StatusCode::GONE,
+            "The sync token is unknown to this server, pull again without since",
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+#[utoipa::path(
+        post,
+        path = "/sync",
+        tag = TAG_SYNC,
+        request_body = SyncRequest,
+        responses(
+            (status = 200, description = "Result of each change, in order. Pull afterwards to receive changes made by others", body = SyncPushResponse),
+            (status = 422, description = "The batch is empty or too large", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        )
+    )]
+pub async fn push(State(app_state): State<AppState>, Json(payload): Json<SyncRequest>) -> Response {
+    if let Err(errors) = payload.validate() {
+        return validation_failed(errors);
+    }
+
+    let mut results = Vec::with_capacity(payload.changes.len());
+    for change in payload.changes {
+        match app_state.todo_service.apply_change(&app_state.db, change).await {
+            Ok(result) => results.push(result),
+            Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+        }
+    }
+
+    (StatusCode::OK, Json(SyncPushResponse { results })).into_response()
+}
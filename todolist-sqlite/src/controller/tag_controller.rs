@@ -0,0 +1,244 @@
+use crate::{
+    config::open_api::TAG_TAG,
+    controller::todo_controller::validation_failed,
+    model::{
+        tag::{
+            TagError, TagMergeRequest, TagRequest, TagResponse, TodoTagsRequest,
+            invalid_merge_target, name_taken,
+        },
+        v2::todo_response::TodoResponse,
+        validation_error_response::ValidationErrorResponse,
+    },
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+use validator::Validate;
+
+pub struct TagController;
+impl TagController {
+    /// Routes of the tags themselves
+    pub fn router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new()
+            .routes(routes!(get_tags, create_tag))
+            .routes(routes!(update_tag, delete_tag))
+            .routes(routes!(merge_tag))
+    }
+
+    /// Routes relative to the todo router
+    pub fn todo_router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new()
+            .routes(routes!(add_todo_tags))
+            .routes(routes!(remove_todo_tag))
+    }
+}
+
+#[utoipa::path(
+        get,
+        path = "/tags",
+        tag = TAG_TAG,
+        responses(
+            (status = 200, description = "Every tag, by name", body = [TagResponse]),
+            (status = 500, description = "Internal server error")
+        )
+    )]
+pub async fn get_tags(State(app_state): State<AppState>) -> Response {
+    app_state
+        .tag_service
+        .list(&app_state.db)
+        .await
+        .map(|tags| (StatusCode::OK, Json(tags)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        post,
+        path = "/tags",
+        tag = TAG_TAG,
+        request_body = TagRequest,
+        responses(
+            (status = 201, description = "Tag created", body = TagResponse),
+            (status = 422, description = "Request failed validation, or another tag has the name", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        )
+    )]
+pub async fn create_tag(
+    State(app_state): State<AppState>,
+    Json(payload): Json<TagRequest>,
+) -> Response {
+    if let Err(errors) = payload.validate() {
+        return validation_failed(errors);
+    }
+
+    match app_state.tag_service.create(&app_state.db, payload).await {
+        Ok(tag) => (StatusCode::CREATED, Json(tag)).into_response(),
+        Err(e) => tag_error(e),
+    }
+}
+
+#[utoipa::path(
+        put,
+        path = "/tags/{tag_id}",
+        tag = TAG_TAG,
+        request_body = TagRequest,
+        responses(
+            (status = 200, description = "Tag renamed or recoloured on every todo item", body = TagResponse),
+            (status = 404, description = "Tag not found"),
+            (status = 422, description = "Request failed validation, or another tag has the name", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("tag_id" = i64, Path, description = "ID of the tag to update")
+        )
+    )]
+pub async fn update_tag(
+    State(app_state): State<AppState>,
+    Path(tag_id): Path<i64>,
+    Json(payload): Json<TagRequest>,
+) -> Response {
+    if let Err(errors) = payload.validate() {
+        return validation_failed(errors);
+    }
+
+    match app_state.tag_service.update(&app_state.db, tag_id, payload).await {
+        Ok(tag) => (StatusCode::OK, Json(tag)).into_response(),
+        Err(e) => tag_error(e),
+    }
+}
+
+fn tag_error(error: TagError) -> Response {
+    match error {
+        TagError::NotFound(e) => (StatusCode::NOT_FOUND, e).into_response(),
+        TagError::Taken(existing) => validation_failed(name_taken(&existing)),
+        TagError::Failed(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+#[utoipa::path(
+        delete,
+        path = "/tags/{tag_id}",
+        tag = TAG_TAG,
+        responses(
+            (status = 204, description = "Tag deleted and taken off every todo item"),
+            (status = 404, description = "Tag not found"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("tag_id" = i64, Path, description = "ID of the tag to delete")
+        )
+    )]
+pub async fn delete_tag(State(app_state): State<AppState>, Path(tag_id): Path<i64>) -> Response {
+    match app_state.tag_service.delete(&app_state.db, tag_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (StatusCode::NOT_FOUND, format!("Tag with id {} not found", tag_id)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+#[utoipa::path(
+        post,
+        path = "/tags/{tag_id}/merge",
+        tag = TAG_TAG,
+        request_body = TagMergeRequest,
+        responses(
+            (status = 200, description = "Todo items moved over to `into`, which is returned; the merged tag is deleted", body = TagResponse),
+            (status = 404, description = "Tag not found"),
+            (status = 422, description = "`into` is the same or a missing tag", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("tag_id" = i64, Path, description = "ID of the tag to merge away")
+        )
+    )]
+pub async fn merge_tag(
+    State(app_state): State<AppState>,
+    Path(tag_id): Path<i64>,
+    Json(payload): Json<TagMergeRequest>,
+) -> Response {
+    let service = &app_state.tag_service;
+    if let Err(e) = service.get(&app_state.db, tag_id).await {
+        return (StatusCode::NOT_FOUND, e).into_response();
+    }
+    if payload.into == tag_id || service.get(&app_state.db, payload.into).await.is_err() {
+        return validation_failed(invalid_merge_target(tag_id, payload.into));
+    }
+
+    service
+        .merge(&app_state.db, tag_id, payload.into)
+        .await
+        .map(|tag| (StatusCode::OK, Json(tag)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        post,
+        path = "/{id}/tags",
+        tag = TAG_TAG,
+        request_body = TodoTagsRequest,
+        responses(
+            (status = 200, description = "Tags added to the todo item; missing tags are created", body = TodoResponse),
+            (status = 404, description = "Todo item not found"),
+            (status = 422, description = "Request failed validation", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item to tag")
+        )
+    )]
+pub async fn add_todo_tags(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(payload): Json<TodoTagsRequest>,
+) -> Response {
+    if let Err(errors) = payload.validate() {
+        return validation_failed(errors);
+    }
+    if let Err(e) = app_state.todo_service.get_by_id(&app_state.db, id).await {
+        return (StatusCode::NOT_FOUND, e).into_response();
+    }
+
+    app_state
+        .todo_service
+        .add_tags(&app_state.db, id, payload.names())
+        .await
+        .map(|todo| (StatusCode::OK, Json(TodoResponse::from(todo))).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        delete,
+        path = "/{id}/tags/{name}",
+        tag = TAG_TAG,
+        responses(
+            (status = 200, description = "Tag removed from the todo item; the tag itself is kept", body = TodoResponse),
+            (status = 404, description = "Todo item not found, or it doesn't have the tag"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item"),
+            ("name" = String, Path, description = "Name of the tag to remove, ignoring case")
+        )
+    )]
+pub async fn remove_todo_tag(
+    State(app_state): State<AppState>,
+    Path((id, name)): Path<(i64, String)>,
+) -> Response {
+    if let Err(e) = app_state.todo_service.get_by_id(&app_state.db, id).await {
+        return (StatusCode::NOT_FOUND, e).into_response();
+    }
+
+    match app_state.todo_service.remove_tag(&app_state.db, id, &name).await {
+        Ok(Some(todo)) => (StatusCode::OK, Json(TodoResponse::from(todo))).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            format!("Todo item with id {} has no tag {}", id, name),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{
+    config::open_api::TAG_TIME,
+    controller::{comment_controller::Author, todo_controller::validation_failed},
+    model::{
+        time_entry::{TimeEntryRequest, TimeEntryResponse, TimeReportQuery, TimeReportResponse},
+        todo_stats::StatsFormat,
+        validation_error_response::ValidationErrorResponse,
+    },
+    service::time_service::TimerError,
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::Query;
+use chrono::Utc;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+pub struct TimeController;
+impl TimeController {
+    /// Routes relative to the todo router
+    pub fn router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new()
+            .routes(routes!(start_timer))
+            .routes(routes!(stop_timer))
+            .routes(routes!(get_time_entries, create_time_entry))
+            .routes(routes!(delete_time_entry))
+            .routes(routes!(get_time_report))
+    }
+}
+
+#[utoipa::path(
+        post,
+        path = "/{id}/timer/start",
+        tag = TAG_TIME,
+        responses(
+            (status = 201, description = "Timer started", body = TimeEntryResponse),
+            (status = 400, description = "The user header is not a valid username"),
+            (status = 401, description = "The user header is missing"),
+            (status = 404, description = "Todo item not found"),
+            (status = 409, description = "The user's timer already runs, on this or another todo item"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item to track time on"),
+            ("X-User" = String, Header, description = "Username tracking the time (header name set by `comments.author_header`)")
+        )
+    )]
+pub async fn start_timer(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+    Author(user): Author,
+) -> Response {
+    match app_state.time_service.start(&app_state.db, id, &user).await {
+        Ok(entry) => (StatusCode::CREATED, Json(entry)).into_response(),
+        Err(TimerError::NotFound(e)) => (StatusCode::NOT_FOUND, e).into_response(),
+        Err(TimerError::AlreadyRunning(entry)) => (
+            StatusCode::CONFLICT,
+            format!(
+                "{} already has a timer running on todo {}; stop it first",
+                user, entry.todo_id
+            ),
+        )
+            .into_response(),
+        Err(TimerError::Failed(e)) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+#[utoipa::path(
+        post,
+        path = "/{id}/timer/stop",
+        tag = TAG_TIME,
+        responses(
+            (status = 200, description = "Timer stopped, with the time it tracked", body = TimeEntryResponse),
+            (status = 400, description = "The user header is not a valid username"),
+            (status = 401, description = "The user header is missing"),
+            (status = 404, description = "Todo item not found, or the user has no timer running on it"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item the timer runs on"),
+            ("X-User" = String, Header, description = "Username tracking the time (header name set by `comments.author_header`)")
+        )
+    )]
+pub async fn stop_timer(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+    Author(user): Author,
+) -> Response {
+    match app_state.time_service.stop(&app_state.db, id, &user).await {
+        Ok(Some(entry)) => (StatusCode::OK, Json(entry)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            format!("{} has no timer running on todo {}", user, id),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+#[utoipa::path(
+        get,
+        path = "/{id}/time-entries",
+        tag = TAG_TIME,
+        responses(
+            (status = 200, description = "Time tracked on the todo item, latest first", body = [TimeEntryResponse]),
+            (status = 404, description = "Todo item not found"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item")
+        )
+    )]
+pub async fn get_time_entries(State(app_state): State<AppState>, Path(id): Path<i64>) -> Response {
+    if let Err(e) = app_state.todo_service.get_by_id(&app_state.db, id).await {
+        return (StatusCode::NOT_FOUND, e).into_response();
+    }
+
+    app_state
+        .time_service
+        .list(&app_state.db, id)
+        .await
+        .map(|entries| (StatusCode::OK, Json(entries)).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        post,
+        path = "/{id}/time-entries",
+        tag = TAG_TIME,
+        request_body = TimeEntryRequest,
+        responses(
+            (status = 201, description = "Time entry added", body = TimeEntryResponse),
+            (status = 400, description = "The user header is not a valid username"),
+            (status = 401, description = "The user header is missing"),
+            (status = 404, description = "Todo item not found"),
+            (status = 422, description = "The entry doesn't end after it starts, ends in the future or failed validation", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item"),
+            ("X-User" = String, Header, description = "Username tracking the time (header name set by `comments.author_header`)")
+        )
+    )]
+pub async fn create_time_entry(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+    Author(user): Author,
+    Json(payload): Json<TimeEntryRequest>,
+) -> Response {
+    if let Err(errors) = payload.check(Utc::now()) {
+        return validation_failed(errors);
+    }
+
+    match app_state.time_service.create(&app_state.db, id, &user, payload).await {
+        Ok(Some(entry)) => (StatusCode::CREATED, Json(entry)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, format!("Todo item with id {} not found", id)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+#[utoipa::path(
+        delete,
+        path = "/{id}/time-entries/{entry_id}",
+        tag = TAG_TIME,
+        responses(
+            (status = 204, description = "Time entry deleted"),
+            (status = 400, description = "The user header is not a valid username"),
+            (status = 401, description = "The user header is missing"),
+            (status = 403, description = "Only the user who tracked the time may delete it"),
+            (status = 404, description = "Time entry not found"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item"),
+            ("entry_id" = i64, Path, description = "ID of the time entry to delete"),
+            ("X-User" = String, Header, description = "Username tracking the time (header name set by `comments.author_header`)")
+        )
+    )]
+pub async fn delete_time_entry(
+    State(app_state): State<AppState>,
+    Path((id, entry_id)): Path<(i64, i64)>,
+    Author(user): Author,
+) -> Response {
+    let Some(entry) = app_state.time_service.get(&app_state.db, id, entry_id).await else {
+        return (
+            StatusCode::NOT_FOUND,
+            format!("Time entry {} not found on todo {}", entry_id, id),
+        )
+            .into_response();
+    };
+    if entry.user != user {
+        return (
+            StatusCode::FORBIDDEN,
+            format!("Time entry {} can only be deleted by {}", entry_id, entry.user),
+        )
+            .into_response();
+    }
+
+    app_state
+        .time_service
+        .delete(&app_state.db, entry_id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT.into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        get,
+        path = "/time-report",
+        tag = TAG_TIME,
+        params(TimeReportQuery),
+        responses(
+            (status = 200, description = "Tracked time per todo item, tag or day, or its rows as CSV with `format=csv`", content(
+                (TimeReportResponse = "application/json"),
+                (String = "text/csv", example = "key,label,seconds,hours\n1,Write release notes,5400,1.50\n")
+            )),
+            (status = 422, description = "The range is reversed or spans too many days", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        )
+    )]
+pub async fn get_time_report(
+    State(app_state): State<AppState>,
+    Query(query): Query<TimeReportQuery>,
+) -> Response {
+    let range = match query.range(Utc::now().date_naive()) {
+        Ok(range) => range,
+        Err(errors) => return validation_failed(errors),
+    };
+
+    let report = match app_state.time_service.report(&app_state.db, &range).await {
+        Ok(report) => report,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    };
+    match query.format.unwrap_or_default() {
+        StatsFormat::Json => (StatusCode::OK, Json(report)).into_response(),
+        StatsFormat::Csv => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"time-report-{}-{}.csv\"",
+                        report.from, report.to
+                    ),
+                ),
+            ],
+            report.to_csv(),
+        )
+            .into_response(),
+    }
+}
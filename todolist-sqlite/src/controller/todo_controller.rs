@@ -1,140 +1,126 @@
 use crate::{
-    config::open_api::TAG_TODO,
-    model::{todo_item_request::TodoItemRequest, todo_item_response::TodoItemResponse},
+    model::{
+        move_request::{MoveRequest, unknown_target},
+        todo_filter::TodoFilter, todo_item_request::TodoItemRequest,
+        todo_item_response::TodoItemResponse, validation_error_response::ValidationErrorResponse,
+    },
+    service::todo_service::UpdateError,
     types::AppState,
 };
 use axum::{
     Json,
-    extract::{Path, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
-use utoipa_axum::{router::OpenApiRouter, routes};
+use serde::Serialize;
+use validator::{Validate, ValidationErrors};
 
+/// Maps the DTOs of one API version onto the service models, so every version
+/// shares the handlers of [`TodoController`]
+pub trait TodoDtoAdapter {
+    type Request: Validate + Into<TodoItemRequest>;
+    type Response: Serialize + From<TodoItemResponse>;
+    type List: Serialize + From<Vec<TodoItemResponse>>;
+
+    /// Rules checked on create, the same as on update unless overridden
+    fn validate_create(request: &Self::Request) -> Result<(), ValidationErrors> {
+        request.validate()
+    }
+}
+
+/// Version independent todo handlers; each version's controller documents its routes
+/// and delegates here with its own [`TodoDtoAdapter`]
 pub struct TodoController;
 impl TodoController {
-    pub fn router() -> OpenApiRouter<AppState> {
-        OpenApiRouter::new()
-            // Rutas para la raíz "/"
-            .routes(routes!(create_todo))
-            .routes(routes!(get_all))
-            // Rutas para "/{id}"
-            .routes(routes!(get_by_id))
-            .routes(routes!(update_todo))
-            .routes(routes!(delete_by_id))
+    pub async fn create<A: TodoDtoAdapter>(app_state: AppState, payload: A::Request) -> Response {
+        if let Err(errors) = A::validate_create(&payload) {
+            return validation_failed(errors);
+        }
+
+        app_state
+            .todo_service
+            .create(&app_state.db, payload.into())
+            .await
+            .map(|todo| (StatusCode::CREATED, Json(A::Response::from(todo))).into_response())
+            .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
     }
-}
 
-#[utoipa::path(
-        post,
-        path = "/",
-        tag = TAG_TODO,
-        request_body = TodoItemRequest,
-        responses(
-            (status = 201, description = "Todo item created successfully", body = TodoItemResponse),
-            (status = 500, description = "Internal server error")
-        )
-    )]
-pub async fn create_todo(
-    State(app_state): State<AppState>,
-    Json(payload): Json<TodoItemRequest>,
-) -> impl IntoResponse {
-    app_state
-        .todo_service
-        .create(&app_state.db, payload)
-        .await
-        .map(|todo| (StatusCode::CREATED, Json(todo)).into_response())
-        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
-}
+    pub async fn get_all<A: TodoDtoAdapter>(app_state: AppState, filter: TodoFilter) -> Response {
+        if let Err(errors) = filter.check() {
+            return validation_failed(errors);
+        }
 
-#[utoipa::path(
-        get,
-        path = "/",
-        tag = TAG_TODO,
-        responses(
-            (status = 200, description = "List of todo items", body = [TodoItemResponse]),
-            (status = 500, description = "Internal server error")
-        )
-    )]
-pub async fn get_all(State(app_state): State<AppState>) -> impl IntoResponse {
-    app_state
-        .todo_service
-        .get_all(&app_state.db)
-        .await
-        .map(|todos| (StatusCode::OK, Json(todos)).into_response())
-        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
-}
+        app_state
+            .todo_service
+            .get_all(&app_state.db, filter)
+            .await
+            .map(|todos| (StatusCode::OK, Json(A::List::from(todos))).into_response())
+            .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+    }
 
-#[utoipa::path(
-        put,
-        path = "/{id}",
-        tag = TAG_TODO,
-        request_body = TodoItemRequest,
-        responses(
-            (status = 200, description = "Todo item updated successfully", body = TodoItemResponse),
-            (status = 500, description = "Internal server error")
-        ),
-        params(
-            ("id" = i64, Path, description = "ID of the todo item to update")
-        )
-    )]
-pub async fn update_todo(
-    State(app_state): State<AppState>,
-    Path(id): Path<i64>,
-    Json(payload): Json<TodoItemRequest>,
-) -> impl IntoResponse {
-    app_state
-        .todo_service
-        .update(&app_state.db, id, payload)
-        .await
-        .map(|todo| (StatusCode::OK, Json(todo)).into_response())
-        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
-}
+    pub async fn update<A: TodoDtoAdapter>(
+        app_state: AppState,
+        id: i64,
+        payload: A::Request,
+    ) -> Response {
+        if let Err(errors) = payload.validate() {
+            return validation_failed(errors);
+        }
+
+        match app_state.todo_service.update(&app_state.db, id, payload.into()).await {
+            Ok(todo) => (StatusCode::OK, Json(A::Response::from(todo))).into_response(),
+            Err(UpdateError::Transition(errors)) => validation_failed(errors),
+            Err(UpdateError::NotFound(e)) => (StatusCode::NOT_FOUND, e).into_response(),
+        }
+    }
+
+    pub async fn get_by_id<A: TodoDtoAdapter>(app_state: AppState, id: i64) -> Response {
+        app_state
+            .todo_service
+            .get_by_id(&app_state.db, id)
+            .await
+            .map(|todo| (StatusCode::OK, Json(A::Response::from(todo))).into_response())
+            .unwrap_or_else(|e| (StatusCode::NOT_FOUND, e).into_response())
+    }
 
-#[utoipa::path(
-        get,
-        path = "/{id}",
-        tag = TAG_TODO,
-        responses(
-            (status = 200, description = "Todo item found", body = TodoItemResponse),
-            (status = 404, description = "Todo item not found")
-        ),
-        params(
-            ("id" = i64, Path, description = "ID of the todo item to retrieve")
-        )
-    )]
-pub async fn get_by_id(
-    State(app_state): State<AppState>,
-    Path(id): Path<i64>,
-) -> impl IntoResponse {
-    app_state
-        .todo_service
-        .get_by_id(&app_state.db, id)
-        .await
-        .map(|todo| (StatusCode::OK, Json(todo)).into_response())
-        .unwrap_or_else(|e| (StatusCode::NOT_FOUND, e).into_response())
+    pub async fn move_to<A: TodoDtoAdapter>(
+        app_state: AppState,
+        id: i64,
+        payload: MoveRequest,
+    ) -> Response {
+        let service = &app_state.todo_service;
+        if let Err(e) = service.get_by_id(&app_state.db, id).await {
+            return (StatusCode::NOT_FOUND, e).into_response();
+        }
+        let placement = match payload.placement(id) {
+            Ok(placement) => placement,
+            Err(errors) => return validation_failed(errors),
+        };
+        if service.get_by_id(&app_state.db, placement.target()).await.is_err() {
+            return validation_failed(unknown_target(placement));
+        }
+
+        service
+            .move_to(&app_state.db, id, placement)
+            .await
+            .map(|todo| (StatusCode::OK, Json(A::Response::from(todo))).into_response())
+            .unwrap_or_else(|e| (StatusCode::NOT_FOUND, e).into_response())
+    }
+
+    pub async fn delete_by_id(app_state: AppState, id: i64) -> Response {
+        app_state
+            .todo_service
+            .delete_by_id(&app_state.db, id)
+            .await
+            .map(|_| StatusCode::NO_CONTENT.into_response())
+            .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+    }
 }
 
-#[utoipa::path(
-        delete,
-        path = "/{id}",
-        tag = TAG_TODO,
-        responses(
-            (status = 204, description = "Todo item deleted successfully"),
-            (status = 500, description = "Internal server error")
-        ),
-        params(
-            ("id" = i64, Path, description = "ID of the todo item to delete")
-        )
-    )]
-pub async fn delete_by_id(
-    State(app_state): State<AppState>,
-    Path(id): Path<i64>,
-) -> impl IntoResponse {
-    app_state
-        .todo_service
-        .delete_by_id(&app_state.db, id)
-        .await
-        .map(|_| StatusCode::NO_CONTENT.into_response())
-        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+pub(crate) fn validation_failed(errors: ValidationErrors) -> Response {
+    (
+        StatusCode::UNPROCESSABLE_ENTITY,
+        Json(ValidationErrorResponse::from(errors)),
+    )
+        .into_response()
 }
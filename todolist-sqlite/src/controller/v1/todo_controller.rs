@@ -0,0 +1,145 @@
+use crate::{
+    config::open_api::TAG_TODO,
+    controller::todo_controller::{TodoController, TodoDtoAdapter},
+    model::{
+        todo_filter::TodoFilter, todo_item_request::TodoItemRequest,
+        todo_item_response::TodoItemResponse, validation_error_response::ValidationErrorResponse,
+    },
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::Response,
+};
+// Reads repeated keys like `?tag=work&tag=urgent` into lists
+use axum_extra::extract::Query;
+use utoipa_axum::{router::OpenApiRouter, routes};
+use validator::ValidationErrors;
+
+/// The original todo API, which takes the id in the request body and lists bare arrays
+pub struct TodoControllerV1;
+
+impl TodoDtoAdapter for TodoControllerV1 {
+    type Request = TodoItemRequest;
+    type Response = TodoItemResponse;
+    type List = Vec<TodoItemResponse>;
+
+    fn validate_create(request: &TodoItemRequest) -> Result<(), ValidationErrors> {
+        request.validate_for_create()
+    }
+}
+
+impl TodoControllerV1 {
+    pub fn router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new()
+            // Rutas para la raíz "/"
+            .routes(routes!(create_todo))
+            .routes(routes!(get_all))
+            // Rutas para "/{id}"
+            .routes(routes!(get_by_id))
+            .routes(routes!(update_todo))
+            .routes(routes!(delete_by_id))
+    }
+}
+
+#[utoipa::path(
+        post,
+        path = "/",
+        tag = TAG_TODO,
+        request_body = TodoItemRequest,
+        responses(
+            (status = 201, description = "Todo item created successfully", body = TodoItemResponse),
+            (status = 409, description = "A request with the same Idempotency-Key is still in progress"),
+            (status = 422, description = "Request failed validation, or the Idempotency-Key was used with a different payload", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("Idempotency-Key" = Option<String>, Header, description = "Client-generated key making retries safe. The first response is stored and replayed, with an `Idempotent-Replayed: true` header, for identical retries within the retention period")
+        )
+    )]
+pub async fn create_todo(
+    State(app_state): State<AppState>,
+    Json(payload): Json<TodoItemRequest>,
+) -> Response {
+    TodoController::create::<TodoControllerV1>(app_state, payload).await
+}
+
+#[utoipa::path(
+        get,
+        path = "/",
+        tag = TAG_TODO,
+        params(TodoFilter),
+        responses(
+            (status = 200, description = "List of todo items", body = [TodoItemResponse]),
+            (status = 422, description = "`q` is too long or has a syntax error, reported with its position", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        )
+    )]
+pub async fn get_all(
+    State(app_state): State<AppState>,
+    Query(filter): Query<TodoFilter>,
+) -> Response {
+    TodoController::get_all::<TodoControllerV1>(app_state, filter).await
+}
+
+#[utoipa::path(
+        put,
+        path = "/{id}",
+        tag = TAG_TODO,
+        request_body = TodoItemRequest,
+        responses(
+            (status = 200, description = "Todo item updated successfully", body = TodoItemResponse),
+            (status = 404, description = "Todo item not found"),
+            (status = 422, description = "Request failed validation, or the workflow doesn't let the item be completed or reopened from its state", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item to update")
+        )
+    )]
+pub async fn update_todo(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(payload): Json<TodoItemRequest>,
+) -> Response {
+    TodoController::update::<TodoControllerV1>(app_state, id, payload).await
+}
+
+#[utoipa::path(
+        get,
+        path = "/{id}",
+        tag = TAG_TODO,
+        responses(
+            (status = 200, description = "Todo item found", body = TodoItemResponse),
+            (status = 404, description = "Todo item not found")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item to retrieve")
+        )
+    )]
+pub async fn get_by_id(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Response {
+    TodoController::get_by_id::<TodoControllerV1>(app_state, id).await
+}
+
+#[utoipa::path(
+        delete,
+        path = "/{id}",
+        tag = TAG_TODO,
+        responses(
+            (status = 204, description = "Todo item deleted successfully"),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item to delete")
+        )
+    )]
+pub async fn delete_by_id(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Response {
+    TodoController::delete_by_id(app_state, id).await
+}
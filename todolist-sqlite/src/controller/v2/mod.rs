@@ -0,0 +1 @@
+pub mod todo_controller;
@@ -0,0 +1,84 @@
+use crate::{
+    config::open_api::TAG_WORKFLOW,
+    controller::todo_controller::validation_failed,
+    model::{
+        todo_filter::TodoFilter,
+        v2::todo_response::TodoResponse,
+        validation_error_response::ValidationErrorResponse,
+        workflow::{BoardResponse, TransitionRequest},
+    },
+    service::todo_service::UpdateError,
+    types::AppState,
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::Query;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+pub struct WorkflowController;
+impl WorkflowController {
+    /// Routes relative to the todo router
+    pub fn router() -> OpenApiRouter<AppState> {
+        OpenApiRouter::new()
+            .routes(routes!(get_board))
+            .routes(routes!(transition_todo))
+    }
+}
+
+#[utoipa::path(
+        get,
+        path = "/board",
+        tag = TAG_WORKFLOW,
+        params(TodoFilter),
+        responses(
+            (status = 200, description = "Todo items matching the filters, in one column per workflow state", body = BoardResponse),
+            (status = 422, description = "`q` is too long or has a syntax error, reported with its position", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        )
+    )]
+pub async fn get_board(
+    State(app_state): State<AppState>,
+    Query(filter): Query<TodoFilter>,
+) -> Response {
+    if let Err(errors) = filter.check() {
+        return validation_failed(errors);
+    }
+
+    let service = &app_state.todo_service;
+    service
+        .get_all(&app_state.db, filter)
+        .await
+        .map(|todos| (StatusCode::OK, Json(BoardResponse::new(service.workflow(), todos))).into_response())
+        .unwrap_or_else(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())
+}
+
+#[utoipa::path(
+        post,
+        path = "/{id}/transition",
+        tag = TAG_WORKFLOW,
+        request_body = TransitionRequest,
+        responses(
+            (status = 200, description = "Todo item moved to the state, and completed or reopened to match it", body = TodoResponse),
+            (status = 404, description = "Todo item not found"),
+            (status = 422, description = "The state is unknown, or can't be reached from the current one", body = ValidationErrorResponse),
+            (status = 500, description = "Internal server error")
+        ),
+        params(
+            ("id" = i64, Path, description = "ID of the todo item to move")
+        )
+    )]
+pub async fn transition_todo(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(payload): Json<TransitionRequest>,
+) -> Response {
+    match app_state.todo_service.transition(&app_state.db, id, &payload.to).await {
+        Ok(todo) => (StatusCode::OK, Json(TodoResponse::from(todo))).into_response(),
+        Err(UpdateError::Transition(errors)) => validation_failed(errors),
+        Err(UpdateError::NotFound(e)) => (StatusCode::NOT_FOUND, e).into_response(),
+    }
+}
@@ -0,0 +1,86 @@
+use std::{ffi::CStr, path::Path};
+
+use sqlite::{Connection, OpenFlags};
+use sqlite3_sys as ffi;
+
+use crate::db::migrations;
+
+/// Replaces the content of `destination` with a consistent copy of `source`, page by page,
+/// with SQLite's online backup API. Callers hold the lock of the live connection, so no
+/// write can land halfway through.
+pub fn copy(source: &Connection, destination: &Connection) -> Result<(), String> {
+    let main = c"main";
+
+    // SAFETY: both handles stay open for the whole call, and the backup object is finished
+    // before returning
+    unsafe {
+        let backup = ffi::sqlite3_backup_init(
+            destination.as_raw(),
+            main.as_ptr(),
+            source.as_raw(),
+            main.as_ptr(),
+        );
+        if backup.is_null() {
+            return Err(format!("Backup failed to start: {}", error_message(destination)));
+        }
+
+        let step = ffi::sqlite3_backup_step(backup, -1);
+        let finish = ffi::sqlite3_backup_finish(backup);
+        if step != ffi::SQLITE_DONE || finish != ffi::SQLITE_OK {
+            return Err(format!("Backup failed: {}", error_message(destination)));
+        }
+    }
+
+    Ok(())
+}
+
+fn error_message(connection: &Connection) -> String {
+    // SAFETY: SQLite returns a NUL-terminated message owned by the open connection
+    unsafe { CStr::from_ptr(ffi::sqlite3_errmsg(connection.as_raw())) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Writes a snapshot of `connection` to a new database file at `path`
+pub fn write_snapshot(connection: &Connection, path: &Path) -> Result<(), String> {
+    let snapshot = sqlite::open(path)
+        .map_err(|e| format!("Could not create {}: {}", path.display(), e))?;
+    copy(connection, &snapshot)?;
+
+    // The copy inherits WAL mode; a snapshot is a single self-contained file instead
+    snapshot
+        .execute("PRAGMA journal_mode = DELETE")
+        .map_err(|e| format!("Could not finish {}: {}", path.display(), e))
+}
+
+/// Opens `path` read-only and checks it is an intact todo database this build can migrate.
+/// Returns its schema version.
+pub fn validate(path: &Path) -> Result<i64, String> {
+    let connection = Connection::open_with_flags(path, OpenFlags::new().with_read_only())
+        .map_err(|e| format!("Could not open {}: {}", path.display(), e))?;
+
+    let mut statement = connection
+        .prepare("PRAGMA integrity_check")
+        .map_err(|e| format!("Not a SQLite database: {}", e))?;
+    let problems = statement
+        .iter()
+        .map(|row| row.map(|row| row.read::<&str, _>(0).to_string()))
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| format!("Integrity check failed: {}", e))?;
+    if problems != ["ok"] {
+        return Err(format!("Integrity check failed: {}", problems.join("; ")));
+    }
+
+    let version = migrations::current_version(&connection);
+    let latest = migrations::latest_version();
+    if version == 0 {
+        return Err("Not a todo database: it has no schema version".to_string());
+    }
+    if version > latest {
+        return Err(format!(
+            "Schema version {} is newer than the {} this server supports",
+            version, latest
+        ));
+    }
+    Ok(version)
+}
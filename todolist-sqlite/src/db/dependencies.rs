@@ -0,0 +1,121 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use sqlite::Connection;
+
+/// Whether a todo aliased `t` waits on an open todo, for queries reading todos
+pub const IS_BLOCKED: &str = "EXISTS (SELECT 1 FROM todo_dependencies bd JOIN todos bt ON bt.id = bd.blocked_by
+    WHERE bd.todo_id = t.id AND bt.completed = 0) AS is_blocked";
+
+/// Reads the [`IS_BLOCKED`] column of a row; rows without it aren't blocked
+pub fn read_is_blocked(row: &sqlite::Row) -> bool {
+    row.try_read::<Option<i64>, _>("is_blocked")
+        .ok()
+        .flatten()
+        .is_some_and(|blocked| blocked != 0)
+}
+
+/// Records that `todo_id` is blocked by `blocked_by`. Returns false when it already was.
+pub fn add(connection: &Connection, todo_id: i64, blocked_by: i64) -> bool {
+    let mut statement = connection
+        .prepare("INSERT OR IGNORE INTO todo_dependencies (todo_id, blocked_by) VALUES (?, ?)")
+        .unwrap();
+    statement.bind((1, todo_id)).unwrap();
+    statement.bind((2, blocked_by)).unwrap();
+    statement.next().unwrap();
+    connection.change_count() > 0
+}
+
+/// Returns false when `todo_id` wasn't blocked by `blocked_by`
+pub fn remove(connection: &Connection, todo_id: i64, blocked_by: i64) -> bool {
+    let mut statement = connection
+        .prepare("DELETE FROM todo_dependencies WHERE todo_id = ? AND blocked_by = ?")
+        .unwrap();
+    statement.bind((1, todo_id)).unwrap();
+    statement.bind((2, blocked_by)).unwrap();
+    statement.next().unwrap();
+    connection.change_count() > 0
+}
+
+/// Removes a todo from the graph, on both ends of its edges
+pub fn remove_all(connection: &Connection, todo_id: i64) {
+    let mut statement = connection
+        .prepare("DELETE FROM todo_dependencies WHERE todo_id = ?1 OR blocked_by = ?1")
+        .unwrap();
+    statement.bind((1, todo_id)).unwrap();
+    statement.next().unwrap();
+}
+
+/// Every edge as `(todo_id, blocked_by)`
+pub fn edges(connection: &Connection) -> Vec<(i64, i64)> {
+    let mut statement = connection
+        .prepare("SELECT todo_id, blocked_by FROM todo_dependencies ORDER BY todo_id, blocked_by")
+        .unwrap();
+    statement
+        .iter()
+        .map(|row| {
+            let row = row.unwrap();
+            (row.read::<i64, _>("todo_id"), row.read::<i64, _>("blocked_by"))
+        })
+        .collect()
+}
+
+/// The cycle that making `todo_id` blocked by `blocked_by` would close, starting and
+/// ending at `todo_id` with each todo blocked by the next; `None` when there is none
+pub fn cycle(connection: &Connection, todo_id: i64, blocked_by: i64) -> Option<Vec<i64>> {
+    let mut blockers: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (todo, blocker) in edges(connection) {
+        blockers.entry(todo).or_default().push(blocker);
+    }
+
+    // Breadth first from the new blocker, so the shortest cycle is reported
+    let mut previous: HashMap<i64, i64> = HashMap::new();
+    let mut queue = VecDeque::from([blocked_by]);
+    let mut seen = HashSet::from([blocked_by]);
+    while let Some(current) = queue.pop_front() {
+        if current == todo_id {
+            let mut path = vec![todo_id];
+            let mut step = todo_id;
+            while step != blocked_by {
+                step = previous[&step];
+                path.push(step);
+            }
+            path.push(todo_id);
+            path.reverse();
+            return Some(path);
+        }
+        for &next in blockers.get(&current).into_iter().flatten() {
+            if seen.insert(next) {
+                previous.insert(next, current);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Orders `ids` so every todo comes after the ones blocking it, keeping the given order
+/// between todos that don't depend on each other. Edges to todos outside `ids` are ignored.
+pub fn topological_order(ids: &[i64], edges: &[(i64, i64)]) -> Vec<i64> {
+    let index: HashMap<i64, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let mut waiting_on = vec![0; ids.len()];
+    let mut unblocks: Vec<Vec<usize>> = vec![Vec::new(); ids.len()];
+    for (todo, blocker) in edges {
+        if let (Some(&todo), Some(&blocker)) = (index.get(todo), index.get(blocker)) {
+            waiting_on[todo] += 1;
+            unblocks[blocker].push(todo);
+        }
+    }
+
+    let mut ready: BTreeSet<usize> = (0..ids.len()).filter(|&i| waiting_on[i] == 0).collect();
+    let mut order = Vec::with_capacity(ids.len());
+    while let Some(i) = ready.pop_first() {
+        order.push(ids[i]);
+        for &next in &unblocks[i] {
+            waiting_on[next] -= 1;
+            if waiting_on[next] == 0 {
+                ready.insert(next);
+            }
+        }
+    }
+    order
+}
@@ -0,0 +1,168 @@
+use sqlite::Connection;
+
+/// Schema migrations, applied in order. The position of a migration in this list
+/// is its version, tracked in SQLite's `user_version` pragma. Never edit or reorder
+/// an entry once released; append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: todo items
+    "CREATE TABLE IF NOT EXISTS todos (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        title TEXT NOT NULL,
+        completed BOOLEAN NOT NULL
+    );",
+    // 2: stored responses for Idempotency-Key replays
+    "CREATE TABLE idempotency_keys (
+        key TEXT PRIMARY KEY,
+        fingerprint TEXT NOT NULL,
+        status INTEGER,
+        content_type TEXT,
+        body BLOB,
+        created_at INTEGER NOT NULL
+    );
+    CREATE INDEX idx_idempotency_keys_created_at ON idempotency_keys (created_at);",
+    // 3: files attached to todos; content is stored on disk, named by its hash
+    "CREATE TABLE attachments (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        todo_id INTEGER NOT NULL,
+        file_name TEXT NOT NULL,
+        content_type TEXT NOT NULL,
+        size INTEGER NOT NULL,
+        sha256 TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+    );
+    CREATE INDEX idx_attachments_todo_id ON attachments (todo_id);
+    CREATE INDEX idx_attachments_sha256 ON attachments (sha256);",
+    // 4: comments with their @mentions, and the activity timeline they are listed in
+    "CREATE TABLE comments (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        todo_id INTEGER NOT NULL,
+        author TEXT NOT NULL,
+        body TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        edited_at INTEGER
+    );
+    CREATE INDEX idx_comments_todo_id ON comments (todo_id);
+    CREATE TABLE comment_mentions (
+        comment_id INTEGER NOT NULL,
+        username TEXT NOT NULL,
+        PRIMARY KEY (comment_id, username)
+    );
+    CREATE INDEX idx_comment_mentions_username ON comment_mentions (username);
+    CREATE TABLE todo_activity (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        todo_id INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        comment_id INTEGER,
+        old_value TEXT,
+        new_value TEXT,
+        created_at INTEGER NOT NULL
+    );
+    CREATE INDEX idx_todo_activity_todo_id ON todo_activity (todo_id);",
+    // 5: manual order, as fractional positions that start out in creation order
+    "ALTER TABLE todos ADD COLUMN position REAL NOT NULL DEFAULT 0;
+    UPDATE todos SET position = id * 1024.0;
+    CREATE INDEX idx_todos_position ON todos (position);",
+    // 6: when items were created, completed and are due; 0 for items whose creation
+    // time is unknown because they predate it
+    "ALTER TABLE todos ADD COLUMN created_at INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE todos ADD COLUMN completed_at INTEGER;
+    ALTER TABLE todos ADD COLUMN due_at INTEGER;
+    CREATE INDEX idx_todos_created_at ON todos (created_at);
+    CREATE INDEX idx_todos_completed_at ON todos (completed_at);
+    CREATE INDEX idx_todos_due_at ON todos (due_at);",
+    // 7: change sequence for delta sync, per-field modification times for
+    // last-writer-wins merges, and tombstones of deleted items (times in Unix ms)
+    "CREATE TABLE change_sequence (value INTEGER NOT NULL);
+    INSERT INTO change_sequence (value) VALUES (1);
+    ALTER TABLE todos ADD COLUMN change_seq INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE todos ADD COLUMN title_modified_at INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE todos ADD COLUMN completed_modified_at INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE todos ADD COLUMN due_at_modified_at INTEGER NOT NULL DEFAULT 0;
+    UPDATE todos SET change_seq = 1,
+        title_modified_at = created_at * 1000,
+        completed_modified_at = COALESCE(completed_at, created_at) * 1000,
+        due_at_modified_at = created_at * 1000;
+    CREATE INDEX idx_todos_change_seq ON todos (change_seq);
+    CREATE TABLE todo_tombstones (
+        id INTEGER PRIMARY KEY,
+        change_seq INTEGER NOT NULL,
+        deleted_at INTEGER NOT NULL
+    );
+    CREATE INDEX idx_todo_tombstones_change_seq ON todo_tombstones (change_seq);",
+    // 8: tags, unique ignoring case, and the todos they are on
+    "CREATE TABLE tags (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL COLLATE NOCASE UNIQUE,
+        color TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+    );
+    CREATE TABLE todo_tags (
+        todo_id INTEGER NOT NULL,
+        tag_id INTEGER NOT NULL,
+        PRIMARY KEY (todo_id, tag_id)
+    );
+    CREATE INDEX idx_todo_tags_tag_id ON todo_tags (tag_id);",
+    // 9: saved search queries
+    "CREATE TABLE smart_lists (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL COLLATE NOCASE UNIQUE,
+        query TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+    );",
+    // 10: workflow state, filled in from `completed` on startup as the states are configured
+    "ALTER TABLE todos ADD COLUMN state TEXT;",
+    // 11: tracked time, with at most one running timer per user
+    "CREATE TABLE time_entries (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        todo_id INTEGER NOT NULL,
+        user TEXT NOT NULL,
+        started_at INTEGER NOT NULL,
+        ended_at INTEGER,
+        note TEXT,
+        created_at INTEGER NOT NULL
+    );
+    CREATE INDEX idx_time_entries_todo_id ON time_entries (todo_id);
+    CREATE UNIQUE INDEX idx_time_entries_running ON time_entries (user) WHERE ended_at IS NULL;",
+    // 12: todos blocked by other todos, kept free of cycles
+    "CREATE TABLE todo_dependencies (
+        todo_id INTEGER NOT NULL,
+        blocked_by INTEGER NOT NULL,
+        PRIMARY KEY (todo_id, blocked_by)
+    );
+    CREATE INDEX idx_todo_dependencies_blocked_by ON todo_dependencies (blocked_by);",
+    // 13: tag names folded to lower case, which unlike NOCASE covers non-ASCII letters;
+    // filled in on startup as SQLite's lower() only folds ASCII
+    "ALTER TABLE tags ADD COLUMN folded_name TEXT;
+    CREATE UNIQUE INDEX idx_tags_folded_name ON tags (folded_name);",
+];
+
+/// Schema version of a fully migrated database
+pub fn latest_version() -> i64 {
+    MIGRATIONS.len() as i64
+}
+
+pub fn current_version(connection: &Connection) -> i64 {
+    let mut statement = connection.prepare("PRAGMA user_version").unwrap();
+    statement.next().unwrap();
+    statement.read::<i64, _>(0).unwrap()
+}
+
+/// Applies every migration newer than the database's `user_version`, each in its own transaction
+pub fn run(connection: &Connection) -> Result<(), String> {
+    let current = current_version(connection);
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let version = index + 1;
+        connection
+            .execute(format!(
+                "BEGIN; {} PRAGMA user_version = {}; COMMIT;",
+                migration, version
+            ))
+            .map_err(|e| {
+                let _ = connection.execute("ROLLBACK");
+                format!("Migration {} failed: {}", version, e)
+            })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+pub mod backup;
+pub mod dependencies;
+pub mod migrations;
+pub mod ordering;
+pub mod tags;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use sqlite::Connection;
+
+use crate::{types::Db, worker::Shutdown};
+
+/// Opens the SQLite database at `path` in WAL mode so readers don't block the writer,
+/// and brings its schema up to date.
+pub fn open(path: &str) -> Db {
+    let connection = sqlite::open(path).unwrap();
+    connection.execute("PRAGMA journal_mode = WAL").unwrap();
+    migrations::run(&connection).expect("Failed to migrate database");
+    tags::fold_names(&connection);
+
+    Arc::new(Mutex::new(connection))
+}
+
+/// Whether todo item `id` exists. Rows referring to an item check this under the lock
+/// that inserts them, as deleting the item removes it before the rows referring to it.
+pub fn todo_exists(connection: &Connection, id: i64) -> bool {
+    let mut statement = connection.prepare("SELECT 1 FROM todos WHERE id = ?").unwrap();
+    statement.bind((1, id)).unwrap();
+    statement.next().unwrap() == sqlite::State::Row
+}
+
+/// Copies the WAL content back into the main database file and truncates the WAL.
+pub fn checkpoint(db: &Db) -> Result<(), String> {
+    let connection = db.lock().unwrap();
+    connection
+        .execute("PRAGMA wal_checkpoint(TRUNCATE)")
+        .map_err(|e| format!("WAL checkpoint failed: {}", e))
+}
+
+/// Background worker that periodically checkpoints the WAL so it doesn't grow unbounded.
+pub async fn run_checkpoints(db: Db, every: Duration, mut shutdown: Shutdown) {
+    let mut ticker = tokio::time::interval(every);
+    ticker.tick().await;
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                if let Err(e) = checkpoint(&db) {
+                    eprintln!("⚠️  {}", e);
+                }
+            }
+            _ = shutdown.requested() => break,
+        }
+    }
+}
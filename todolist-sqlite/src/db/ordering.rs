@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use sqlite::Connection;
+
+use crate::{types::Db, worker::Shutdown};
+
+/// Distance between neighbours when items are appended or rebalanced
+pub const STEP: f64 = 1024.0;
+
+/// Gaps the periodic rebalance spreads out again, well before moves run out of room
+const REBALANCE_BELOW: f64 = 1e-3;
+
+/// Where to move an item, relative to another one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    Before(i64),
+    After(i64),
+}
+
+impl Placement {
+    pub fn target(&self) -> i64 {
+        match self {
+            Placement::Before(target) | Placement::After(target) => *target,
+        }
+    }
+}
+
+/// Position for a new item, after every existing one
+pub fn next_position(connection: &Connection) -> f64 {
+    let mut statement = connection
+        .prepare("SELECT COALESCE(MAX(position), 0) FROM todos")
+        .unwrap();
+    statement.next().unwrap();
+    statement.read::<f64, _>(0).unwrap() + STEP
+}
+
+/// Moves item `id` next to the placement's target by giving it a position halfway to the
+/// target's neighbour, so no other row changes unless the gap there has run out.
+/// Returns false when either item doesn't exist.
+pub fn move_to(connection: &Connection, id: i64, placement: Placement) -> bool {
+    if position_of(connection, id).is_none() {
+        return false;
+    }
+    let position = match position_for(connection, id, placement) {
+        Some(Some(position)) => position,
+        Some(None) => {
+            rebalance(connection);
+            match position_for(connection, id, placement) {
+                Some(Some(position)) => position,
+                _ => return false,
+            }
+        }
+        None => return false,
+    };
+
+    let mut statement = connection
+        .prepare("UPDATE todos SET position = ? WHERE id = ?")
+        .unwrap();
+    statement.bind((1, position)).unwrap();
+    statement.bind((2, id)).unwrap();
+    statement.next().unwrap();
+    true
+}
+
+/// `None` when the target doesn't exist, `Some(None)` when there is no room next to it
+fn position_for(connection: &Connection, id: i64, placement: Placement) -> Option<Option<f64>> {
+    let target = position_of(connection, placement.target())?;
+
+    // The moved item is skipped, since it leaves its current spot
+    let (query, step) = match placement {
+        Placement::Before(_) => (
+            "SELECT MAX(position) FROM todos WHERE position < ? AND id != ?",
+            -STEP,
+        ),
+        Placement::After(_) => (
+            "SELECT MIN(position) FROM todos WHERE position > ? AND id != ?",
+            STEP,
+        ),
+    };
+    let mut statement = connection.prepare(query).unwrap();
+    statement.bind((1, target)).unwrap();
+    statement.bind((2, id)).unwrap();
+    statement.next().unwrap();
+
+    Some(match statement.read::<Option<f64>, _>(0).unwrap() {
+        None => Some(target + step),
+        // Once the neighbours are adjacent floats, at any magnitude, the halfway point
+        // rounds onto one of them and the list has to be rebalanced first
+        Some(neighbour) => {
+            let halfway = target + (neighbour - target) / 2.0;
+            (halfway != target && halfway != neighbour).then_some(halfway)
+        }
+    })
+}
+
+fn position_of(connection: &Connection, id: i64) -> Option<f64> {
+    let mut statement = connection
+        .prepare("SELECT position FROM todos WHERE id = ?")
+        .unwrap();
+    statement.bind((1, id)).unwrap();
+    statement
+        .iter()
+        .map(|row| row.unwrap().read::<f64, _>("position"))
+        .next()
+}
+
+/// Spreads every item `STEP` apart again, keeping their order
+pub fn rebalance(connection: &Connection) {
+    let ids = connection
+        .prepare("SELECT id FROM todos ORDER BY position, id")
+        .unwrap()
+        .iter()
+        .map(|row| row.unwrap().read::<i64, _>("id"))
+        .collect::<Vec<i64>>();
+
+    connection.execute("BEGIN").unwrap();
+    let mut statement = connection
+        .prepare("UPDATE todos SET position = ? WHERE id = ?")
+        .unwrap();
+    for (index, id) in ids.into_iter().enumerate() {
+        statement.reset().unwrap();
+        statement.bind((1, (index + 1) as f64 * STEP)).unwrap();
+        statement.bind((2, id)).unwrap();
+        statement.next().unwrap();
+    }
+    connection.execute("COMMIT").unwrap();
+}
+
+/// Rebalances when two neighbours have come closer than the periodic threshold.
+/// Returns whether it did.
+pub fn rebalance_if_needed(db: &Db) -> bool {
+    let connection = db.lock().unwrap();
+    let positions = connection
+        .prepare("SELECT position FROM todos ORDER BY position")
+        .unwrap()
+        .iter()
+        .map(|row| row.unwrap().read::<f64, _>("position"))
+        .collect::<Vec<f64>>();
+
+    let crowded = positions
+        .windows(2)
+        .any(|pair| pair[1] - pair[0] < REBALANCE_BELOW);
+    if crowded {
+        rebalance(&connection);
+    }
+    crowded
+}
+
+/// Background worker that periodically rebalances the positions of crowded lists
+pub async fn run_rebalance(db: Db, every: Duration, mut shutdown: Shutdown) {
+    let mut ticker = tokio::time::interval(every);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                rebalance_if_needed(&db);
+            }
+            _ = shutdown.requested() => break,
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::Utc;
+use sqlite::Connection;
+
+use crate::model::tag::{DEFAULT_TAG_COLOR, TagResponse};
+
+/// Tags of a todo aliased `t` as a JSON array, for queries that add [`JOIN`] and group
+/// by `t.id`, so a list is read with its tags in one query
+pub const COLUMN: &str = "json_group_array(json_object('id', g.id, 'name', g.name, 'color', g.color))
+    FILTER (WHERE g.id IS NOT NULL) AS tags";
+
+pub const JOIN: &str = "LEFT JOIN todo_tags tt ON tt.todo_id = t.id LEFT JOIN tags g ON g.id = tt.tag_id";
+
+/// Reads the [`COLUMN`] of a row, sorted by name; rows without it have no tags
+pub fn read(row: &sqlite::Row) -> Vec<TagResponse> {
+    let json = row.try_read::<Option<&str>, _>("tags").ok().flatten();
+    let mut tags: Vec<TagResponse> = json
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default();
+    tags.sort_by_key(|tag| tag.name.to_lowercase());
+    tags
+}
+
+pub fn read_tag(row: &sqlite::Row) -> TagResponse {
+    TagResponse {
+        id: row.read::<i64, _>("id"),
+        name: row.read::<&str, _>("name").to_string(),
+        color: row.read::<&str, _>("color").to_string(),
+    }
+}
+
+/// The `folded_name` tag names are compared by, in any script
+pub fn fold(name: &str) -> String {
+    name.to_lowercase()
+}
+
+/// Fills in `folded_name` for tags stored before it existed. A tag folding to the name
+/// of another is left without one, and only found by id.
+pub fn fold_names(connection: &Connection) {
+    let mut statement = connection
+        .prepare("SELECT id, name FROM tags WHERE folded_name IS NULL")
+        .unwrap();
+    let unfolded: Vec<(i64, String)> = statement
+        .iter()
+        .map(|row| {
+            let row = row.unwrap();
+            (row.read::<i64, _>("id"), row.read::<&str, _>("name").to_string())
+        })
+        .collect();
+
+    for (id, name) in unfolded {
+        let mut statement = connection
+            .prepare("UPDATE OR IGNORE tags SET folded_name = ? WHERE id = ?")
+            .unwrap();
+        statement.bind((1, fold(&name).as_str())).unwrap();
+        statement.bind((2, id)).unwrap();
+        statement.next().unwrap();
+    }
+}
+
+/// The tag with this name, ignoring case
+pub fn find_by_name(connection: &Connection, name: &str) -> Option<TagResponse> {
+    let mut statement = connection
+        .prepare("SELECT id, name, color FROM tags WHERE folded_name = ?")
+        .unwrap();
+    statement.bind((1, fold(name).as_str())).unwrap();
+    statement.iter().map(|row| read_tag(&row.unwrap())).next()
+}
+
+/// Returns `None` when another tag already has the name
+pub fn insert(connection: &Connection, name: &str, color: &str) -> Result<Option<TagResponse>, String> {
+    let mut statement = connection
+        .prepare(
+            "INSERT INTO tags (name, folded_name, color, created_at) VALUES (?, ?, ?, ?)
+            ON CONFLICT DO NOTHING RETURNING id",
+        )
+        .unwrap();
+    statement.bind((1, name)).unwrap();
+    statement.bind((2, fold(name).as_str())).unwrap();
+    statement.bind((3, color.to_lowercase().as_str())).unwrap();
+    statement.bind((4, Utc::now().timestamp())).unwrap();
+    let state = statement
+        .next()
+        .map_err(|e| format!("Could not create tag {}: {}", name, e))?;
+    if state == sqlite::State::Done {
+        return Ok(None);
+    }
+
+    Ok(Some(TagResponse {
+        id: statement.read::<i64, _>("id").unwrap(),
+        name: name.to_string(),
+        color: color.to_lowercase(),
+    }))
+}
+
+/// Tags a todo, creating the tags that don't exist yet. Returns false when it already
+/// had all of them.
+pub fn attach(connection: &Connection, todo_id: i64, names: &[String]) -> Result<bool, String> {
+    let mut added = false;
+    for name in names {
+        let tag = match find_by_name(connection, name) {
+            Some(tag) => tag,
+            None => insert(connection, name, DEFAULT_TAG_COLOR)?
+                .ok_or_else(|| format!("Could not create tag {}", name))?,
+        };
+        let mut statement = connection
+            .prepare("INSERT OR IGNORE INTO todo_tags (todo_id, tag_id) VALUES (?, ?)")
+            .unwrap();
+        statement.bind((1, todo_id)).unwrap();
+        statement.bind((2, tag.id)).unwrap();
+        statement.next().unwrap();
+        added |= connection.change_count() > 0;
+    }
+    Ok(added)
+}
+
+/// Returns false when the todo didn't have the tag
+pub fn detach(connection: &Connection, todo_id: i64, tag_id: i64) -> bool {
+    let mut statement = connection
+        .prepare("DELETE FROM todo_tags WHERE todo_id = ? AND tag_id = ?")
+        .unwrap();
+    statement.bind((1, todo_id)).unwrap();
+    statement.bind((2, tag_id)).unwrap();
+    statement.next().unwrap();
+    connection.change_count() > 0
+}
+
+pub fn detach_all(connection: &Connection, todo_id: i64) {
+    let mut statement = connection
+        .prepare("DELETE FROM todo_tags WHERE todo_id = ?")
+        .unwrap();
+    statement.bind((1, todo_id)).unwrap();
+    statement.next().unwrap();
+}
+
+/// Ids of the todos carrying a tag
+pub fn todos_with(connection: &Connection, tag_id: i64) -> Vec<i64> {
+    let mut statement = connection
+        .prepare("SELECT todo_id FROM todo_tags WHERE tag_id = ? ORDER BY todo_id")
+        .unwrap();
+    statement.bind((1, tag_id)).unwrap();
+    statement
+        .iter()
+        .map(|row| row.unwrap().read::<i64, _>("todo_id"))
+        .collect()
+}
@@ -0,0 +1,42 @@
+use tokio::sync::broadcast;
+
+use crate::model::todo_item_response::TodoItemResponse;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TodoEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// A change made to a todo item, published after it has been written to the database
+#[derive(Debug, Clone)]
+pub struct TodoEvent {
+    pub kind: TodoEventKind,
+    pub id: i64,
+    /// State after the change, `None` for deletions
+    pub todo: Option<TodoItemResponse>,
+}
+
+/// In-process fan-out of todo changes to live subscribers (e.g. GraphQL subscriptions).
+/// Subscribers that fall more than `capacity` events behind miss the oldest ones.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<TodoEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: TodoEvent) {
+        // Nobody listening is not an error
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<TodoEvent> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,117 @@
+pub mod schema;
+
+use std::{future::ready, str::FromStr};
+
+use async_graphql::http::{
+    ALL_WEBSOCKET_PROTOCOLS, GraphiQLSource, WebSocket as GraphqlWebSocket, WebSocketProtocols,
+    WsMessage,
+};
+use axum::{
+    Json, Router,
+    extract::{
+        State, WebSocketUpgrade,
+        ws::{CloseFrame, Message},
+    },
+    http::{HeaderMap, HeaderValue, header},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
+};
+use futures_util::{SinkExt, StreamExt};
+
+use crate::{config::settings::AppSettings, graphql::schema::TodoSchema, types::AppState};
+
+/// GraphiQL loads its assets from unpkg, so it gets a CSP of its own instead of the API default
+const GRAPHIQL_CSP: &str = "default-src 'self'; script-src 'self' 'unsafe-inline' https://unpkg.com; \
+    style-src 'self' 'unsafe-inline' https://unpkg.com; img-src 'self' data: https:; \
+    font-src 'self' data: https://unpkg.com; connect-src 'self' ws: wss:";
+
+/// Routes for the GraphQL endpoint, its WebSocket subscription endpoint and the GraphiQL
+/// playground, relative to `app.prefix`
+pub fn router<S>(app_settings: &AppSettings, app_state: AppState) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let graphql = &app_settings.graphql;
+    let schema = schema::build_schema(app_state);
+
+    let endpoint = format!("{}{}", app_settings.app.prefix, graphql.path);
+    let subscription_endpoint = format!("{}{}", app_settings.app.prefix, graphql.ws_path);
+    let playground = GraphiQLSource::build()
+        .endpoint(&endpoint)
+        .subscription_endpoint(&subscription_endpoint)
+        .title("Todo GraphQL")
+        .finish();
+
+    Router::new()
+        .route(&graphql.path, post(execute))
+        .route(&graphql.ws_path, get(subscribe))
+        .route(
+            &graphql.playground_path,
+            get(move || ready(graphiql(playground.clone()))),
+        )
+        .with_state(schema)
+}
+
+async fn execute(
+    State(schema): State<TodoSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request).await)
+}
+
+fn graphiql(page: String) -> Response {
+    let mut response = Html(page).into_response();
+    response.headers_mut().insert(
+        header::CONTENT_SECURITY_POLICY,
+        HeaderValue::from_static(GRAPHIQL_CSP),
+    );
+    response
+}
+
+/// Serves subscriptions over WebSocket using either the `graphql-transport-ws`
+/// or the legacy `graphql-ws` sub-protocol
+async fn subscribe(
+    State(schema): State<TodoSchema>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let protocol = headers
+        .get(header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|protocols| {
+            protocols
+                .split(',')
+                .find_map(|protocol| WebSocketProtocols::from_str(protocol.trim()).ok())
+        })
+        .unwrap_or(WebSocketProtocols::GraphQLWS);
+
+    upgrade
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |socket| async move {
+            let (mut sink, stream) = socket.split();
+            let input = stream
+                .take_while(|message| ready(message.is_ok()))
+                .filter_map(|message| {
+                    ready(match message {
+                        Ok(message @ (Message::Text(_) | Message::Binary(_))) => {
+                            Some(message.into_data())
+                        }
+                        _ => None,
+                    })
+                });
+
+            let mut output = GraphqlWebSocket::new(schema, input, protocol);
+            while let Some(message) = output.next().await {
+                let message = match message {
+                    WsMessage::Text(text) => Message::Text(text.into()),
+                    WsMessage::Close(code, reason) => Message::Close(Some(CloseFrame {
+                        code,
+                        reason: reason.into(),
+                    })),
+                };
+                if sink.send(message).await.is_err() {
+                    break;
+                }
+            }
+        })
+}
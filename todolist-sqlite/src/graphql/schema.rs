@@ -0,0 +1,243 @@
+use async_graphql::{
+    Context, Enum, ErrorExtensions, InputObject, Object, Schema, SimpleObject, Subscription,
+};
+use tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream};
+use validator::{Validate, ValidationErrors};
+
+use crate::{
+    events::{TodoEvent, TodoEventKind},
+    model::{
+        todo_filter::TodoFilter, todo_item_request::TodoItemRequest,
+        todo_item_response::TodoItemResponse,
+        validation_error_response::ValidationErrorResponse,
+    },
+    service::todo_service::UpdateError,
+    types::AppState,
+};
+
+pub type TodoSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
+
+pub fn build_schema(app_state: AppState) -> TodoSchema {
+    Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+        .data(app_state)
+        .finish()
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct Todo {
+    pub id: i64,
+    pub title: String,
+    pub completed: bool,
+}
+
+impl From<TodoItemResponse> for Todo {
+    fn from(todo: TodoItemResponse) -> Self {
+        Self {
+            id: todo.id.unwrap_or_default(),
+            title: todo.title,
+            completed: todo.completed,
+        }
+    }
+}
+
+#[derive(InputObject, Default)]
+pub struct TodoFilterInput {
+    /// Only return items with this completion state
+    pub completed: Option<bool>,
+    /// Only return items whose title contains this text, ignoring case
+    pub search: Option<String>,
+}
+
+#[derive(InputObject)]
+pub struct CreateTodoInput {
+    pub title: String,
+    #[graphql(default)]
+    pub completed: bool,
+}
+
+#[derive(InputObject)]
+pub struct UpdateTodoInput {
+    pub title: Option<String>,
+    pub completed: Option<bool>,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum TodoChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct TodoChange {
+    pub kind: TodoChangeKind,
+    pub id: i64,
+    /// State after the change, null for deletions
+    pub todo: Option<Todo>,
+}
+
+impl From<TodoEvent> for TodoChange {
+    fn from(event: TodoEvent) -> Self {
+        let kind = match event.kind {
+            TodoEventKind::Created => TodoChangeKind::Created,
+            TodoEventKind::Updated => TodoChangeKind::Updated,
+            TodoEventKind::Deleted => TodoChangeKind::Deleted,
+        };
+        Self {
+            kind,
+            id: event.id,
+            todo: event.todo.map(Todo::from),
+        }
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn todos(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TodoFilterInput>,
+    ) -> async_graphql::Result<Vec<Todo>> {
+        let app_state = ctx.data::<AppState>()?;
+        let filter = filter.unwrap_or_default();
+        let todos = app_state
+            .todo_service
+            .get_all(
+                &app_state.db,
+                TodoFilter {
+                    completed: filter.completed,
+                    search: filter.search,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        Ok(todos.into_iter().map(Todo::from).collect())
+    }
+
+    async fn todo(&self, ctx: &Context<'_>, id: i64) -> async_graphql::Result<Option<Todo>> {
+        let app_state = ctx.data::<AppState>()?;
+        Ok(app_state
+            .todo_service
+            .get_by_id(&app_state.db, id)
+            .await
+            .ok()
+            .map(Todo::from))
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn create_todo(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateTodoInput,
+    ) -> async_graphql::Result<Todo> {
+        let app_state = ctx.data::<AppState>()?;
+        let request = TodoItemRequest {
+            id: None,
+            title: input.title,
+            completed: input.completed,
+            due_at: None,
+        };
+        request.validate_for_create().map_err(validation_error)?;
+
+        let todo = app_state.todo_service.create(&app_state.db, request).await?;
+        Ok(todo.into())
+    }
+
+    async fn update_todo(
+        &self,
+        ctx: &Context<'_>,
+        id: i64,
+        input: UpdateTodoInput,
+    ) -> async_graphql::Result<Todo> {
+        let app_state = ctx.data::<AppState>()?;
+        let current = app_state.todo_service.get_by_id(&app_state.db, id).await?;
+        let request = TodoItemRequest {
+            id: Some(id),
+            title: input.title.unwrap_or(current.title),
+            completed: input.completed.unwrap_or(current.completed),
+            due_at: None,
+        };
+        request.validate().map_err(validation_error)?;
+
+        let todo = app_state
+            .todo_service
+            .update(&app_state.db, id, request)
+            .await
+            .map_err(update_error)?;
+        Ok(todo.into())
+    }
+
+    /// Returns true when an item was deleted
+    async fn delete_todo(&self, ctx: &Context<'_>, id: i64) -> async_graphql::Result<bool> {
+        let app_state = ctx.data::<AppState>()?;
+        if app_state.todo_service.get_by_id(&app_state.db, id).await.is_err() {
+            return Ok(false);
+        }
+
+        app_state.todo_service.delete_by_id(&app_state.db, id).await?;
+        Ok(true)
+    }
+
+    async fn toggle_todo(&self, ctx: &Context<'_>, id: i64) -> async_graphql::Result<Todo> {
+        let app_state = ctx.data::<AppState>()?;
+        let todo = app_state.todo_service.toggle(&app_state.db, id).await.map_err(update_error)?;
+        Ok(todo.into())
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Streams every change made to todos, optionally narrowed to some kinds of change
+    async fn todo_changed(
+        &self,
+        ctx: &Context<'_>,
+        kinds: Option<Vec<TodoChangeKind>>,
+    ) -> async_graphql::Result<impl Stream<Item = TodoChange>> {
+        let app_state = ctx.data::<AppState>()?;
+        let receiver = app_state.events.subscribe();
+
+        Ok(BroadcastStream::new(receiver)
+            // Lagged subscribers skip the events they missed
+            .filter_map(|event| event.ok())
+            .map(TodoChange::from)
+            .filter(move |change| {
+                kinds
+                    .as_ref()
+                    .is_none_or(|kinds| kinds.contains(&change.kind))
+            }))
+    }
+}
+
+/// Exposes field errors under `extensions.fields`, mirroring the REST 422 body
+fn update_error(error: UpdateError) -> async_graphql::Error {
+    match error {
+        UpdateError::Transition(errors) => validation_error(errors),
+        UpdateError::NotFound(e) => async_graphql::Error::new(e),
+    }
+}
+
+fn validation_error(errors: ValidationErrors) -> async_graphql::Error {
+    let response = ValidationErrorResponse::from(errors);
+    async_graphql::Error::new(response.message).extend_with(|_, extensions| {
+        extensions.set("code", "VALIDATION_FAILED");
+        extensions.set(
+            "fields",
+            response
+                .errors
+                .iter()
+                .map(|error| {
+                    async_graphql::Value::from_json(serde_json::to_value(error).unwrap()).unwrap()
+                })
+                .collect::<Vec<async_graphql::Value>>(),
+        );
+    })
+}
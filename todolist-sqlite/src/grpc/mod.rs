@@ -0,0 +1,71 @@
+pub mod service;
+
+use tonic::transport::{Server, server::TcpIncoming};
+use tonic_health::ServingStatus;
+
+use crate::{grpc::service::TodoGrpcService, types::AppState, worker::Shutdown};
+
+/// Code generated from `proto/todo.proto`
+pub mod proto {
+    tonic::include_proto!("todo.v1");
+
+    /// Encoded descriptors served by the reflection service
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("todo_descriptor");
+}
+
+/// Serves the gRPC todo service, with health checking and optional reflection, on `addr`
+/// until shutdown is requested
+pub async fn serve(
+    addr: String,
+    app_state: AppState,
+    reflection: bool,
+    shutdown: Shutdown,
+) -> Result<(), String> {
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| format!("Failed to bind gRPC server to {}: {}", addr, e))?;
+    println!("🔌 gRPC server listening on {}", addr);
+
+    serve_with_incoming(TcpIncoming::from(listener), app_state, reflection, shutdown).await
+}
+
+/// Same as [`serve`] but on an already bound listener, so tests can pick a free port
+pub async fn serve_with_incoming(
+    incoming: TcpIncoming,
+    app_state: AppState,
+    reflection: bool,
+    mut shutdown: Shutdown,
+) -> Result<(), String> {
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    health_reporter
+        .set_serving::<proto::todo_service_server::TodoServiceServer<TodoGrpcService>>()
+        .await;
+
+    let reflection_service = reflection
+        .then(|| {
+            tonic_reflection::server::Builder::configure()
+                .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+                .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+                .build_v1()
+        })
+        .transpose()
+        .map_err(|e| format!("Failed to build gRPC reflection service: {}", e))?;
+
+    let todo_service = TodoGrpcService::new(app_state, shutdown.clone());
+    let signal = async move {
+        shutdown.requested().await;
+        health_reporter
+            .set_service_status("", ServingStatus::NotServing)
+            .await;
+    };
+
+    Server::builder()
+        .add_service(health_service)
+        .add_optional_service(reflection_service)
+        .add_service(proto::todo_service_server::TodoServiceServer::new(
+            todo_service,
+        ))
+        .serve_with_incoming_shutdown(incoming, signal)
+        .await
+        .map_err(|e| format!("gRPC server error: {}", e))
+}
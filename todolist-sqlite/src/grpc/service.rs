@@ -0,0 +1,204 @@
+use std::pin::Pin;
+
+use tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream};
+use tonic::{Code, Request, Response, Status};
+use tonic_types::{ErrorDetails, FieldViolation, StatusExt};
+use validator::{Validate, ValidationErrors};
+
+use crate::{
+    events::{TodoEvent, TodoEventKind},
+    grpc::proto::{
+        ChangeKind, CreateTodoRequest, DeleteTodoRequest, DeleteTodoResponse, GetTodoRequest,
+        ListTodosRequest, ListTodosResponse, Todo, TodoChange, UpdateTodoRequest,
+        WatchTodosRequest, todo_service_server::TodoService,
+    },
+    model::{
+        todo_filter::TodoFilter, todo_item_request::TodoItemRequest,
+        todo_item_response::TodoItemResponse, validation_error_response::ValidationErrorResponse,
+    },
+    service::todo_service::UpdateError,
+    types::AppState,
+    worker::Shutdown,
+};
+
+/// gRPC front end for the shared `TodoServiceInterface`
+pub struct TodoGrpcService {
+    app_state: AppState,
+    shutdown: Shutdown,
+}
+
+impl TodoGrpcService {
+    pub fn new(app_state: AppState, shutdown: Shutdown) -> Self {
+        Self {
+            app_state,
+            shutdown,
+        }
+    }
+
+    async fn find(&self, id: i64) -> Result<TodoItemResponse, Status> {
+        self.app_state
+            .todo_service
+            .get_by_id(&self.app_state.db, id)
+            .await
+            .map_err(Status::not_found)
+    }
+}
+
+type TodoChangeStream = Pin<Box<dyn Stream<Item = Result<TodoChange, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl TodoService for TodoGrpcService {
+    async fn create_todo(
+        &self,
+        request: Request<CreateTodoRequest>,
+    ) -> Result<Response<Todo>, Status> {
+        let request = request.into_inner();
+        let todo_item = TodoItemRequest {
+            id: None,
+            title: request.title,
+            completed: request.completed,
+            due_at: None,
+        };
+        todo_item.validate_for_create().map_err(invalid_argument)?;
+
+        let todo = self
+            .app_state
+            .todo_service
+            .create(&self.app_state.db, todo_item)
+            .await
+            .map_err(Status::internal)?;
+        Ok(Response::new(todo.into()))
+    }
+
+    async fn get_todo(&self, request: Request<GetTodoRequest>) -> Result<Response<Todo>, Status> {
+        let todo = self.find(request.into_inner().id).await?;
+        Ok(Response::new(todo.into()))
+    }
+
+    async fn list_todos(
+        &self,
+        request: Request<ListTodosRequest>,
+    ) -> Result<Response<ListTodosResponse>, Status> {
+        let request = request.into_inner();
+        let filter = TodoFilter {
+            completed: request.completed,
+            search: request.search,
+            ..Default::default()
+        };
+
+        let todos = self
+            .app_state
+            .todo_service
+            .get_all(&self.app_state.db, filter)
+            .await
+            .map_err(Status::internal)?;
+        Ok(Response::new(ListTodosResponse {
+            todos: todos.into_iter().map(Todo::from).collect(),
+        }))
+    }
+
+    async fn update_todo(
+        &self,
+        request: Request<UpdateTodoRequest>,
+    ) -> Result<Response<Todo>, Status> {
+        let request = request.into_inner();
+        let current = self.find(request.id).await?;
+        let todo_item = TodoItemRequest {
+            id: Some(request.id),
+            title: request.title.unwrap_or(current.title),
+            completed: request.completed.unwrap_or(current.completed),
+            due_at: None,
+        };
+        todo_item.validate().map_err(invalid_argument)?;
+
+        let todo = self
+            .app_state
+            .todo_service
+            .update(&self.app_state.db, request.id, todo_item)
+            .await
+            .map_err(|e| match e {
+                UpdateError::Transition(errors) => invalid_argument(errors),
+                UpdateError::NotFound(e) => Status::not_found(e),
+            })?;
+        Ok(Response::new(todo.into()))
+    }
+
+    async fn delete_todo(
+        &self,
+        request: Request<DeleteTodoRequest>,
+    ) -> Result<Response<DeleteTodoResponse>, Status> {
+        let id = request.into_inner().id;
+        self.find(id).await?;
+
+        self.app_state
+            .todo_service
+            .delete_by_id(&self.app_state.db, id)
+            .await
+            .map_err(Status::internal)?;
+        Ok(Response::new(DeleteTodoResponse {}))
+    }
+
+    type WatchTodosStream = TodoChangeStream;
+
+    async fn watch_todos(
+        &self,
+        request: Request<WatchTodosRequest>,
+    ) -> Result<Response<Self::WatchTodosStream>, Status> {
+        let kinds = request.into_inner().kinds().collect::<Vec<ChangeKind>>();
+        let receiver = self.app_state.events.subscribe();
+        let mut shutdown = self.shutdown.clone();
+
+        // Lagging watchers skip the events they missed rather than failing the stream,
+        // and every stream ends when the server shuts down so it can drain
+        let changes = BroadcastStream::new(receiver)
+            .filter_map(|event| event.ok())
+            .map(TodoChange::from)
+            .filter(move |change| kinds.is_empty() || kinds.contains(&change.kind()))
+            .map(Ok);
+        let changes =
+            futures_util::StreamExt::take_until(changes, async move { shutdown.requested().await });
+
+        Ok(Response::new(Box::pin(changes)))
+    }
+}
+
+impl From<TodoItemResponse> for Todo {
+    fn from(todo: TodoItemResponse) -> Self {
+        Self {
+            id: todo.id.unwrap_or_default(),
+            title: todo.title,
+            completed: todo.completed,
+        }
+    }
+}
+
+impl From<TodoEvent> for TodoChange {
+    fn from(event: TodoEvent) -> Self {
+        let kind = match event.kind {
+            TodoEventKind::Created => ChangeKind::Created,
+            TodoEventKind::Updated => ChangeKind::Updated,
+            TodoEventKind::Deleted => ChangeKind::Deleted,
+        };
+        Self {
+            kind: kind.into(),
+            id: event.id,
+            todo: event.todo.map(Todo::from),
+        }
+    }
+}
+
+/// Reports every field error as a `google.rpc.BadRequest` field violation
+fn invalid_argument(errors: ValidationErrors) -> Status {
+    let response = ValidationErrorResponse::from(errors);
+    let violations = response
+        .errors
+        .into_iter()
+        .map(|error| FieldViolation::new(error.field, error.message))
+        .collect::<Vec<FieldViolation>>();
+
+    Status::with_error_details(
+        Code::InvalidArgument,
+        response.message,
+        ErrorDetails::with_bad_request(violations),
+    )
+}
@@ -0,0 +1,17 @@
+pub mod api;
+pub mod app;
+pub mod config;
+pub mod controller;
+pub mod db;
+pub mod events;
+pub mod graphql;
+pub mod grpc;
+pub mod middleware;
+pub mod model;
+pub mod query;
+pub mod quick_add;
+pub mod routes;
+pub mod service;
+pub mod types;
+pub mod web;
+pub mod worker;
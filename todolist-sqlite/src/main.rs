@@ -1,34 +1,25 @@
-use std::sync::{Arc, Mutex};
-
-use crate::{config::{app_info}, service::todo_service, types::AppState};
-mod config;
-mod controller;
-mod routes;
-mod types;
-mod model;
-mod service;
-mod api;
+use axum_tutorial::{
+    api, app,
+    config::{app_info, settings::AppSettings},
+    db, routes,
+};
 
 #[tokio::main]
 async fn main() { 
     // Load configuration
-    let app_settings = config
-        ::settings
-        ::AppSettings::new().expect("Failed to load app settings");
+    let app_settings = AppSettings::new().expect("Failed to load app settings");
    
     let app_info = app_info::AppInfo::new();
-    let app_state = setup_app_state();
-    let app = routes::build_router(&app_settings, app_state);
-
-    api::start_server(app, &app_settings, &app_info).await;
-}
+    let app_state = app::build_state(&app_settings, db::open(&app_settings.database.path));
+    let workers = app::spawn_workers(&app_settings, &app_state);
+    let app = routes::build_router(&app_settings, app_state.clone());
 
-fn setup_app_state() -> AppState {
-    let connection = sqlite::open("data/todo.db").unwrap();
-    let todo_service = Arc::new(todo_service::TodoServiceImpl{});
+    api::start_server(app, &app_settings, &app_info, &workers).await;
 
-    AppState::new(
-         Arc::new(Mutex::new(connection)),
-        todo_service
-    )
-}
\ No newline at end of file
+    // Flush background workers, then fold the WAL back into the database file
+    workers.shutdown(app_settings.shutdown_timeout()).await;
+    if let Err(e) = db::checkpoint(&app_state.db) {
+        eprintln!("⚠️  {}", e);
+    }
+    println!("👋 Server stopped");
+}
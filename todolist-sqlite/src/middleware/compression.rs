@@ -0,0 +1,9 @@
+use tower_http::compression::CompressionLayer;
+
+use crate::config::settings::CompressionConfig;
+
+pub fn layer(config: &CompressionConfig) -> CompressionLayer {
+    CompressionLayer::new()
+        .gzip(config.gzip)
+        .br(config.brotli)
+}
@@ -0,0 +1,18 @@
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::config::settings::CorsConfig;
+
+/// Builds the CORS layer from config, which has already refused wildcards combined
+/// with credentials
+pub fn layer(config: &CorsConfig) -> CorsLayer {
+    let origins = match &config.allowed_origins {
+        None => AllowOrigin::any(),
+        Some(origins) => AllowOrigin::list(origins.clone()),
+    };
+
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(config.allowed_methods.clone())
+        .allow_headers(config.allowed_headers.clone())
+        .allow_credentials(config.allow_credentials)
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use axum::{
+    Router,
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue, header},
+    middleware::{self, Next},
+    response::Response,
+};
+use tower_http::set_header::SetResponseHeaderLayer;
+
+use crate::config::settings::ApiVersionConfig;
+
+/// Marks every response of a deprecated API version with `Deprecation` (RFC 9745),
+/// `Sunset` (RFC 8594) and, when there is one, a `Link` to the same path under
+/// `successor`, the successor version's base path. Versions that aren't deprecated
+/// are returned unchanged.
+pub fn apply<S>(router: Router<S>, config: &ApiVersionConfig, successor: Option<&str>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let Some(deprecated_at) = config.deprecated_at else {
+        return router;
+    };
+
+    let mut headers = vec![(
+        HeaderName::from_static("deprecation"),
+        HeaderValue::from_str(&format!("@{}", deprecated_at.timestamp())).unwrap(),
+    )];
+
+    if let Some(sunset_at) = config.sunset_at {
+        headers.push((
+            HeaderName::from_static("sunset"),
+            HeaderValue::from_str(&sunset_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+                .unwrap(),
+        ));
+    }
+
+    let router = headers
+        .into_iter()
+        .fold(router, |router, (name, value): (HeaderName, HeaderValue)| {
+            router.layer(SetResponseHeaderLayer::overriding(name, value))
+        });
+    match successor {
+        Some(successor) => {
+            let successor = Arc::<str>::from(successor);
+            router.layer(middleware::from_fn_with_state(successor, successor_link))
+        }
+        None => router,
+    }
+}
+
+/// Links to the successor's equivalent of the requested path, which the version router
+/// sees without its own prefix
+async fn successor_link(State(successor): State<Arc<str>>, req: Request, next: Next) -> Response {
+    let path = req.uri().path_and_query().map_or("/", |path| path.as_str());
+    let link = HeaderValue::from_str(&format!("<{}{}>; rel=\"successor-version\"", successor, path));
+
+    let mut response = next.run(req).await;
+    if let Ok(link) = link {
+        response.headers_mut().insert(header::LINK, link);
+    }
+    response
+}
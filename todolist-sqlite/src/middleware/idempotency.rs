@@ -0,0 +1,147 @@
+use axum::{
+    body::{Body, Bytes, to_bytes},
+    extract::{FromRequest, Request, State},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+use crate::{
+    config::settings::RateLimitConfig,
+    middleware::rate_limit::client_key,
+    service::idempotency_service::{Claim, StoredResponse},
+    types::AppState,
+};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Makes POST requests carrying an `Idempotency-Key` header safe to retry: the first
+/// response is stored and replayed for identical retries, while reusing the key with a
+/// different payload is rejected with 422. Keys are scoped to the client, identified as
+/// for rate limiting.
+pub async fn idempotency(
+    State((app_state, clients)): State<(AppState, Arc<RateLimitConfig>)>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if req.method() != Method::POST || is_multipart(&req) {
+        return next.run(req).await;
+    }
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(req).await;
+    };
+    let key = match key.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => {
+            digest(&[client_key(&clients, &req).as_bytes(), key.as_bytes()])
+        }
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("Idempotency-Key must be 1 to {} visible ASCII characters", MAX_KEY_LENGTH),
+            )
+                .into_response();
+        }
+    };
+
+    // Buffering through the `Bytes` extractor keeps `DefaultBodyLimit` in force
+    let (parts, body) = req.into_parts();
+    let body = match Bytes::from_request(Request::from_parts(parts.clone(), body), &()).await {
+        Ok(body) => body,
+        Err(rejection) => return rejection.into_response(),
+    };
+
+    let fingerprint = digest(&[
+        parts.method.as_str().as_bytes(),
+        parts.uri.path().as_bytes(),
+        parts.uri.query().unwrap_or_default().as_bytes(),
+        &body,
+    ]);
+
+    let service = &app_state.idempotency_service;
+    match service.claim(&app_state.db, &key, &fingerprint).await {
+        Ok(Claim::New) => {}
+        Ok(Claim::Replay(stored)) => return replay(stored),
+        Ok(Claim::InProgress) => {
+            return (
+                StatusCode::CONFLICT,
+                "A request with this Idempotency-Key is still being processed",
+            )
+                .into_response();
+        }
+        Ok(Claim::Mismatch) => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Idempotency-Key was already used with a different request",
+            )
+                .into_response();
+        }
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    // Server errors are not recorded so the client can retry with the same key
+    if response.status().is_server_error() {
+        let _ = service.release(&app_state.db, &key).await;
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            let _ = service.release(&app_state.db, &key).await;
+            return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+        }
+    };
+
+    let stored = StoredResponse {
+        status: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        body: body.to_vec(),
+    };
+    if let Err(e) = service.complete(&app_state.db, &key, stored).await {
+        eprintln!("⚠️  Failed to store idempotent response: {}", e);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Uploads run under their route's own body limit, which buffering here would undercut,
+/// so they are passed through without being recorded
+fn is_multipart(req: &Request) -> bool {
+    req.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("multipart/"))
+}
+
+/// SHA-256 of `parts`, each prefixed with its length so their boundaries can't shift
+fn digest(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let mut response = (status, stored.body).into_response();
+    let headers = response.headers_mut();
+
+    if let Some(content_type) = stored.content_type.and_then(|ct| HeaderValue::from_str(&ct).ok()) {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
@@ -0,0 +1,6 @@
+pub mod compression;
+pub mod cors;
+pub mod deprecation;
+pub mod idempotency;
+pub mod rate_limit;
+pub mod security_headers;
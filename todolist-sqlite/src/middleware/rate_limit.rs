@@ -0,0 +1,155 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::config::settings::RateLimitConfig;
+
+/// Clients tracked at most; the one seen least recently is dropped to make room
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+    /// Position in [`Buckets::by_last_seen`]
+    seen: u64,
+}
+
+/// Buckets with an index by when they were last used, to evict the oldest in O(log n)
+#[derive(Default)]
+struct Buckets {
+    by_client: HashMap<String, Bucket>,
+    by_last_seen: BTreeMap<u64, String>,
+    next_seen: u64,
+}
+
+pub enum Decision {
+    Allowed { remaining: u64, reset_secs: u64 },
+    Limited { retry_after_secs: u64 },
+}
+
+/// Token-bucket rate limiter keyed by API identity or client IP
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    max_clients: usize,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self::with_max_clients(config, MAX_TRACKED_CLIENTS)
+    }
+
+    pub fn with_max_clients(config: RateLimitConfig, max_clients: usize) -> Self {
+        Self {
+            config,
+            max_clients: max_clients.max(1),
+            buckets: Mutex::new(Buckets::default()),
+        }
+    }
+
+    /// Number of clients with a bucket
+    pub fn tracked_clients(&self) -> usize {
+        self.buckets.lock().unwrap().by_client.len()
+    }
+
+    /// Takes a token from the client's bucket if it has one left
+    pub fn check(&self, client: &str) -> Decision {
+        let capacity = self.config.burst as f64;
+        let rate = self.config.refill_per_sec;
+        let now = Instant::now();
+
+        let mut guard = self.buckets.lock().unwrap();
+        let buckets = &mut *guard;
+        let seen = buckets.next_seen;
+        buckets.next_seen += 1;
+        if !buckets.by_client.contains_key(client)
+            && buckets.by_client.len() >= self.max_clients
+            && let Some((_, oldest)) = buckets.by_last_seen.pop_first()
+        {
+            buckets.by_client.remove(&oldest);
+        }
+
+        let bucket = buckets.by_client.entry(client.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            last_refill: now,
+            seen,
+        });
+        buckets.by_last_seen.remove(&bucket.seen);
+        buckets.by_last_seen.insert(seen, client.to_string());
+        bucket.seen = seen;
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Decision::Allowed {
+                remaining: bucket.tokens.floor() as u64,
+                reset_secs: ((capacity - bucket.tokens) / rate).ceil() as u64,
+            }
+        } else {
+            Decision::Limited {
+                retry_after_secs: ((1.0 - bucket.tokens) / rate).ceil() as u64,
+            }
+        }
+    }
+}
+
+/// Who sent a request: the configured key in the identity header when there is one,
+/// otherwise the peer IP
+pub fn client_key(config: &RateLimitConfig, req: &Request) -> String {
+    let identity = req
+        .headers()
+        .get(config.identity_header.as_str())
+        .and_then(|value| value.to_str().ok())
+        .filter(|identity| config.api_keys.iter().any(|key| key == identity));
+
+    match identity {
+        Some(identity) => format!("id:{}", identity),
+        None => req
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| format!("ip:{}", addr.ip()))
+            .unwrap_or_else(|| "ip:unknown".to_string()),
+    }
+}
+
+pub async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let limit = limiter.config.burst;
+
+    match limiter.check(&client_key(&limiter.config, &req)) {
+        Decision::Allowed { remaining, reset_secs } => {
+            let mut response = next.run(req).await;
+            insert_rate_limit_headers(response.headers_mut(), limit, remaining, reset_secs);
+            response
+        }
+        Decision::Limited { retry_after_secs } => {
+            let mut response = (StatusCode::TOO_MANY_REQUESTS, "Too many requests").into_response();
+            let headers = response.headers_mut();
+            insert_rate_limit_headers(headers, limit, 0, retry_after_secs);
+            headers.insert("retry-after", HeaderValue::from(retry_after_secs));
+            response
+        }
+    }
+}
+
+fn insert_rate_limit_headers(headers: &mut HeaderMap, limit: u64, remaining: u64, reset_secs: u64) {
+    headers.insert("ratelimit-limit", HeaderValue::from(limit));
+    headers.insert("ratelimit-remaining", HeaderValue::from(remaining));
+    headers.insert("ratelimit-reset", HeaderValue::from(reset_secs));
+}
@@ -0,0 +1,42 @@
+use axum::{
+    Router,
+    http::{HeaderName, HeaderValue, header},
+};
+use tower_http::set_header::SetResponseHeaderLayer;
+
+use crate::config::settings::SecurityHeadersConfig;
+
+/// Adds the security headers to every response that doesn't already set them.
+/// HSTS is skipped when `hsts_max_age_secs` is 0, e.g. while serving plain HTTP.
+pub fn apply<S>(router: Router<S>, config: &SecurityHeadersConfig) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let mut headers = vec![
+        (header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+        (header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY")),
+        (header::REFERRER_POLICY, HeaderValue::from_static("no-referrer")),
+        (
+            header::CONTENT_SECURITY_POLICY,
+            HeaderValue::from_str(&config.content_security_policy)
+                .expect("Invalid Content-Security-Policy"),
+        ),
+    ];
+
+    if config.hsts_max_age_secs > 0 {
+        headers.push((
+            header::STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_str(&format!(
+                "max-age={}; includeSubDomains",
+                config.hsts_max_age_secs
+            ))
+            .unwrap(),
+        ));
+    }
+
+    headers
+        .into_iter()
+        .fold(router, |router, (name, value): (HeaderName, HeaderValue)| {
+            router.layer(SetResponseHeaderLayer::if_not_present(name, value))
+        })
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    Completed,
+    Reopened,
+    Renamed,
+    Tagged,
+    Untagged,
+    /// Moved between workflow states without being completed or reopened
+    Transitioned,
+}
+
+impl ActivityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityKind::Completed => "completed",
+            ActivityKind::Reopened => "reopened",
+            ActivityKind::Renamed => "renamed",
+            ActivityKind::Tagged => "tagged",
+            ActivityKind::Untagged => "untagged",
+            ActivityKind::Transitioned => "transitioned",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "completed" => Some(ActivityKind::Completed),
+            "reopened" => Some(ActivityKind::Reopened),
+            "renamed" => Some(ActivityKind::Renamed),
+            "tagged" => Some(ActivityKind::Tagged),
+            "untagged" => Some(ActivityKind::Untagged),
+            "transitioned" => Some(ActivityKind::Transitioned),
+            _ => None,
+        }
+    }
+}
+
+/// A change to a todo item recorded by the system
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct ActivityResponse {
+    #[schema(example = "renamed")]
+    pub kind: ActivityKind,
+
+    /// Previous title for renames, the tag removed when untagged, the state left when transitioned
+    #[schema(example = "Buy milk")]
+    pub from: Option<String>,
+
+    /// New title for renames, the tag added when tagged, the state entered when transitioned
+    #[schema(example = "Buy oat milk")]
+    pub to: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Metadata of a file attached to a todo item
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct AttachmentResponse {
+    /// Unique identifier of the attachment
+    #[schema(example = 1)]
+    pub id: i64,
+
+    /// Todo item the file is attached to
+    #[schema(example = 1)]
+    pub todo_id: i64,
+
+    /// Name of the file as uploaded
+    #[schema(example = "receipt.pdf")]
+    pub file_name: String,
+
+    #[schema(example = "application/pdf")]
+    pub content_type: String,
+
+    /// Size in bytes
+    #[schema(example = 48213)]
+    pub size: i64,
+
+    /// SHA-256 of the content, also sent as the download's ETag
+    #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")]
+    pub sha256: String,
+
+    pub created_at: DateTime<Utc>,
+}
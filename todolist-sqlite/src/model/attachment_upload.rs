@@ -0,0 +1,10 @@
+use utoipa::ToSchema;
+
+/// Multipart form for uploading attachments
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct AttachmentUpload {
+    /// One or more files, each sent as a `file` part
+    #[schema(value_type = Vec<String>, format = Binary)]
+    pub file: Vec<Vec<u8>>,
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A snapshot of the database in `backup.dir`
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct BackupResponse {
+    /// File name, used to restore the snapshot
+    #[schema(example = "todo-20261019-083000-125.db")]
+    pub name: String,
+
+    #[schema(example = 24576)]
+    pub size_bytes: u64,
+
+    #[schema(example = "2026-10-19T08:30:00.125Z")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outcome of restoring a snapshot
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct RestoreResponse {
+    /// Snapshot now in use
+    pub restored: BackupResponse,
+
+    /// Schema version of the snapshot, before pending migrations were applied to it
+    #[schema(example = 7)]
+    pub schema_version: i64,
+
+    /// Snapshot of the database as it was just before the restore, to undo it
+    pub previous: BackupResponse,
+}
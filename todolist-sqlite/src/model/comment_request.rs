@@ -0,0 +1,21 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+/// Request model for writing or editing a comment
+#[derive(Serialize, Deserialize, Default, Clone, Debug, utoipa::ToSchema, Validate)]
+pub struct CommentRequest {
+    /// Markdown text; `@username` mentions outside of code are recorded
+    #[schema(example = "Ordered the milk, @bob can you pick it up?", min_length = 1, max_length = 10000)]
+    #[validate(length(min = 1, max = 10000), custom(function = "validate_not_blank"))]
+    pub body: String,
+}
+
+fn validate_not_blank(body: &str) -> Result<(), ValidationError> {
+    if body.trim().is_empty() {
+        return Err(ValidationError::new("blank")
+            .with_message(Cow::from("body must not be blank")));
+    }
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A comment written on a todo item
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct CommentResponse {
+    /// Unique identifier of the comment
+    #[schema(example = 1)]
+    pub id: i64,
+
+    /// Todo item the comment belongs to
+    #[schema(example = 1)]
+    pub todo_id: i64,
+
+    /// Username of the author, the only one allowed to edit or delete it
+    #[schema(example = "alice")]
+    pub author: String,
+
+    /// Markdown text as written
+    #[schema(example = "Ordered the milk, @bob can you pick it up?")]
+    pub body: String,
+
+    /// The body rendered as HTML, with any raw HTML escaped
+    #[schema(example = "<p>Ordered the milk, @bob can you pick it up?</p>\n")]
+    pub body_html: String,
+
+    /// Usernames mentioned in the body, sorted
+    #[schema(example = json!(["bob"]))]
+    pub mentions: Vec<String>,
+
+    pub created_at: DateTime<Utc>,
+
+    /// When the body was last changed, if ever
+    pub edited_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,68 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{ValidationError, ValidationErrors};
+
+use crate::model::{todo_item_response::TodoItemResponse, v2::todo_response::TodoResponse};
+
+/// Request model for blocking a todo item by another one
+#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema)]
+pub struct DependencyRequest {
+    /// ID of the todo item that has to be completed first
+    #[schema(example = 2)]
+    pub blocked_by: i64,
+}
+
+impl DependencyRequest {
+    /// The blocking todo item doesn't exist
+    pub fn unknown(&self) -> ValidationErrors {
+        self.error(
+            ValidationError::new("not_found")
+                .with_message(Cow::from(format!("Todo item with id {} not found", self.blocked_by))),
+        )
+    }
+
+    /// Adding the dependency would close `path`, each todo item blocked by the next
+    pub fn cycle(&self, path: &[i64]) -> ValidationErrors {
+        let ids: Vec<String> = path.iter().map(i64::to_string).collect();
+        let mut error = ValidationError::new("cycle").with_message(Cow::from(format!(
+            "would create a cycle: {}",
+            ids.join(" → ")
+        )));
+        error.add_param(Cow::from("path"), &path);
+        self.error(error)
+    }
+
+    fn error(&self, error: ValidationError) -> ValidationErrors {
+        let mut errors = ValidationErrors::new();
+        errors.add("blocked_by", error);
+        errors
+    }
+}
+
+/// Why a dependency could not be added
+#[derive(Debug, PartialEq, Eq)]
+pub enum DependencyError {
+    /// Todo ids of the cycle it would close, each blocked by the next
+    Cycle(Vec<i64>),
+}
+
+/// The todo items a todo item waits on, and those waiting on it
+#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema)]
+pub struct DependenciesResponse {
+    /// Todo items to complete first, in manual order
+    pub blocked_by: Vec<TodoResponse>,
+
+    /// Todo items blocked by this one, in manual order
+    pub blocks: Vec<TodoResponse>,
+}
+
+impl DependenciesResponse {
+    pub fn new(blocked_by: Vec<TodoItemResponse>, blocks: Vec<TodoItemResponse>) -> Self {
+        Self {
+            blocked_by: blocked_by.into_iter().map(TodoResponse::from).collect(),
+            blocks: blocks.into_iter().map(TodoResponse::from).collect(),
+        }
+    }
+}
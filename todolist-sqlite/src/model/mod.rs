@@ -1,2 +1,21 @@
+pub mod activity_response;
+pub mod attachment_response;
+pub mod attachment_upload;
+pub mod backup_response;
+pub mod comment_request;
+pub mod comment_response;
+pub mod dependency;
+pub mod move_request;
+pub mod quick_add;
+pub mod smart_list;
+pub mod tag;
+pub mod thread_entry;
+pub mod time_entry;
+pub mod todo_filter;
 pub mod todo_item_request;
-pub mod todo_item_response;
\ No newline at end of file
+pub mod todo_item_response;
+pub mod todo_stats;
+pub mod todo_sync;
+pub mod v2;
+pub mod validation_error_response;
+pub mod workflow;
@@ -0,0 +1,77 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use validator::{ValidationError, ValidationErrors};
+
+use crate::db::ordering::Placement;
+
+/// Request model for moving a todo item next to another one
+///
+/// Exactly one of `before` and `after` must be set
+#[derive(Serialize, Deserialize, Default, Clone, Debug, utoipa::ToSchema)]
+pub struct MoveRequest {
+    /// Place the item right before the todo item with this id
+    #[schema(example = 2)]
+    pub before: Option<i64>,
+
+    /// Place the item right after the todo item with this id
+    #[schema(example = json!(null))]
+    pub after: Option<i64>,
+}
+
+impl MoveRequest {
+    /// Validates the request for moving item `id` and tells where it goes
+    pub fn placement(&self, id: i64) -> Result<Placement, ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let placement = match (self.before, self.after) {
+            (Some(before), None) => Some(("before", Placement::Before(before))),
+            (None, Some(after)) => Some(("after", Placement::After(after))),
+            (Some(_), Some(_)) => {
+                errors.add(
+                    "after",
+                    ValidationError::new("exclusive")
+                        .with_message(Cow::from("set either before or after, not both")),
+                );
+                None
+            }
+            (None, None) => {
+                errors.add(
+                    "before",
+                    ValidationError::new("required")
+                        .with_message(Cow::from("set either before or after")),
+                );
+                None
+            }
+        };
+
+        match placement {
+            Some((field, placement)) if placement.target() == id => {
+                errors.add(
+                    field,
+                    ValidationError::new("self")
+                        .with_message(Cow::from("a todo item can't be moved next to itself")),
+                );
+                Err(errors)
+            }
+            Some((_, placement)) => Ok(placement),
+            None => Err(errors),
+        }
+    }
+}
+
+/// Reports a move target that doesn't exist in the shape of a validation error
+pub fn unknown_target(placement: Placement) -> ValidationErrors {
+    let field = match placement {
+        Placement::Before(_) => "before",
+        Placement::After(_) => "after",
+    };
+    let mut errors = ValidationErrors::new();
+    errors.add(
+        field,
+        ValidationError::new("not_found").with_message(Cow::from(format!(
+            "todo item {} not found",
+            placement.target()
+        ))),
+    );
+    errors
+}
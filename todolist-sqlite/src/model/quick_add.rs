@@ -0,0 +1,128 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::{
+    config::settings::QuickAddConfig,
+    model::todo_item_request::TodoItemRequest,
+    quick_add::{ParseOptions, Priority, QuickAdd, QuickAddMatch, Recurrence},
+};
+
+/// Text to read a todo item from
+#[derive(Serialize, Deserialize, Default, Clone, Debug, utoipa::ToSchema, Validate)]
+pub struct QuickAddRequest {
+    /// Title with the due date, tags, priority and recurrence written into it
+    #[schema(example = "Pay rent tomorrow 9am #home !high every month", min_length = 1, max_length = 500)]
+    #[validate(length(min = 1, max = 500))]
+    pub text: String,
+
+    /// IANA timezone the dates and times are written in, the server's default when omitted
+    #[serde(default)]
+    #[schema(example = "Europe/Madrid")]
+    pub timezone: Option<String>,
+}
+
+impl QuickAddRequest {
+    /// Validates the request and resolves its timezone
+    pub fn options(
+        &self,
+        config: &QuickAddConfig,
+        now: DateTime<Utc>,
+    ) -> Result<ParseOptions, ValidationErrors> {
+        let mut errors = self.validate().err().unwrap_or_default();
+
+        let timezone = match &self.timezone {
+            None => config.timezone,
+            Some(name) => name.parse::<Tz>().unwrap_or_else(|_| {
+                errors.add(
+                    "timezone",
+                    ValidationError::new("unknown_timezone")
+                        .with_message(Cow::from(format!("{} is not an IANA timezone", name))),
+                );
+                config.timezone
+            }),
+        };
+
+        if errors.is_empty() {
+            Ok(ParseOptions {
+                timezone,
+                default_time: config.default_time,
+                now,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// What was read from a quick-add text, for the client to confirm
+///
+/// Nothing is saved; posting `todo` to the todo endpoint creates the item, and posting its
+/// `tags` to the item's tags endpoint tags it.
+#[derive(Serialize, Deserialize, Clone, Debug, utoipa::ToSchema)]
+pub struct QuickAddResponse {
+    /// The text without the recognised phrases
+    #[schema(example = "Pay rent")]
+    pub title: String,
+
+    #[schema(example = "2026-10-20T09:00:00Z")]
+    pub due_at: Option<DateTime<Utc>>,
+
+    /// Lowercase tag names without `#`
+    #[schema(example = json!(["home"]))]
+    pub tags: Vec<String>,
+
+    /// Not stored on todo items, so only returned here
+    pub priority: Option<Priority>,
+
+    /// Not stored on todo items, so only returned here
+    pub recurrence: Option<Recurrence>,
+
+    /// Recognised phrases in the order they appear, as typed
+    pub matches: Vec<QuickAddMatch>,
+
+    /// Timezone the text was read in
+    #[schema(example = "UTC")]
+    pub timezone: String,
+
+    /// The todo item to create once confirmed
+    pub todo: QuickAddTodo,
+}
+
+/// A todo item to create, with the tags to attach to it
+#[derive(Serialize, Deserialize, Clone, Debug, utoipa::ToSchema)]
+pub struct QuickAddTodo {
+    #[serde(flatten)]
+    pub item: TodoItemRequest,
+
+    /// Ignored by the todo endpoint; attached through `/todo/{id}/tags` once created
+    #[schema(example = json!(["home"]))]
+    pub tags: Vec<String>,
+}
+
+impl QuickAddResponse {
+    pub fn new(parsed: QuickAdd, timezone: Tz) -> Self {
+        let todo = QuickAddTodo {
+            item: TodoItemRequest {
+                id: None,
+                title: parsed.title.clone(),
+                completed: false,
+                due_at: Some(parsed.due_at),
+            },
+            tags: parsed.tags.clone(),
+        };
+        Self {
+            title: parsed.title,
+            due_at: parsed.due_at,
+            tags: parsed.tags,
+            priority: parsed.priority,
+            recurrence: parsed.recurrence,
+            matches: parsed.matches,
+            timezone: timezone.name().to_string(),
+            todo,
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::query;
+
+/// A saved search query over the todo list
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq, ToSchema)]
+pub struct SmartListResponse {
+    /// Unique identifier of the smart list
+    #[schema(example = 1)]
+    pub id: i64,
+
+    /// Unique name, compared ignoring case
+    #[schema(example = "Work this month")]
+    pub name: String,
+
+    /// Query in the syntax of the `q` filter of the todo list
+    #[schema(example = "completed:false tag:work due<2026-11-01")]
+    pub query: String,
+}
+
+/// Request model for creating or changing a smart list
+#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema, Validate)]
+pub struct SmartListRequest {
+    #[schema(example = "Work this month", min_length = 1, max_length = 100)]
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+
+    /// Query in the syntax of the `q` filter of the todo list; relative days such as
+    /// `today` are resolved each time the list is read
+    #[schema(example = "completed:false tag:work due<2026-11-01", max_length = 500)]
+    #[validate(length(max = 500), custom(function = "validate_query"))]
+    pub query: String,
+}
+
+fn validate_query(text: &str) -> Result<(), ValidationError> {
+    query::parse(text).map(|_| ()).map_err(|e| e.to_validation_error())
+}
+
+/// Reports a smart list name used by another list in the shape of a validation error
+pub fn name_taken(name: &str) -> ValidationErrors {
+    let mut errors = ValidationErrors::new();
+    errors.add(
+        "name",
+        ValidationError::new("taken").with_message(Cow::from(format!(
+            "a smart list named {} already exists",
+            name
+        ))),
+    );
+    errors
+}
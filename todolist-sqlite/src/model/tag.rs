@@ -0,0 +1,157 @@
+use std::{borrow::Cow, collections::BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+/// Colour of tags created without one, including those created by tagging a todo
+pub const DEFAULT_TAG_COLOR: &str = "#6b7280";
+
+/// Most tags added to a todo item in one request
+pub const MAX_TAGS_PER_REQUEST: usize = 20;
+
+/// A label todo items can be filtered by
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq, ToSchema)]
+pub struct TagResponse {
+    /// Unique identifier of the tag
+    #[schema(example = 1)]
+    pub id: i64,
+
+    /// Unique name, compared ignoring case
+    #[schema(example = "work")]
+    pub name: String,
+
+    /// Colour as `#rrggbb`
+    #[schema(example = "#2563eb")]
+    pub color: String,
+}
+
+/// Why a tag could not be created or updated
+#[derive(Debug, PartialEq, Eq)]
+pub enum TagError {
+    NotFound(String),
+    /// Name of the other tag that already has the name, ignoring case
+    Taken(String),
+    Failed(String),
+}
+
+/// Request model for creating, renaming or recolouring a tag
+#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema, Validate)]
+pub struct TagRequest {
+    /// Letters, digits, `-` and `_`, not only digits
+    #[schema(example = "work", min_length = 1, max_length = 50, pattern = r"^[\p{L}\p{N}_-]+$")]
+    #[validate(length(min = 1, max = 50), custom(function = "validate_tag_name"))]
+    pub name: String,
+
+    /// Colour as `#rrggbb`; grey for new tags and unchanged on update when omitted
+    #[serde(default)]
+    #[schema(example = "#2563eb", pattern = "^#[0-9a-fA-F]{6}$")]
+    #[validate(custom(function = "validate_color"))]
+    pub color: Option<String>,
+}
+
+/// Request model for merging a tag into another one
+#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema)]
+pub struct TagMergeRequest {
+    /// Tag that takes over the todo items of the merged one
+    #[schema(example = 2)]
+    pub into: i64,
+}
+
+/// Request model for tagging a todo item
+#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema, Validate)]
+pub struct TodoTagsRequest {
+    /// Names of the tags to add; tags that don't exist yet are created
+    #[schema(example = json!(["work", "urgent"]), min_items = 1, max_items = 20)]
+    #[validate(length(min = 1, max = 20), custom(function = "validate_tag_names"))]
+    pub tags: Vec<String>,
+}
+
+impl TodoTagsRequest {
+    /// The names without repeats, ignoring case
+    pub fn names(&self) -> Vec<String> {
+        let mut seen = BTreeSet::new();
+        self.tags
+            .iter()
+            .filter(|name| seen.insert(name.to_lowercase()))
+            .cloned()
+            .collect()
+    }
+}
+
+/// How the `tag` filter of the todo list combines several tags
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TagMatch {
+    /// Items with at least one of the tags
+    #[default]
+    Any,
+    /// Items with every one of the tags
+    All,
+}
+
+/// Letters, digits, `-` and `_`, not only digits so names never look like ids
+pub fn is_tag_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().count() <= 50
+        && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        && !name.chars().all(|c| c.is_ascii_digit())
+}
+
+fn validate_tag_name(name: &str) -> Result<(), ValidationError> {
+    // The length has its own rule
+    let length = name.chars().count();
+    if (1..=50).contains(&length) && !is_tag_name(name) {
+        return Err(ValidationError::new("tag_name").with_message(Cow::from(
+            "name must be letters, digits, '-' and '_', and not only digits",
+        )));
+    }
+    Ok(())
+}
+
+fn validate_tag_names(names: &[String]) -> Result<(), ValidationError> {
+    match names.iter().find(|name| !is_tag_name(name)) {
+        Some(name) => Err(ValidationError::new("tag_name").with_message(Cow::from(format!(
+            "{:?} is not a tag name: use 1 to 50 letters, digits, '-' and '_', not only digits",
+            name
+        )))),
+        None => Ok(()),
+    }
+}
+
+fn validate_color(color: &str) -> Result<(), ValidationError> {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(ValidationError::new("color")
+            .with_message(Cow::from("color must be a hex colour like #2563eb")));
+    }
+    Ok(())
+}
+
+/// Reports a tag name used by another tag in the shape of a validation error
+pub fn name_taken(name: &str) -> ValidationErrors {
+    let mut errors = ValidationErrors::new();
+    errors.add(
+        "name",
+        ValidationError::new("taken").with_message(Cow::from(format!(
+            "a tag named {} already exists; merge the tags instead",
+            name
+        ))),
+    );
+    errors
+}
+
+/// Reports a merge target that can't take the tag's items
+pub fn invalid_merge_target(id: i64, into: i64) -> ValidationErrors {
+    let error = if id == into {
+        ValidationError::new("self").with_message(Cow::from("a tag can't be merged into itself"))
+    } else {
+        ValidationError::new("not_found")
+            .with_message(Cow::from(format!("tag {} not found", into)))
+    };
+    let mut errors = ValidationErrors::new();
+    errors.add("into", error);
+    errors
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::model::{activity_response::ActivityResponse, comment_response::CommentResponse};
+
+/// One entry of a todo item's thread, told apart by `type`
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ThreadEntry {
+    Comment(CommentResponse),
+    Activity(ActivityResponse),
+}
@@ -0,0 +1,231 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::model::todo_stats::StatsFormat;
+
+/// Most days a single time report may span
+const MAX_REPORT_DAYS: i64 = 366;
+
+/// Time spent on a todo item, from a timer or entered by hand
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, ToSchema)]
+pub struct TimeEntryResponse {
+    #[schema(example = 1)]
+    pub id: i64,
+
+    #[schema(example = 1)]
+    pub todo_id: i64,
+
+    /// Who tracked the time
+    #[schema(example = "alice")]
+    pub user: String,
+
+    #[schema(example = "2026-10-19T09:00:00Z")]
+    pub started_at: DateTime<Utc>,
+
+    /// When the time stopped; `null` while the timer runs
+    #[schema(example = "2026-10-19T10:30:00Z")]
+    pub ended_at: Option<DateTime<Utc>>,
+
+    /// Seconds tracked, up to now for a running timer
+    #[schema(example = 5400)]
+    pub duration_secs: i64,
+
+    #[schema(example = "Call with the client")]
+    pub note: Option<String>,
+}
+
+/// Request model for entering time by hand
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema, Validate)]
+pub struct TimeEntryRequest {
+    #[schema(example = "2026-10-19T09:00:00Z")]
+    pub started_at: DateTime<Utc>,
+
+    /// After `started_at`, and not in the future
+    #[schema(example = "2026-10-19T10:30:00Z")]
+    pub ended_at: DateTime<Utc>,
+
+    #[serde(default)]
+    #[schema(example = "Call with the client", max_length = 500)]
+    #[validate(length(max = 500))]
+    pub note: Option<String>,
+}
+
+impl TimeEntryRequest {
+    /// Validates the fields, and that the entry ends after it starts and by `now`
+    pub fn check(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = self.validate().err().unwrap_or_default();
+        if self.ended_at <= self.started_at {
+            errors.add(
+                "ended_at",
+                ValidationError::new("range")
+                    .with_message(Cow::from("ended_at must be after started_at")),
+            );
+        } else if self.ended_at > now {
+            errors.add(
+                "ended_at",
+                ValidationError::new("future").with_message(Cow::from(
+                    "ended_at must not be in the future; start a timer instead",
+                )),
+            );
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// What the rows of a time report add up
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeGroup {
+    #[default]
+    Todo,
+    /// Time on items with several tags counts for each of them
+    Tag,
+    /// Entries running past midnight (UTC) are split between the days
+    Day,
+}
+
+/// Query parameters of a time report
+#[derive(Serialize, Deserialize, Default, Clone, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TimeReportQuery {
+    /// First day of the range (UTC), 30 days before `to` when omitted
+    #[param(example = "2026-10-01")]
+    pub from: Option<NaiveDate>,
+
+    /// Last day of the range (UTC), today when omitted
+    #[param(example = "2026-10-31")]
+    pub to: Option<NaiveDate>,
+
+    /// Rows per `todo`, `tag` or `day`, `todo` when omitted
+    #[param(inline)]
+    pub group_by: Option<TimeGroup>,
+
+    /// Only count the time of this user
+    #[param(example = "alice")]
+    pub user: Option<String>,
+
+    /// `csv` downloads the rows as a CSV file instead
+    #[param(inline)]
+    pub format: Option<StatsFormat>,
+}
+
+/// Validated range and grouping of a time report
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeReportRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub group_by: TimeGroup,
+    pub user: Option<String>,
+}
+
+impl TimeReportRange {
+    /// Start of `from` and of the day after `to`, in seconds
+    pub fn bounds(&self) -> (i64, i64) {
+        let start = |day: NaiveDate| day.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
+        (start(self.from), start(self.to + Days::new(1)))
+    }
+}
+
+impl TimeReportQuery {
+    pub fn range(&self, today: NaiveDate) -> Result<TimeReportRange, ValidationErrors> {
+        let to = self.to.unwrap_or(today);
+        let from = self.from.unwrap_or(to - Days::new(29));
+
+        let mut errors = ValidationErrors::new();
+        if from > to {
+            errors.add(
+                "from",
+                ValidationError::new("range").with_message(Cow::from("from must not be after to")),
+            );
+        } else if (to - from).num_days() >= MAX_REPORT_DAYS {
+            errors.add(
+                "to",
+                ValidationError::new("range").with_message(Cow::from(format!(
+                    "a report spans at most {} days",
+                    MAX_REPORT_DAYS
+                ))),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(TimeReportRange {
+                from,
+                to,
+                group_by: self.group_by.unwrap_or_default(),
+                user: self.user.as_ref().map(|user| user.to_lowercase()),
+            })
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Time tracked for one todo item, tag or day
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, ToSchema)]
+pub struct TimeReportRow {
+    /// Todo id, tag name or ISO day; empty for time on untagged items
+    #[schema(example = "1")]
+    pub key: String,
+
+    /// Todo title, tag name or ISO day
+    #[schema(example = "Write release notes")]
+    pub label: String,
+
+    #[schema(example = 5400)]
+    pub seconds: i64,
+}
+
+/// Tracked time within a range, running timers counted up to now
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct TimeReportResponse {
+    #[schema(example = "2026-10-01")]
+    pub from: NaiveDate,
+
+    #[schema(example = "2026-10-31")]
+    pub to: NaiveDate,
+
+    pub group_by: TimeGroup,
+
+    /// Seconds tracked in the range, each counted once
+    #[schema(example = 9000)]
+    pub total_secs: i64,
+
+    /// Most time first; days in order when grouped by day
+    pub rows: Vec<TimeReportRow>,
+}
+
+impl TimeReportResponse {
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("key,label,seconds,hours\n");
+        for row in &self.rows {
+            csv.push_str(&format!(
+                "{},{},{},{:.2}\n",
+                csv_field(&row.key),
+                csv_field(&row.label),
+                row.seconds,
+                row.seconds as f64 / 3600.0
+            ));
+        }
+        csv
+    }
+}
+
+/// Quotes a field holding a separator, quote or line break. Titles starting like a formula
+/// get a leading `'` so spreadsheets show them as text.
+fn csv_field(value: &str) -> Cow<'_, str> {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        Cow::from(format!("'{}", value))
+    } else {
+        Cow::from(value)
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::from(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        value
+    }
+}
@@ -0,0 +1,55 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+use validator::{ValidationError, ValidationErrors};
+
+use crate::{model::tag::TagMatch, query};
+
+/// Query parameters narrowing down the todo list
+#[derive(Serialize, Deserialize, Default, Clone, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TodoFilter {
+    /// Only return items with this completion state
+    #[param(example = false)]
+    pub completed: Option<bool>,
+
+    /// Only return items whose title contains this text, ignoring case
+    #[param(example = "groceries")]
+    pub search: Option<String>,
+
+    /// Only return items with these tags, repeated for several: `?tag=work&tag=urgent`
+    #[serde(default)]
+    #[param(example = json!(["work", "urgent"]))]
+    pub tag: Vec<String>,
+
+    /// Whether items need `any` of the tags or `all` of them, `any` when omitted
+    #[param(inline)]
+    pub tag_match: Option<TagMatch>,
+
+    /// Search query, e.g. `completed:false tag:work due<2026-11-01 "release notes"`;
+    /// see the README for the syntax
+    #[param(example = "completed:false tag:work due<2026-11-01", max_length = 500)]
+    pub q: Option<String>,
+}
+
+impl TodoFilter {
+    /// Checks `q` is at most [`query::MAX_LENGTH`] characters and parses
+    pub fn check(&self) -> Result<(), ValidationErrors> {
+        let Some(q) = self.q.as_deref() else {
+            return Ok(());
+        };
+        if q.chars().count() > query::MAX_LENGTH {
+            let mut errors = ValidationErrors::new();
+            errors.add(
+                "q",
+                ValidationError::new("length").with_message(Cow::from(format!(
+                    "q must be at most {} characters",
+                    query::MAX_LENGTH
+                ))),
+            );
+            return Err(errors);
+        }
+        query::parse(q).map(|_| ()).map_err(|e| e.to_validation_errors("q"))
+    }
+}
@@ -1,20 +1,74 @@
-use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use validator::{Validate, ValidationError, ValidationErrors};
 
 /// Request model for creating or updating a todo item
 /// 
 /// This structure represents the data required to create or update a todo item
 #[derive(Serialize, Deserialize, Default, Clone, Debug, 
-    utoipa::ToSchema)]
+    utoipa::ToSchema, Validate)]
 pub struct TodoItemRequest {
     /// Optional identifier (used for updates, omit for creation)
     #[schema(example = 1)]
     pub id: Option<i64>,
     
     /// Title or description of the todo task
-    #[schema(example = "Buy groceries")]
+    #[schema(
+        example = "Buy groceries",
+        min_length = 1,
+        max_length = 200,
+        pattern = r"^[^\s\x00-\x1F\x7F-\x9F](?:[^\x00-\x1F\x7F-\x9F]*[^\s\x00-\x1F\x7F-\x9F])?$"
+    )]
+    #[validate(length(min = 1, max = 200), custom(function = "validate_title"))]
     pub title: String,
     
     /// Indicates whether the todo item is completed
     #[schema(example = false)]
     pub completed: bool,
-}
\ No newline at end of file
+
+    /// When the todo item is due, if it has a deadline. On update `null` removes the
+    /// deadline, while omitting the field keeps it, as clients predating it do
+    #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<DateTime<Utc>>, example = "2026-11-01T17:00:00Z")]
+    pub due_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// Reads a field that is present as `Some`, even when it is `null`
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+impl TodoItemRequest {
+    /// Validates the request for creation, where the id is assigned by the database
+    pub fn validate_for_create(&self) -> Result<(), ValidationErrors> {
+        let mut errors = self.validate().err().unwrap_or_default();
+
+        if self.id.is_some() {
+            errors.add(
+                "id",
+                ValidationError::new("read_only")
+                    .with_message(Cow::from("id is assigned by the server and must be omitted")),
+            );
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+pub(crate) fn validate_title(title: &str) -> Result<(), ValidationError> {
+    if title.trim() != title {
+        return Err(ValidationError::new("trimmed")
+            .with_message(Cow::from("title must not start or end with whitespace")));
+    }
+    if title.chars().any(char::is_control) {
+        return Err(ValidationError::new("control_characters")
+            .with_message(Cow::from("title must not contain control characters")));
+    }
+    Ok(())
+}
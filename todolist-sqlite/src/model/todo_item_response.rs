@@ -1,10 +1,13 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::model::tag::TagResponse;
+
 /// Response model for a todo item
 /// 
 /// This structure represents a todo item returned by the API
-#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, ToSchema)]
 pub struct TodoItemResponse {
     /// Unique identifier of the todo item
     #[schema(example = 1, rename = "id", additional_properties = false)]
@@ -14,7 +17,26 @@ pub struct TodoItemResponse {
     #[schema(example = "Buy groceries")]
     pub title: String,
     
-    /// Indicates whether the todo item has been completed
+    /// Indicates whether the todo item has been completed, i.e. its state is a completed one
     #[schema(example = false)]
     pub completed: bool,
+
+    /// Workflow state, one of those configured under `workflow.states`
+    #[serde(default)]
+    #[schema(example = "in_progress")]
+    pub state: String,
+
+    /// When the todo item is due, if it has a deadline
+    #[serde(default)]
+    #[schema(example = "2026-11-01T17:00:00Z")]
+    pub due_at: Option<DateTime<Utc>>,
+
+    /// Tags on the todo item, by name
+    #[serde(default)]
+    pub tags: Vec<TagResponse>,
+
+    /// Whether the todo item is blocked by another one that is still open
+    #[serde(default)]
+    #[schema(example = false)]
+    pub is_blocked: bool,
 }
\ No newline at end of file
@@ -0,0 +1,168 @@
+use std::borrow::Cow;
+
+use chrono::{Datelike, Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::{ValidationError, ValidationErrors};
+
+/// Most periods a single report may span
+const MAX_PERIODS: u64 = 366;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsInterval {
+    #[default]
+    Day,
+    /// Weeks start on Monday
+    Week,
+}
+
+impl StatsInterval {
+    pub fn days(&self) -> u64 {
+        match self {
+            StatsInterval::Day => 1,
+            StatsInterval::Week => 7,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsFormat {
+    #[default]
+    Json,
+    /// One row per period, for spreadsheets
+    Csv,
+}
+
+/// Query parameters choosing the reported range
+#[derive(Serialize, Deserialize, Default, Clone, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    /// First day of the range (UTC), 30 days or 12 weeks before `to` when omitted
+    #[param(example = "2026-10-01")]
+    pub from: Option<NaiveDate>,
+
+    /// Last day of the range (UTC), today when omitted
+    #[param(example = "2026-10-31")]
+    pub to: Option<NaiveDate>,
+
+    /// Length of each period, `day` when omitted
+    #[param(inline)]
+    pub interval: Option<StatsInterval>,
+
+    /// `csv` downloads the periods as a CSV file instead
+    #[param(inline)]
+    pub format: Option<StatsFormat>,
+}
+
+/// Validated range of a report, with `from` aligned to the start of its period
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StatsRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub interval: StatsInterval,
+}
+
+impl StatsQuery {
+    pub fn range(&self, today: NaiveDate) -> Result<StatsRange, ValidationErrors> {
+        let interval = self.interval.unwrap_or_default();
+        let to = self.to.unwrap_or(today);
+        let from = self.from.unwrap_or_else(|| match interval {
+            StatsInterval::Day => to - Days::new(29),
+            StatsInterval::Week => to - Days::new(7 * 11),
+        });
+        let from = match interval {
+            StatsInterval::Day => from,
+            StatsInterval::Week => from - Days::new(from.weekday().num_days_from_monday() as u64),
+        };
+
+        let mut errors = ValidationErrors::new();
+        if from > to {
+            errors.add(
+                "from",
+                ValidationError::new("range").with_message(Cow::from("from must not be after to")),
+            );
+        } else if (to - from).num_days() as u64 / interval.days() >= MAX_PERIODS {
+            errors.add(
+                "to",
+                ValidationError::new("range").with_message(Cow::from(format!(
+                    "a report spans at most {} periods",
+                    MAX_PERIODS
+                ))),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(StatsRange { from, to, interval })
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Items created and completed during one period
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct StatsPeriod {
+    /// First day of the period
+    #[schema(example = "2026-10-12")]
+    pub start: NaiveDate,
+
+    #[schema(example = 4)]
+    pub created: i64,
+
+    #[schema(example = 3)]
+    pub completed: i64,
+}
+
+/// Productivity report of the todo list
+///
+/// The counts by status describe the list right now; the periods and the average time to
+/// complete cover the requested range
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct TodoStatsResponse {
+    #[schema(example = 12)]
+    pub total: i64,
+
+    #[schema(example = 5)]
+    pub open: i64,
+
+    #[schema(example = 7)]
+    pub completed: i64,
+
+    /// Open items whose due date has passed
+    #[schema(example = 2)]
+    pub overdue: i64,
+
+    /// Share of all items that are completed, from 0 to 1
+    #[schema(example = 0.58)]
+    pub completion_rate: f64,
+
+    /// Average seconds from creation to completion of the items completed in the range
+    #[schema(example = 93600.0)]
+    pub average_completion_secs: Option<f64>,
+
+    pub interval: StatsInterval,
+
+    #[schema(example = "2026-10-01")]
+    pub from: NaiveDate,
+
+    #[schema(example = "2026-10-31")]
+    pub to: NaiveDate,
+
+    /// Every period of the range, oldest first, including empty ones
+    pub periods: Vec<StatsPeriod>,
+}
+
+impl TodoStatsResponse {
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("period_start,created,completed\n");
+        for period in &self.periods {
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                period.start, period.created, period.completed
+            ));
+        }
+        csv
+    }
+}
@@ -0,0 +1,244 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::model::{todo_item_response::TodoItemResponse, validation_error_response::FieldError};
+
+/// Changes returned by a pull when the client doesn't ask for fewer
+pub const DEFAULT_PULL_LIMIT: usize = 500;
+
+/// Most changes a single pull returns
+pub const MAX_PULL_LIMIT: usize = 500;
+
+/// Encodes a position in the change sequence as the token handed to clients.
+/// Clients must treat it as opaque; only the server reads it back.
+pub fn encode_token(sequence: i64) -> String {
+    format!("c{:016x}", sequence)
+}
+
+pub fn parse_token(token: &str) -> Option<i64> {
+    let hex = token.strip_prefix('c').filter(|hex| hex.len() == 16)?;
+    i64::from_str_radix(hex, 16).ok().filter(|sequence| *sequence >= 0)
+}
+
+/// Query parameters of a pull
+#[derive(Serialize, Deserialize, Default, Clone, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SyncQuery {
+    /// Token returned by the previous pull; everything is returned when omitted
+    #[param(example = "c0000000000000042")]
+    pub since: Option<String>,
+
+    /// Most changes to return, 500 when omitted. Pull again with the new token while `has_more`
+    #[param(minimum = 1, maximum = 500)]
+    pub limit: Option<usize>,
+}
+
+impl SyncQuery {
+    /// Position in the change sequence to pull from, and how many changes to return
+    pub fn position(&self) -> Result<(i64, usize), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+
+        let since = match self.since.as_deref().map(parse_token) {
+            None => Some(0),
+            Some(since) => since,
+        };
+        if since.is_none() {
+            errors.add(
+                "since",
+                ValidationError::new("invalid_token")
+                    .with_message(Cow::from("since must be a token returned by a previous sync")),
+            );
+        }
+        let limit = self.limit.unwrap_or(DEFAULT_PULL_LIMIT);
+        if !(1..=MAX_PULL_LIMIT).contains(&limit) {
+            errors.add(
+                "limit",
+                ValidationError::new("range")
+                    .with_message(Cow::from(format!("limit must be between 1 and {}", MAX_PULL_LIMIT))),
+            );
+        }
+
+        match since {
+            Some(since) if errors.is_empty() => Ok((since, limit)),
+            _ => Err(errors),
+        }
+    }
+}
+
+/// When each synced field of a todo item was last changed, compared field by field
+/// when clients push their own changes
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+pub struct SyncModifiedAt {
+    #[schema(example = "2026-10-19T08:30:00.125Z")]
+    pub title: DateTime<Utc>,
+    #[schema(example = "2026-10-19T08:30:00.125Z")]
+    pub completed: DateTime<Utc>,
+    #[schema(example = "2026-10-19T08:30:00.125Z")]
+    pub due_at: DateTime<Utc>,
+}
+
+/// A todo item as of its latest change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, ToSchema)]
+pub struct SyncTodo {
+    #[serde(flatten)]
+    pub todo: TodoItemResponse,
+
+    pub modified_at: SyncModifiedAt,
+}
+
+/// A deleted todo item
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct SyncTombstone {
+    #[schema(example = 7)]
+    pub id: i64,
+
+    #[schema(example = "2026-10-19T09:00:00Z")]
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Everything that changed since the token a pull was made with
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct SyncResponse {
+    /// Token for the next pull
+    #[schema(example = "c0000000000000057")]
+    pub token: String,
+
+    /// More changes are waiting; pull again with `token` straight away
+    pub has_more: bool,
+
+    /// Items created or changed, in the order they last changed
+    pub changes: Vec<SyncTodo>,
+
+    /// Items deleted, including ones created after the token
+    pub deleted: Vec<SyncTombstone>,
+}
+
+/// New value of one field, and when the client changed it
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct FieldChange<T> {
+    pub value: T,
+
+    /// Times ahead of the server's clock count as the time the change is received
+    #[schema(example = "2026-10-19T08:45:00Z")]
+    pub modified_at: DateTime<Utc>,
+}
+
+/// A change made by a client while it was offline. Omit `id` to create an item, send
+/// `deleted_at` to delete one, or send the changed fields to update one.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, ToSchema)]
+pub struct ClientChange {
+    /// Item changed or deleted; omitted for items created by the client
+    #[schema(example = 7)]
+    pub id: Option<i64>,
+
+    /// Client's own reference to the change, echoed in its result
+    #[schema(example = "local-3")]
+    pub client_ref: Option<String>,
+
+    pub title: Option<FieldChange<String>>,
+
+    pub completed: Option<FieldChange<bool>>,
+
+    #[schema(value_type = Option<FieldChange<Option<String>>>)]
+    pub due_at: Option<FieldChange<Option<DateTime<Utc>>>>,
+
+    /// When the client deleted the item
+    #[schema(example = "2026-10-19T08:50:00Z")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Batch of client changes, applied in order
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema, Validate)]
+pub struct SyncRequest {
+    #[validate(length(min = 1, max = 500))]
+    pub changes: Vec<ClientChange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeStatus {
+    /// Every field of the change was applied
+    Applied,
+    /// The server kept some fields because they changed later there
+    Conflict,
+    /// Nothing was applied, see `errors`
+    Rejected,
+}
+
+/// A field the server kept, with the value the client should adopt
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct FieldConflict {
+    /// `title`, `completed`, `due_at`, or `deleted` when the item no longer exists
+    #[schema(example = "title")]
+    pub field: String,
+
+    #[schema(example = "Buy oat milk")]
+    pub server_value: serde_json::Value,
+
+    #[schema(example = "2026-10-19T08:47:00Z")]
+    pub server_modified_at: DateTime<Utc>,
+}
+
+/// Outcome of one client change
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct ChangeResult {
+    pub client_ref: Option<String>,
+
+    /// Item the change applied to, including the id assigned to created items
+    pub id: Option<i64>,
+
+    pub status: ChangeStatus,
+
+    pub conflicts: Vec<FieldConflict>,
+
+    pub errors: Vec<FieldError>,
+
+    /// Item as stored on the server after the change, absent once deleted
+    pub todo: Option<SyncTodo>,
+}
+
+impl ChangeResult {
+    pub fn new(change: &ClientChange) -> Self {
+        Self {
+            client_ref: change.client_ref.clone(),
+            id: change.id,
+            status: ChangeStatus::Applied,
+            conflicts: Vec::new(),
+            errors: Vec::new(),
+            todo: None,
+        }
+    }
+
+    /// Reports a field the server kept; a rejected change stays rejected
+    pub fn add_conflict<T: Serialize>(&mut self, field: &str, server_value: &T, server_modified_at: DateTime<Utc>) {
+        if self.status == ChangeStatus::Applied {
+            self.status = ChangeStatus::Conflict;
+        }
+        self.conflicts.push(FieldConflict {
+            field: field.to_string(),
+            server_value: serde_json::to_value(server_value).unwrap_or_default(),
+            server_modified_at,
+        });
+    }
+
+    pub fn reject(&mut self, error: FieldError) {
+        self.reject_all(vec![error]);
+    }
+
+    /// Nothing of the change is applied, so conflicts found on the way no longer matter
+    pub fn reject_all(&mut self, errors: Vec<FieldError>) {
+        self.status = ChangeStatus::Rejected;
+        self.conflicts.clear();
+        self.errors = errors;
+    }
+}
+
+/// Results of a push, in the order of its changes
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct SyncPushResponse {
+    pub results: Vec<ChangeResult>,
+}
@@ -0,0 +1,3 @@
+pub mod todo_list_response;
+pub mod todo_request;
+pub mod todo_response;
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::model::{todo_item_response::TodoItemResponse, v2::todo_response::TodoResponse};
+
+/// A page of todo items
+///
+/// Wrapping the list leaves room for paging metadata without breaking clients
+#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema)]
+pub struct TodoListResponse {
+    pub items: Vec<TodoResponse>,
+
+    /// Number of items matching the filter
+    #[schema(example = 1)]
+    pub total: usize,
+}
+
+impl From<Vec<TodoItemResponse>> for TodoListResponse {
+    fn from(todos: Vec<TodoItemResponse>) -> Self {
+        Self {
+            total: todos.len(),
+            items: todos.into_iter().map(TodoResponse::from).collect(),
+        }
+    }
+}
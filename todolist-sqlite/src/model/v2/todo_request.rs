@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::model::todo_item_request::{TodoItemRequest, validate_title};
+
+/// Request model for creating or updating a todo item
+///
+/// Unlike v1 the id only ever comes from the path
+#[derive(Serialize, Deserialize, Default, Clone, Debug, utoipa::ToSchema, Validate)]
+pub struct TodoRequest {
+    /// Title or description of the todo task
+    #[schema(
+        example = "Buy groceries",
+        min_length = 1,
+        max_length = 200,
+        pattern = r"^[^\s\x00-\x1F\x7F-\x9F](?:[^\x00-\x1F\x7F-\x9F]*[^\s\x00-\x1F\x7F-\x9F])?$"
+    )]
+    #[validate(length(min = 1, max = 200), custom(function = "validate_title"))]
+    pub title: String,
+
+    /// Indicates whether the todo item is completed, false when omitted
+    #[serde(default)]
+    #[schema(example = false)]
+    pub completed: bool,
+
+    /// When the todo item is due; omitting it on update removes the deadline
+    #[serde(default)]
+    #[schema(example = "2026-11-01T17:00:00Z")]
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+impl From<TodoRequest> for TodoItemRequest {
+    fn from(request: TodoRequest) -> Self {
+        Self {
+            id: None,
+            title: request.title,
+            completed: request.completed,
+            due_at: Some(request.due_at),
+        }
+    }
+}
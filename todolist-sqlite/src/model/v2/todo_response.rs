@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::model::{tag::TagResponse, todo_item_response::TodoItemResponse};
+
+/// Response model for a todo item
+#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema)]
+pub struct TodoResponse {
+    /// Unique identifier of the todo item
+    #[schema(example = 1)]
+    pub id: i64,
+
+    /// Title or description of the todo task
+    #[schema(example = "Buy groceries")]
+    pub title: String,
+
+    /// Indicates whether the todo item has been completed, i.e. its state is a completed one
+    #[schema(example = false)]
+    pub completed: bool,
+
+    /// Workflow state; change it with `POST /todo/{id}/transition`
+    #[schema(example = "in_progress")]
+    pub state: String,
+
+    /// When the todo item is due, if it has a deadline
+    #[schema(example = "2026-11-01T17:00:00Z")]
+    pub due_at: Option<DateTime<Utc>>,
+
+    /// Tags on the todo item, by name
+    pub tags: Vec<TagResponse>,
+
+    /// Whether the todo item is blocked by another one that is still open; see
+    /// `GET /todo/{id}/dependencies`
+    #[schema(example = false)]
+    pub is_blocked: bool,
+}
+
+impl From<TodoItemResponse> for TodoResponse {
+    fn from(todo: TodoItemResponse) -> Self {
+        Self {
+            id: todo.id.unwrap_or_default(),
+            title: todo.title,
+            completed: todo.completed,
+            state: todo.state,
+            due_at: todo.due_at,
+            tags: todo.tags,
+            is_blocked: todo.is_blocked,
+        }
+    }
+}
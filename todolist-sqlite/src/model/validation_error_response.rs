@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::ValidationErrors;
+
+/// A single rule violated by a request field
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct FieldError {
+    /// Name of the offending field
+    #[schema(example = "title")]
+    pub field: String,
+
+    /// Machine readable rule identifier
+    #[schema(example = "length")]
+    pub code: String,
+
+    /// Human readable description of the violation
+    #[schema(example = "title must be between 1 and 200 characters")]
+    pub message: String,
+
+    /// Offset in characters of a syntax error in the field, from 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 7)]
+    pub position: Option<usize>,
+
+    /// Todo ids of a dependency cycle, each blocked by the next
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = json!([1, 2, 1]))]
+    pub path: Option<Vec<i64>>,
+}
+
+/// Response body returned with 422 when a request fails validation
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct ValidationErrorResponse {
+    #[schema(example = "Validation failed")]
+    pub message: String,
+
+    /// Every field error found in the request
+    pub errors: Vec<FieldError>,
+}
+
+impl From<ValidationErrors> for ValidationErrorResponse {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut field_errors = errors
+            .field_errors()
+            .into_iter()
+            .flat_map(|(field, errors)| {
+                errors.iter().map(move |error| FieldError {
+                    field: field.to_string(),
+                    code: error.code.to_string(),
+                    message: error
+                        .message
+                        .as_ref()
+                        .map(|message| message.to_string())
+                        .unwrap_or_else(|| default_message(&field, error)),
+                    position: error
+                        .params
+                        .get("position")
+                        .and_then(|position| position.as_u64())
+                        .map(|position| position as usize),
+                    path: error
+                        .params
+                        .get("path")
+                        .and_then(|path| serde_json::from_value(path.clone()).ok()),
+                })
+            })
+            .collect::<Vec<FieldError>>();
+        field_errors.sort_by(|a, b| a.field.cmp(&b.field));
+
+        Self {
+            message: "Validation failed".to_string(),
+            errors: field_errors,
+        }
+    }
+}
+
+fn default_message(field: &str, error: &validator::ValidationError) -> String {
+    match (error.params.get("min"), error.params.get("max")) {
+        (Some(min), Some(max)) => format!("{} must be between {} and {} characters", field, min, max),
+        _ => format!("{} is invalid ({})", field, error.code),
+    }
+}
@@ -0,0 +1,126 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{ValidationError, ValidationErrors};
+
+use crate::{
+    config::settings::WorkflowConfig,
+    model::{todo_item_response::TodoItemResponse, v2::todo_response::TodoResponse},
+};
+
+/// Request model for moving a todo item to another workflow state
+#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema)]
+pub struct TransitionRequest {
+    /// State to move to, allowed from the item's current state
+    #[schema(example = "in_review")]
+    pub to: String,
+}
+
+impl TransitionRequest {
+    /// Checks `to` is a configured state the workflow allows moving to from `from`;
+    /// staying in the same state is always allowed
+    pub fn check(&self, workflow: &WorkflowConfig, from: &str) -> Result<(), ValidationErrors> {
+        let error = match workflow.state(&self.to) {
+            None => ValidationError::new("unknown_state").with_message(Cow::from(format!(
+                "unknown state {}, expected one of: {}",
+                self.to,
+                names(workflow.states.iter().map(|state| &state.name))
+            ))),
+            Some(_) if self.to == from => return Ok(()),
+            Some(_) => match workflow.state(from) {
+                Some(current) if current.to.contains(&self.to) => return Ok(()),
+                Some(current) => ValidationError::new("transition").with_message(Cow::from(format!(
+                    "can't move from {} to {}, only to: {}",
+                    from,
+                    self.to,
+                    names(current.to.iter())
+                ))),
+                // Items are aligned on startup, so this only covers a state removed meanwhile
+                None => return Ok(()),
+            },
+        };
+        let mut errors = ValidationErrors::new();
+        errors.add("to", error);
+        Err(errors)
+    }
+}
+
+/// A `completed` write that the workflow doesn't allow from state `from`
+pub fn completion_refused(workflow: &WorkflowConfig, from: &str, completed: bool) -> ValidationErrors {
+    let to = workflow.state(from).map(|state| names(state.to.iter())).unwrap_or_default();
+    let error = ValidationError::new("transition").with_message(Cow::from(format!(
+        "can't {} an item in {}, which only moves to: {}",
+        if completed { "complete" } else { "reopen" },
+        from,
+        to
+    )));
+    let mut errors = ValidationErrors::new();
+    errors.add("completed", error);
+    errors
+}
+
+fn names<'a>(names: impl Iterator<Item = &'a String>) -> String {
+    let names: Vec<&str> = names.map(String::as_str).collect();
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// One workflow state with its todo items, in manual order
+#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema)]
+pub struct BoardColumn {
+    #[schema(example = "in_progress")]
+    pub state: String,
+
+    #[schema(example = "In progress")]
+    pub label: String,
+
+    /// Whether items in this state count as completed
+    #[schema(example = false)]
+    pub completed: bool,
+
+    /// States items in this column may move to
+    #[schema(example = json!(["todo", "blocked", "in_review", "done"]))]
+    pub to: Vec<String>,
+
+    pub items: Vec<TodoResponse>,
+
+    /// Number of items in the column
+    #[schema(example = 1)]
+    pub total: usize,
+}
+
+/// The todo list grouped by workflow state, one column per configured state
+#[derive(Serialize, Deserialize, Default, Clone, Debug, ToSchema)]
+pub struct BoardResponse {
+    pub columns: Vec<BoardColumn>,
+}
+
+impl BoardResponse {
+    pub fn new(workflow: &WorkflowConfig, todos: Vec<TodoItemResponse>) -> Self {
+        let mut columns: Vec<BoardColumn> = workflow
+            .states
+            .iter()
+            .map(|state| BoardColumn {
+                state: state.name.clone(),
+                label: state.label.clone(),
+                completed: state.completed,
+                to: state.to.clone(),
+                items: Vec::new(),
+                total: 0,
+            })
+            .collect();
+
+        for todo in todos {
+            let state = workflow.align(&todo.state, todo.completed);
+            if let Some(column) = columns.iter_mut().find(|column| column.state == state) {
+                column.items.push(TodoResponse::from(todo));
+                column.total += 1;
+            }
+        }
+        Self { columns }
+    }
+}
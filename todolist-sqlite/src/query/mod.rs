@@ -0,0 +1,125 @@
+//! The `q` search language of the todo list, e.g.
+//! `completed:false tag:work due<2026-11-01 "release notes"`.
+//!
+//! ```text
+//! query   = or
+//! or      = and ("OR" and)*
+//! and     = unary ("AND"? unary)*
+//! unary   = ("NOT" | "-") unary | "(" or ")" | term
+//! term    = field op value | word | "quoted phrase"
+//! field   = completed | state | tag | due | created | title
+//! op      = ":" | "=" | "<" | "<=" | ">" | ">="
+//! ```
+//!
+//! - Words and phrases match titles containing them, ignoring case; so does `title:`
+//! - `completed:true|false` (or `yes|no`), and workflow states like `state:in_review`
+//! - `tag:work`, or `tag:none` for untagged items
+//! - `due` and `created` compare UTC days: `due<2026-11-01`, `due:today`,
+//!   `created>=yesterday`; `due:none` and `due:any` test whether there is a deadline
+//!
+//! Terms next to each other must all match. Queries are parsed into an [`Expr`] and
+//! compiled to an SQL condition whose values are all bound as parameters. Queries are
+//! capped in length, terms and nesting so the SQL stays within SQLite's expression depth.
+
+mod parser;
+mod sql;
+
+/// Most characters in a query
+pub const MAX_LENGTH: usize = 500;
+/// Most search terms in a query
+pub const MAX_TERMS: usize = 100;
+/// Most levels of `NOT` and parentheses around a term
+pub const MAX_DEPTH: usize = 16;
+
+use std::{borrow::Cow, fmt};
+
+use chrono::NaiveDate;
+use validator::{ValidationError, ValidationErrors};
+
+pub use sql::{Condition, compile};
+
+/// Parsed query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    /// Every item matches an empty `And`
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Term(Term),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Term {
+    /// The title contains the text, ignoring case
+    Text(String),
+    Completed(bool),
+    /// Items in the workflow state
+    State(String),
+    /// Items with the tag, or without any tag for `None`
+    Tag(Option<String>),
+    Due(DateFilter),
+    /// Items created at a time compared by UTC day, or whose creation time is unknown for `none`
+    Created(DateFilter),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateFilter {
+    /// No date set
+    None,
+    /// Any date set
+    Any,
+    Compare(Comparison, Day),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    /// On the day, for `:` and `=`
+    On,
+    Before,
+    OnOrBefore,
+    After,
+    OnOrAfter,
+}
+
+/// A UTC day; relative days are resolved when the query runs, so saved queries keep
+/// meaning `today`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Day {
+    Date(NaiveDate),
+    /// Days from today, `-1` for yesterday
+    Today(i64),
+}
+
+/// Syntax error in a query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    /// Offset in characters of where the error is, from 0
+    pub position: usize,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl QueryError {
+    /// The error as the `syntax` rule of `field`, with its `position`
+    pub fn to_validation_error(&self) -> ValidationError {
+        let mut error = ValidationError::new("syntax").with_message(Cow::from(self.to_string()));
+        error.add_param(Cow::from("position"), &self.position);
+        error
+    }
+
+    pub fn to_validation_errors(&self, field: &'static str) -> ValidationErrors {
+        let mut errors = ValidationErrors::new();
+        errors.add(field, self.to_validation_error());
+        errors
+    }
+}
+
+/// Parses a query; a blank one matches every item
+pub fn parse(text: &str) -> Result<Expr, QueryError> {
+    parser::parse(text)
+}
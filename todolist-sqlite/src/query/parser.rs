@@ -0,0 +1,333 @@
+//! Recursive descent parser over the tokens of a query. Positions are counted in
+//! characters so clients can point at them in the text they sent.
+
+use chrono::NaiveDate;
+
+use crate::{
+    model::tag::is_tag_name,
+    query::{Comparison, DateFilter, Day, Expr, MAX_DEPTH, MAX_LENGTH, MAX_TERMS, QueryError, Term},
+};
+
+const FIELDS: &str = "completed, state, tag, due, created or title";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Kind {
+    Open,
+    Close,
+    Word(String),
+    Quoted(String),
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: Kind,
+    start: usize,
+    end: usize,
+}
+
+fn error<T>(message: impl Into<String>, position: usize) -> Result<T, QueryError> {
+    Err(QueryError {
+        message: message.into(),
+        position,
+    })
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, QueryError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let start = i;
+        let kind = match chars[i] {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => {
+                i += 1;
+                Kind::Open
+            }
+            ')' => {
+                i += 1;
+                Kind::Close
+            }
+            '"' => {
+                let Some(length) = chars[i + 1..].iter().position(|c| *c == '"') else {
+                    return error("unterminated quote", start);
+                };
+                i += length + 2;
+                Kind::Quoted(chars[start + 1..i - 1].iter().collect())
+            }
+            _ => {
+                while i < chars.len() && !chars[i].is_whitespace() && !matches!(chars[i], '(' | ')' | '"') {
+                    i += 1;
+                }
+                Kind::Word(chars[start..i].iter().collect())
+            }
+        };
+        tokens.push(Token {
+            kind,
+            start,
+            end: i,
+        });
+    }
+    Ok(tokens)
+}
+
+pub(super) fn parse(text: &str) -> Result<Expr, QueryError> {
+    let length = text.chars().count();
+    if length > MAX_LENGTH {
+        return error(format!("query is longer than {} characters", MAX_LENGTH), MAX_LENGTH);
+    }
+
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        next: 0,
+        length,
+        depth: 0,
+        terms: 0,
+    };
+    if parser.tokens.is_empty() {
+        return Ok(Expr::And(Vec::new()));
+    }
+
+    let expr = parser.or()?;
+    match parser.peek() {
+        Some(token) if token.kind == Kind::Close => error("unexpected \")\" without a matching \"(\"", token.start),
+        Some(token) => error("unexpected input", token.start),
+        None => Ok(expr),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+    /// Length of the text, where errors at its end point
+    length: usize,
+    /// Levels of `NOT` and parentheses around the current token
+    depth: usize,
+    /// Search terms read so far
+    terms: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.next).cloned();
+        self.next += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token { kind: Kind::Word(word), .. }) if word == keyword)
+    }
+
+    /// Where the next token starts, or the end of the text
+    fn position(&self) -> usize {
+        self.peek().map_or(self.length, |token| token.start)
+    }
+
+    fn or(&mut self) -> Result<Expr, QueryError> {
+        let mut operands = vec![self.and()?];
+        while self.peek_keyword("OR") {
+            self.advance();
+            operands.push(self.and()?);
+        }
+        Ok(flatten(operands, Expr::Or))
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut operands = vec![self.unary()?];
+        loop {
+            match self.peek() {
+                None => break,
+                Some(token) if token.kind == Kind::Close => break,
+                Some(_) if self.peek_keyword("OR") => break,
+                Some(_) if self.peek_keyword("AND") => {
+                    self.advance();
+                    operands.push(self.unary()?);
+                }
+                Some(_) => operands.push(self.unary()?),
+            }
+        }
+        Ok(flatten(operands, Expr::And))
+    }
+
+    /// Parses `parse` one level deeper, refusing to nest past [`MAX_DEPTH`]
+    fn nested(&mut self, position: usize, parse: impl FnOnce(&mut Self) -> Result<Expr, QueryError>) -> Result<Expr, QueryError> {
+        if self.depth == MAX_DEPTH {
+            return error(format!("nested more than {} levels deep", MAX_DEPTH), position);
+        }
+        self.depth += 1;
+        let expr = parse(self);
+        self.depth -= 1;
+        expr
+    }
+
+    fn unary(&mut self) -> Result<Expr, QueryError> {
+        let position = self.position();
+        let Some(token) = self.advance() else {
+            return error("expected a search term", position);
+        };
+        let is_term = match &token.kind {
+            Kind::Word(word) => !matches!(word.as_str(), "NOT" | "-" | "OR" | "AND"),
+            Kind::Quoted(_) => true,
+            Kind::Open | Kind::Close => false,
+        };
+        if is_term {
+            self.terms += 1;
+            if self.terms > MAX_TERMS {
+                return error(format!("more than {} search terms", MAX_TERMS), token.start);
+            }
+        }
+
+        match token.kind {
+            Kind::Word(word) if word == "NOT" => {
+                self.nested(token.start, |parser| Ok(Expr::Not(Box::new(parser.unary()?))))
+            }
+            Kind::Word(word) if word == "OR" || word == "AND" => {
+                error(format!("expected a search term before {}", word), token.start)
+            }
+            Kind::Word(word) if word == "-" => match self.peek() {
+                // -(a OR b)
+                Some(next) if next.kind == Kind::Open && next.start == token.end => {
+                    self.nested(token.start, |parser| Ok(Expr::Not(Box::new(parser.unary()?))))
+                }
+                _ => error("expected a search term right after \"-\"", token.end),
+            },
+            Kind::Word(word) if word.starts_with('-') => {
+                let term = self.term(&word[1..], token.start + 1, token.end)?;
+                Ok(Expr::Not(Box::new(term)))
+            }
+            Kind::Word(word) => self.term(&word, token.start, token.end),
+            Kind::Quoted(text) => Ok(Expr::Term(Term::Text(text))),
+            Kind::Open => {
+                if self.peek().is_some_and(|next| next.kind == Kind::Close) {
+                    return error("empty parentheses", token.start);
+                }
+                let expr = self.nested(token.start, Self::or)?;
+                match self.advance() {
+                    Some(Token { kind: Kind::Close, .. }) => Ok(expr),
+                    _ => error("unclosed \"(\"", token.start),
+                }
+            }
+            Kind::Close => error("unexpected \")\" without a matching \"(\"", token.start),
+        }
+    }
+
+    /// A word, or a `field op value` term whose value may be a phrase right after it
+    fn term(&mut self, word: &str, start: usize, end: usize) -> Result<Expr, QueryError> {
+        let Some(split) = word.find([':', '<', '>', '=']) else {
+            return Ok(Expr::Term(Term::Text(word.to_string())));
+        };
+        let field = &word[..split];
+        if field.is_empty() || !field.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Ok(Expr::Term(Term::Text(word.to_string())));
+        }
+
+        let rest = &word[split..];
+        let (operator, comparison) = [
+            ("<=", Comparison::OnOrBefore),
+            (">=", Comparison::OnOrAfter),
+            ("<", Comparison::Before),
+            (">", Comparison::After),
+            (":", Comparison::On),
+            ("=", Comparison::On),
+        ]
+        .into_iter()
+        .find(|(operator, _)| rest.starts_with(operator))
+        .unwrap_or((":", Comparison::On));
+        let value_start = start + field.chars().count() + operator.len();
+
+        let mut value = rest[operator.len()..].to_string();
+        if value.is_empty() {
+            match self.peek() {
+                Some(Token { kind: Kind::Quoted(text), start: next, .. }) if *next == end => {
+                    value = text.clone();
+                    self.advance();
+                }
+                _ => return error(format!("expected a value after \"{}{}\"", field, operator), end),
+            }
+        }
+
+        let field = field.to_lowercase();
+        let term = match field.as_str() {
+            "title" | "completed" | "state" | "tag" if comparison != Comparison::On => {
+                return error(
+                    format!("{} only supports \":\", not \"{}\"", field, operator),
+                    start + field.chars().count(),
+                );
+            }
+            "title" => Term::Text(value),
+            "completed" => Term::Completed(boolean(&value, value_start)?),
+            "state" if value.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') => {
+                Term::State(value)
+            }
+            "state" => return error(format!("\"{}\" is not a state name", value), value_start),
+            "tag" if value.eq_ignore_ascii_case("none") => Term::Tag(None),
+            "tag" if is_tag_name(&value) => Term::Tag(Some(value)),
+            "tag" => return error(format!("\"{}\" is not a tag name", value), value_start),
+            "due" => Term::Due(date_filter(&field, comparison, &value, value_start)?),
+            "created" => Term::Created(date_filter(&field, comparison, &value, value_start)?),
+            _ => {
+                return error(
+                    format!("unknown field \"{}\", expected {}", field, FIELDS),
+                    start,
+                );
+            }
+        };
+        Ok(Expr::Term(term))
+    }
+}
+
+/// A single operand stands for itself
+fn flatten(mut operands: Vec<Expr>, combine: fn(Vec<Expr>) -> Expr) -> Expr {
+    if operands.len() == 1 {
+        operands.remove(0)
+    } else {
+        combine(operands)
+    }
+}
+
+fn boolean(value: &str, position: usize) -> Result<bool, QueryError> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" => Ok(true),
+        "false" | "no" => Ok(false),
+        _ => error(format!("expected true or false, not \"{}\"", value), position),
+    }
+}
+
+fn date_filter(
+    field: &str,
+    comparison: Comparison,
+    value: &str,
+    position: usize,
+) -> Result<DateFilter, QueryError> {
+    let day = match value.to_lowercase().as_str() {
+        "none" | "any" if comparison != Comparison::On => {
+            return error(format!("use {}:{} without comparing", field, value), position);
+        }
+        "none" => return Ok(DateFilter::None),
+        "any" => return Ok(DateFilter::Any),
+        "today" => Day::Today(0),
+        "tomorrow" => Day::Today(1),
+        "yesterday" => Day::Today(-1),
+        _ => match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(date) => Day::Date(date),
+            Err(_) => {
+                return error(
+                    format!(
+                        "\"{}\" is not a date, expected YYYY-MM-DD, today, tomorrow, yesterday, none or any",
+                        value
+                    ),
+                    position,
+                );
+            }
+        },
+    };
+    Ok(DateFilter::Compare(comparison, day))
+}
@@ -0,0 +1,126 @@
+//! Compiles a parsed query to a condition on the todos aliased `t`. Every value the
+//! user typed is bound as a parameter; only fixed SQL is written into the text.
+
+use chrono::{Duration, NaiveDate};
+use sqlite::Value;
+
+use crate::{
+    db::tags,
+    query::{Comparison, DateFilter, Day, Expr, Term},
+};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// SQL condition with the values of its numbered `?N` parameters
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition {
+    pub sql: String,
+    /// Value of parameter `first_param + i` at index `i`
+    pub params: Vec<Value>,
+}
+
+/// Compiles `expr` numbering its parameters from `first_param`, with relative days
+/// counted from `today`
+pub fn compile(expr: &Expr, today: NaiveDate, first_param: usize) -> Condition {
+    let mut compiler = Compiler {
+        today,
+        first_param,
+        params: Vec::new(),
+    };
+    let sql = compiler.expr(expr);
+    Condition {
+        sql,
+        params: compiler.params,
+    }
+}
+
+struct Compiler {
+    today: NaiveDate,
+    first_param: usize,
+    params: Vec<Value>,
+}
+
+impl Compiler {
+    /// Adds a parameter, returning its placeholder
+    fn param(&mut self, value: Value) -> String {
+        self.params.push(value);
+        format!("?{}", self.first_param + self.params.len() - 1)
+    }
+
+    fn expr(&mut self, expr: &Expr) -> String {
+        match expr {
+            Expr::And(operands) if operands.is_empty() => "1".to_string(),
+            Expr::And(operands) => self.join(operands, " AND "),
+            Expr::Or(operands) => self.join(operands, " OR "),
+            // A comparison with a missing due date is NULL, which NOT would keep NULL
+            Expr::Not(operand) => format!("NOT COALESCE(({}), 0)", self.expr(operand)),
+            Expr::Term(term) => self.term(term),
+        }
+    }
+
+    fn join(&mut self, operands: &[Expr], separator: &str) -> String {
+        let parts: Vec<String> = operands
+            .iter()
+            .map(|operand| format!("({})", self.expr(operand)))
+            .collect();
+        parts.join(separator)
+    }
+
+    fn term(&mut self, term: &Term) -> String {
+        match term {
+            Term::Text(text) => {
+                let text = self.param(Value::String(text.clone()));
+                format!("instr(lower(t.title), lower({})) > 0", text)
+            }
+            Term::Completed(completed) => {
+                let completed = self.param(Value::Integer(*completed as i64));
+                format!("t.completed = {}", completed)
+            }
+            Term::State(state) => {
+                let state = self.param(Value::String(state.clone()));
+                format!("t.state = {}", state)
+            }
+            Term::Tag(None) => {
+                "NOT EXISTS (SELECT 1 FROM todo_tags qt WHERE qt.todo_id = t.id)".to_string()
+            }
+            Term::Tag(Some(name)) => {
+                let name = self.param(Value::String(tags::fold(name)));
+                format!(
+                    "EXISTS (SELECT 1 FROM todo_tags qt JOIN tags qg ON qg.id = qt.tag_id
+                    WHERE qt.todo_id = t.id AND qg.folded_name = {})",
+                    name
+                )
+            }
+            Term::Due(filter) => self.date("t.due_at", filter),
+            // 0 stands for unknown, so those items only match created:none
+            Term::Created(filter) => self.date("NULLIF(t.created_at, 0)", filter),
+        }
+    }
+
+    fn date(&mut self, column: &str, filter: &DateFilter) -> String {
+        let (comparison, day) = match filter {
+            DateFilter::None => return format!("{} IS NULL", column),
+            DateFilter::Any => return format!("{} IS NOT NULL", column),
+            DateFilter::Compare(comparison, day) => (*comparison, *day),
+        };
+        let date = match day {
+            Day::Date(date) => date,
+            Day::Today(days) => self.today + Duration::days(days),
+        };
+        // Seconds at which the UTC day starts and the next one does
+        let start = date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
+        let end = start + SECONDS_PER_DAY;
+
+        match comparison {
+            Comparison::On => {
+                let start = self.param(Value::Integer(start));
+                let end = self.param(Value::Integer(end));
+                format!("{} >= {} AND {} < {}", column, start, column, end)
+            }
+            Comparison::Before => format!("{} < {}", column, self.param(Value::Integer(start))),
+            Comparison::OnOrBefore => format!("{} < {}", column, self.param(Value::Integer(end))),
+            Comparison::After => format!("{} >= {}", column, self.param(Value::Integer(end))),
+            Comparison::OnOrAfter => format!("{} >= {}", column, self.param(Value::Integer(start))),
+        }
+    }
+}
@@ -0,0 +1,349 @@
+//! Parses quick-add text such as `Pay rent tomorrow 9am #home !high every month` into
+//! the parts of a todo item. Recognised phrases are taken out of the text and whatever
+//! is left becomes the title; words in double quotes are always kept as typed.
+//!
+//! - Due dates: `today`, `tonight`, `tomorrow`, weekdays (`friday`, `next fri`),
+//!   `next week|month|year`, `in 3 days`, `in 2 hours`, `2026-11-01`, `nov 1`,
+//!   `1st of november 2027`, optionally after `on`, `by` or `due`
+//! - Times: `9am`, `9:30 pm`, `21:00`, `noon`, `midnight`, optionally after `at` or `by`
+//! - Tags: `#home`; all-digit ones like `#123` are left alone as issue references
+//! - Priority: `!low`, `!medium`, `!high`, `!urgent`; the last one wins
+//! - Recurrence: `daily`, `every month`, `every other week`, `every 3 days`,
+//!   `every monday and thursday`, `every weekday`
+//!
+//! Dates and times are read in the given timezone; a date without a time is due at
+//! the default time, and a time without a date at its next occurrence.
+
+mod phrases;
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::quick_add::phrases::{DateMatch, Phrase};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// How often a todo repeats
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, ToSchema)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+
+    /// Repeats every `interval` days, weeks, months or years
+    #[schema(example = 1, minimum = 1)]
+    pub interval: u32,
+
+    /// Days of a weekly recurrence as iCalendar codes, `MO` to `SU`
+    #[schema(example = json!(["MO", "TH"]))]
+    pub by_day: Vec<String>,
+
+    /// The same recurrence as an iCalendar `RRULE`
+    #[schema(example = "FREQ=MONTHLY")]
+    pub rule: String,
+}
+
+impl Recurrence {
+    pub fn new(frequency: Frequency, interval: u32, weekdays: &[Weekday]) -> Self {
+        let by_day: Vec<String> = weekdays.iter().map(|day| ical_day(*day).to_string()).collect();
+        let mut rule = format!(
+            "FREQ={}",
+            match frequency {
+                Frequency::Daily => "DAILY",
+                Frequency::Weekly => "WEEKLY",
+                Frequency::Monthly => "MONTHLY",
+                Frequency::Yearly => "YEARLY",
+            }
+        );
+        if interval > 1 {
+            rule.push_str(&format!(";INTERVAL={}", interval));
+        }
+        if !by_day.is_empty() {
+            rule.push_str(&format!(";BYDAY={}", by_day.join(",")));
+        }
+
+        Self {
+            frequency,
+            interval,
+            by_day,
+            rule,
+        }
+    }
+
+    fn weekdays(&self) -> Vec<Weekday> {
+        self.by_day
+            .iter()
+            .filter_map(|code| WEEK.into_iter().find(|day| ical_day(*day) == code))
+            .collect()
+    }
+}
+
+const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+fn ical_day(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    Date,
+    Time,
+    Tag,
+    Priority,
+    Recurrence,
+}
+
+/// A phrase taken out of the text, as typed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, ToSchema)]
+pub struct QuickAddMatch {
+    pub kind: MatchKind,
+
+    #[schema(example = "tomorrow")]
+    pub text: String,
+}
+
+/// Everything read from a quick-add text
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, ToSchema)]
+pub struct QuickAdd {
+    /// The text without the recognised phrases
+    #[schema(example = "Pay rent")]
+    pub title: String,
+
+    #[schema(example = "2026-10-20T09:00:00Z")]
+    pub due_at: Option<DateTime<Utc>>,
+
+    /// Lowercase tag names without `#`, in the order they appear
+    #[schema(example = json!(["home"]))]
+    pub tags: Vec<String>,
+
+    pub priority: Option<Priority>,
+
+    pub recurrence: Option<Recurrence>,
+
+    /// Recognised phrases in the order they appear
+    pub matches: Vec<QuickAddMatch>,
+}
+
+/// What the parser needs besides the text
+#[derive(Clone, Copy, Debug)]
+pub struct ParseOptions {
+    /// Timezone dates and times are written in
+    pub timezone: Tz,
+    /// Time of day of due dates given without a time
+    pub default_time: NaiveTime,
+    /// Relative phrases like `tomorrow` count from here
+    pub now: DateTime<Utc>,
+}
+
+/// A word of the text, and its lowercase form without trailing punctuation
+pub(crate) struct Word {
+    pub text: String,
+    pub key: String,
+    pub quoted: bool,
+}
+
+fn tokenize(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut rest = text.trim_start();
+
+    while !rest.is_empty() {
+        let (text, quoted, remaining) = match rest.strip_prefix('"') {
+            Some(inner) => match inner.find('"') {
+                Some(end) => (&inner[..end], true, &inner[end + 1..]),
+                // An unbalanced quote is just a character
+                None => split_word(rest),
+            },
+            None => split_word(rest),
+        };
+        if quoted || !text.is_empty() {
+            words.push(Word {
+                text: text.to_string(),
+                key: text
+                    .to_lowercase()
+                    .trim_end_matches([',', ';', '.'])
+                    .to_string(),
+                quoted,
+            });
+        }
+        rest = remaining.trim_start();
+    }
+    words
+}
+
+fn split_word(text: &str) -> (&str, bool, &str) {
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    (&text[..end], false, &text[end..])
+}
+
+pub fn parse(text: &str, options: &ParseOptions) -> QuickAdd {
+    let words = tokenize(text);
+    let today = options.now.with_timezone(&options.timezone).date_naive();
+
+    let mut used = vec![false; words.len()];
+    let mut matches = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+    let mut priority = None;
+    let mut recurrence = None;
+    let mut date = None;
+    let mut time = None;
+
+    let mut i = 0;
+    while i < words.len() {
+        let found = if words[i].quoted {
+            None
+        } else {
+            phrases::find(
+                &words[i..],
+                options.now,
+                today,
+                date.is_none(),
+                time.is_none(),
+                recurrence.is_none(),
+            )
+        };
+        let Some((length, phrase)) = found else {
+            i += 1;
+            continue;
+        };
+
+        let kind = match phrase {
+            Phrase::Tag(tag) => {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+                MatchKind::Tag
+            }
+            Phrase::Priority(found) => {
+                priority = Some(found);
+                MatchKind::Priority
+            }
+            Phrase::Recurrence(found) => {
+                recurrence = Some(found);
+                MatchKind::Recurrence
+            }
+            Phrase::Date(found) => {
+                date = Some(found);
+                MatchKind::Date
+            }
+            Phrase::Time(found) => {
+                time = Some(found);
+                MatchKind::Time
+            }
+        };
+        matches.push(QuickAddMatch {
+            kind,
+            text: words[i..i + length]
+                .iter()
+                .map(|word| word.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+        });
+        used[i..i + length].fill(true);
+        i += length;
+    }
+
+    let title = words
+        .iter()
+        .zip(&used)
+        .filter(|(_, used)| !**used)
+        .map(|(word, _)| word.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches([',', ';'])
+        .to_string();
+
+    QuickAdd {
+        title,
+        due_at: due_at(date, time, recurrence.as_ref(), options),
+        tags,
+        priority,
+        recurrence,
+        matches,
+    }
+}
+
+/// Combines the date, time and recurrence found into the first time the todo is due
+fn due_at(
+    date: Option<DateMatch>,
+    time: Option<NaiveTime>,
+    recurrence: Option<&Recurrence>,
+    options: &ParseOptions,
+) -> Option<DateTime<Utc>> {
+    let now = options.now.with_timezone(&options.timezone);
+    let today = now.date_naive();
+
+    let (date, implied_time) = match date {
+        Some(DateMatch::Instant(instant)) => return Some(instant),
+        Some(DateMatch::Day(date, implied_time)) => (Some(date), implied_time),
+        None => (None, None),
+    };
+    let weekdays = recurrence.map(Recurrence::weekdays).unwrap_or_default();
+
+    match (date, time.or(implied_time)) {
+        (Some(date), time) => Some(local(date, time.unwrap_or(options.default_time), options.timezone)),
+        // The first of the recurring weekdays still ahead
+        (None, time) if !weekdays.is_empty() => {
+            let time = time.unwrap_or(options.default_time);
+            (0..=7)
+                .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+                .filter(|date| weekdays.contains(&date.weekday()))
+                .map(|date| local(date, time, options.timezone))
+                .find(|due_at| *due_at > options.now)
+        }
+        (None, Some(time)) => {
+            let due_at = local(today, time, options.timezone);
+            if due_at > options.now {
+                Some(due_at)
+            } else {
+                today
+                    .checked_add_days(Days::new(1))
+                    .map(|tomorrow| local(tomorrow, time, options.timezone))
+            }
+        }
+        (None, None) => None,
+    }
+}
+
+/// Local time to UTC; times skipped by a DST change move forward by the size of the gap
+fn local(date: NaiveDate, time: NaiveTime, timezone: Tz) -> DateTime<Utc> {
+    let local = date.and_time(time);
+    timezone
+        .from_local_datetime(&local)
+        .earliest()
+        .or_else(|| timezone.from_local_datetime(&(local + chrono::Duration::hours(1))).earliest())
+        .map(|due_at| due_at.with_timezone(&Utc))
+        .unwrap_or_else(|| local.and_utc())
+}
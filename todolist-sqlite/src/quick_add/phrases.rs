@@ -0,0 +1,343 @@
+//! Recognisers for the phrases of a quick-add text. Each one looks at the words from
+//! the current position on and returns how many it used.
+
+use chrono::{DateTime, Datelike, Days, Duration, Months, NaiveDate, NaiveTime, Utc, Weekday};
+
+use crate::quick_add::{Frequency, Priority, Recurrence, WEEK, Word};
+
+pub(crate) enum DateMatch {
+    /// A calendar day, with the time the phrase implies (`tonight`)
+    Day(NaiveDate, Option<NaiveTime>),
+    /// An exact moment (`in 2 hours`)
+    Instant(DateTime<Utc>),
+}
+
+pub(crate) enum Phrase {
+    Tag(String),
+    Priority(Priority),
+    Recurrence(Recurrence),
+    Date(DateMatch),
+    Time(NaiveTime),
+}
+
+/// Finds the phrase starting at the first word. A date, a time and a recurrence are
+/// each only taken once; later ones stay part of the title.
+pub(crate) fn find(
+    words: &[Word],
+    now: DateTime<Utc>,
+    today: NaiveDate,
+    want_date: bool,
+    want_time: bool,
+    want_recurrence: bool,
+) -> Option<(usize, Phrase)> {
+    // Phrases never reach into quoted text
+    let keys: Vec<&str> = words
+        .iter()
+        .take_while(|word| !word.quoted)
+        .map(|word| word.key.as_str())
+        .collect();
+    let first = *keys.first()?;
+
+    if let Some(tag) = tag(first) {
+        return Some((1, Phrase::Tag(tag)));
+    }
+    if let Some(priority) = priority(first) {
+        return Some((1, Phrase::Priority(priority)));
+    }
+    if want_recurrence && let Some((length, recurrence)) = recurrence(&keys) {
+        return Some((length, Phrase::Recurrence(recurrence)));
+    }
+    if want_date
+        && let Some((length, date)) =
+            after_connector(&keys, &["due on", "due by", "due", "on", "by"], |keys| date(keys, now, today))
+    {
+        return Some((length, Phrase::Date(date)));
+    }
+    if want_time && let Some((length, time)) = after_connector(&keys, &["at", "by", "@"], time) {
+        return Some((length, Phrase::Time(time)));
+    }
+    None
+}
+
+/// Matches `phrase` alone or after one of the connecting words, which are only taken
+/// along when the phrase follows them
+fn after_connector<T>(
+    keys: &[&str],
+    connectors: &[&str],
+    phrase: impl Fn(&[&str]) -> Option<(usize, T)>,
+) -> Option<(usize, T)> {
+    for connector in connectors {
+        let length = connector.split(' ').count();
+        let matches = keys.len() > length
+            && keys[..length].iter().copied().eq(connector.split(' '));
+        if matches && let Some((found, value)) = phrase(&keys[length..]) {
+            return Some((length + found, value));
+        }
+    }
+    phrase(keys)
+}
+
+fn tag(key: &str) -> Option<String> {
+    let name = key.strip_prefix('#')?;
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-'))
+        && !name.chars().all(|c| c.is_ascii_digit());
+    valid.then(|| name.to_string())
+}
+
+fn priority(key: &str) -> Option<Priority> {
+    match key.strip_prefix('!')? {
+        "low" => Some(Priority::Low),
+        "medium" | "med" | "normal" => Some(Priority::Medium),
+        "high" => Some(Priority::High),
+        "urgent" => Some(Priority::Urgent),
+        _ => None,
+    }
+}
+
+fn recurrence(keys: &[&str]) -> Option<(usize, Recurrence)> {
+    let single = |frequency| Some((1, Recurrence::new(frequency, 1, &[])));
+    match keys[0] {
+        "daily" | "everyday" => return single(Frequency::Daily),
+        "weekly" => return single(Frequency::Weekly),
+        "monthly" => return single(Frequency::Monthly),
+        "yearly" | "annually" => return single(Frequency::Yearly),
+        "every" => {}
+        _ => return None,
+    }
+
+    let rest = &keys[1..];
+    let next = *rest.first()?;
+    if let Some(frequency) = unit(next, false) {
+        return Some((2, Recurrence::new(frequency, 1, &[])));
+    }
+    if next == "other"
+        && let Some(frequency) = rest.get(1).and_then(|key| unit(key, false))
+    {
+        return Some((3, Recurrence::new(frequency, 2, &[])));
+    }
+    if let Some(interval) = number(next).filter(|interval| *interval > 0)
+        && let Some(frequency) = rest.get(1).and_then(|key| unit(key, true))
+    {
+        return Some((3, Recurrence::new(frequency, interval, &[])));
+    }
+    match next {
+        "weekday" | "weekdays" => return Some((2, Recurrence::new(Frequency::Weekly, 1, &WEEK[..5]))),
+        "weekend" | "weekends" => return Some((2, Recurrence::new(Frequency::Weekly, 1, &WEEK[5..]))),
+        _ => {}
+    }
+
+    // every monday, wednesday and friday
+    let mut days = vec![weekday(next, true)?];
+    let mut length = 2;
+    loop {
+        let skip = usize::from(matches!(keys.get(length), Some(&"and") | Some(&"&")));
+        match keys.get(length + skip).and_then(|key| weekday(key, true)) {
+            Some(day) => {
+                days.push(day);
+                length += skip + 1;
+            }
+            None => break,
+        }
+    }
+    days.sort_by_key(|day| day.num_days_from_monday());
+    days.dedup();
+    Some((length, Recurrence::new(Frequency::Weekly, 1, &days)))
+}
+
+/// `day`, `week`, `month` or `year`, also in plural when counting
+fn unit(key: &str, plural: bool) -> Option<Frequency> {
+    let key = if plural { key.strip_suffix('s').unwrap_or(key) } else { key };
+    match key {
+        "day" => Some(Frequency::Daily),
+        "week" => Some(Frequency::Weekly),
+        "month" => Some(Frequency::Monthly),
+        "year" => Some(Frequency::Yearly),
+        _ => None,
+    }
+}
+
+fn number(key: &str) -> Option<u32> {
+    const WORDS: [&str; 12] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve",
+    ];
+    match key {
+        "a" | "an" => Some(1),
+        _ => WORDS
+            .iter()
+            .position(|word| *word == key)
+            .map(|index| index as u32 + 1)
+            .or_else(|| {
+                key.parse()
+                    .ok()
+                    .filter(|number| (1..1000).contains(number) && key.chars().all(|c| c.is_ascii_digit()))
+            }),
+    }
+}
+
+/// Full weekday names, and abbreviations where `abbreviated` says a weekday is expected
+fn weekday(key: &str, abbreviated: bool) -> Option<Weekday> {
+    let day = match key {
+        "monday" => Weekday::Mon,
+        "tuesday" => Weekday::Tue,
+        "wednesday" => Weekday::Wed,
+        "thursday" => Weekday::Thu,
+        "friday" => Weekday::Fri,
+        "saturday" => Weekday::Sat,
+        "sunday" => Weekday::Sun,
+        _ if !abbreviated => return None,
+        "mon" => Weekday::Mon,
+        "tue" | "tues" => Weekday::Tue,
+        "wed" => Weekday::Wed,
+        "thu" | "thur" | "thurs" => Weekday::Thu,
+        "fri" => Weekday::Fri,
+        "sat" => Weekday::Sat,
+        "sun" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(day)
+}
+
+fn month(key: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january", "february", "march", "april", "may", "june", "july", "august", "september", "october",
+        "november", "december",
+    ];
+    MONTHS
+        .iter()
+        .position(|name| *name == key || (key.len() >= 3 && name.starts_with(key) && key.len() <= 4))
+        .map(|index| index as u32 + 1)
+}
+
+/// `1`, `1st`, `22nd`...
+fn day_of_month(key: &str) -> Option<u32> {
+    let digits = key.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &key[digits.len()..];
+    if !matches!(suffix, "" | "st" | "nd" | "rd" | "th") || digits.is_empty() || digits.len() > 2 {
+        return None;
+    }
+    digits.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+fn year(key: &str) -> Option<i32> {
+    (key.len() == 4).then(|| key.parse().ok()).flatten().filter(|year| *year >= 1970)
+}
+
+/// First day after `from` falling on `day`, or `from` itself when `inclusive`
+fn next_weekday(from: NaiveDate, day: Weekday, inclusive: bool) -> NaiveDate {
+    let ahead = (7 + day.num_days_from_monday() - from.weekday().num_days_from_monday()) % 7;
+    let ahead = if ahead == 0 && !inclusive { 7 } else { ahead };
+    from + Days::new(ahead.into())
+}
+
+fn date(keys: &[&str], now: DateTime<Utc>, today: NaiveDate) -> Option<(usize, DateMatch)> {
+    let day = |length, date| Some((length, DateMatch::Day(date, None)));
+    let first = *keys.first()?;
+    let second = keys.get(1).copied();
+
+    match first {
+        "today" => return day(1, today),
+        "tonight" => return Some((1, DateMatch::Day(today, NaiveTime::from_hms_opt(20, 0, 0)))),
+        "tomorrow" | "tmrw" | "tmr" => return day(1, today + Days::new(1)),
+        "day" if keys.get(1..3) == Some(&["after", "tomorrow"][..]) => return day(3, today + Days::new(2)),
+        "weekend" => return day(1, next_weekday(today, Weekday::Sat, true)),
+        _ => {}
+    }
+    if let Some(day_name) = weekday(first, false) {
+        return day(1, next_weekday(today, day_name, false));
+    }
+
+    if first == "next" || first == "this" {
+        let next = second?;
+        return match (first, next) {
+            ("next", "week") => day(2, next_weekday(today, Weekday::Mon, false)),
+            ("next", "month") => day(2, today.with_day(1)?.checked_add_months(Months::new(1))?),
+            ("next", "year") => day(2, NaiveDate::from_ymd_opt(today.year() + 1, 1, 1)?),
+            ("this", "weekend") => day(2, next_weekday(today, Weekday::Sat, true)),
+            _ => day(2, next_weekday(today, weekday(next, true)?, first == "this")),
+        };
+    }
+
+    if first == "in" {
+        let count = number(second?)?;
+        let unit = keys.get(2)?;
+        let unit = unit.strip_suffix('s').unwrap_or(unit);
+        let due = match unit {
+            "minute" | "min" => DateMatch::Instant(now + Duration::minutes(count.into())),
+            "hour" | "hr" => DateMatch::Instant(now + Duration::hours(count.into())),
+            "day" => DateMatch::Day(today + Days::new(count.into()), None),
+            "week" => DateMatch::Day(today + Days::new(u64::from(count) * 7), None),
+            "month" => DateMatch::Day(today.checked_add_months(Months::new(count))?, None),
+            "year" => DateMatch::Day(today.checked_add_months(Months::new(count * 12))?, None),
+            _ => return None,
+        };
+        return Some((3, due));
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(first, "%Y-%m-%d") {
+        return day(1, date);
+    }
+    calendar_date(keys, today).map(|(length, date)| (length, DateMatch::Day(date, None)))
+}
+
+/// `nov 1`, `november 1st 2027`, `1 nov`, `1st of november`. Without a year it is the
+/// next such day, today included.
+fn calendar_date(keys: &[&str], today: NaiveDate) -> Option<(usize, NaiveDate)> {
+    let (month, day, length) = match (month(keys[0]), keys.get(1).and_then(|key| day_of_month(key))) {
+        (Some(month), Some(day)) => (month, day, 2),
+        _ => {
+            let day = day_of_month(keys[0])?;
+            let of = usize::from(keys.get(1) == Some(&"of"));
+            (month(keys.get(1 + of)?)?, day, 2 + of)
+        }
+    };
+
+    if let Some(year) = keys.get(length).and_then(|key| year(key)) {
+        return Some((length + 1, NaiveDate::from_ymd_opt(year, month, day)?));
+    }
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day);
+    match this_year {
+        Some(date) if date >= today => Some((length, date)),
+        _ => Some((length, NaiveDate::from_ymd_opt(today.year() + 1, month, day)?)),
+    }
+}
+
+/// `9am`, `9:30 pm`, `21:00`, `noon`, `midnight`
+fn time(keys: &[&str]) -> Option<(usize, NaiveTime)> {
+    match keys[0] {
+        "noon" | "midday" => return Some((1, NaiveTime::from_hms_opt(12, 0, 0)?)),
+        "midnight" => return Some((1, NaiveTime::MIN)),
+        _ => {}
+    }
+
+    let meridiem = |key: &str| match key {
+        "am" | "a.m" => Some(false),
+        "pm" | "p.m" => Some(true),
+        _ => None,
+    };
+    let key = keys[0];
+    let split = key.find(|c: char| !c.is_ascii_digit() && c != ':').unwrap_or(key.len());
+    let (clock, suffix) = key.split_at(split);
+
+    let (clock, pm, length) = match (suffix, keys.get(1).and_then(|key| meridiem(key))) {
+        ("", Some(pm)) => (clock, Some(pm), 2),
+        ("", None) => (clock, None, 1),
+        (suffix, _) => (clock, Some(meridiem(suffix)?), 1),
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse::<u32>().ok()?, minute.parse().ok()?),
+        // A bare number is not a time, `9am` is
+        None if pm.is_some() => (clock.parse::<u32>().ok()?, 0),
+        _ => return None,
+    };
+    if hour > 99 || clock.starts_with(':') {
+        return None;
+    }
+
+    let hour = match pm {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(pm) => hour % 12 + if pm { 12 } else { 0 },
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0).map(|time| (length, time))
+}
@@ -1,41 +1,144 @@
-use axum::{Json, Router, routing::get};
+use std::sync::Arc;
+
+use axum::{Json, Router, extract::DefaultBodyLimit, middleware, routing::get};
+use tower::limit::GlobalConcurrencyLimitLayer;
 use utoipa_axum::router::OpenApiRouter;
-use utoipa_swagger_ui::SwaggerUi;
+use utoipa_swagger_ui::{SwaggerUi, Url};
 
 use crate::{
     config::{
+        api_version::ApiVersion,
         app_info,
         open_api::{self},
         settings::AppSettings,
     },
-    controller::todo_controller::TodoController,
+    controller::{
+        admin_controller::AdminController, attachment_controller::AttachmentController, comment_controller::CommentController,
+        dependency_controller::DependencyController,
+        quick_add_controller::QuickAddController, stats_controller::StatsController,
+        smart_list_controller::SmartListController, sync_controller::SyncController,
+        tag_controller::TagController, time_controller::TimeController,
+        v1::todo_controller::TodoControllerV1,
+        v2::todo_controller::TodoControllerV2,
+        workflow_controller::WorkflowController,
+    },
+    graphql,
+    middleware::{
+        compression, cors, deprecation, idempotency,
+        rate_limit::{self, RateLimiter},
+        security_headers,
+    },
     types::AppState,
+    web,
 };
 
-pub fn create_routes(app_settings: &AppSettings) -> OpenApiRouter<AppState> {
+/// Routes of one API version, relative to `{app.prefix}/{version}`, with their OpenAPI document
+pub fn create_routes(
+    app_settings: &AppSettings,
+    version: ApiVersion,
+) -> (Router<AppState>, utoipa::openapi::OpenApi) {
     let app_info = app_info::AppInfo::new();
-    let openapi = open_api::configure_openapi(&app_info, app_settings);
+    let openapi = open_api::configure_openapi(&app_info, app_settings, version);
+
+    // New resources are only added to the current version
+    let todo_routes = match version {
+        ApiVersion::V1 => TodoControllerV1::router(),
+        ApiVersion::V2 => TodoControllerV2::router()
+            .merge(AttachmentController::router(&app_settings.attachments))
+            .merge(CommentController::router())
+            .merge(StatsController::router())
+            .merge(QuickAddController::router())
+            .merge(WorkflowController::router())
+            .merge(TimeController::router())
+            .merge(DependencyController::router())
+            .merge(TagController::todo_router()),
+    };
+    let resources = match version {
+        ApiVersion::V1 => OpenApiRouter::new(),
+        ApiVersion::V2 => SyncController::router()
+            .merge(TagController::router())
+            .merge(SmartListController::router())
+            .merge(AdminController::router()),
+    };
+    let (router, mut api) = OpenApiRouter::with_openapi(openapi)
+        .nest("/todo", todo_routes)
+        .merge(resources)
+        .split_for_parts();
+    open_api::mark_deprecated(&mut api, app_settings, version);
 
-    OpenApiRouter::with_openapi(openapi).nest("/todo", TodoController::router())
+    let successor = version
+        .successor()
+        .map(|successor| format!("{}/{}", app_settings.app.prefix, successor.name()));
+    let router = deprecation::apply(router, version.config(app_settings), successor.as_deref());
+    (router, api)
 }
 
 pub fn build_router(app_settings: &AppSettings, app_state: AppState) -> Router {
-    let (api_router, api) = create_routes(app_settings).split_for_parts();
-    let api_clone = api.clone();
-    let openapi_json_path = format!("{}{}", app_settings.app.prefix, app_settings.openapi.json_path);
-    let ui_path = app_settings.openapi.ui_path.clone();
-    let openapi_json_path_for_route = app_settings.openapi.json_path.clone();
-
-    let api_docs = Router::new()
-        .merge(SwaggerUi::new(ui_path)
-            .url(openapi_json_path, api))
-        .route(
-            openapi_json_path_for_route.as_str(),
-            get(|| async move { Json(api_clone) }),
-        )
-        .merge(api_router);
-
-    Router::new()
-        .nest(&app_settings.app.prefix, api_docs)
-        .with_state(app_state)
+    let mut api_docs = Router::new();
+    let mut swagger_urls = Vec::new();
+
+    // Newest first, so it is the one Swagger UI opens with
+    for version in ApiVersion::ALL.into_iter().rev() {
+        let (version_router, api) = create_routes(app_settings, version);
+        let json_path = app_settings.openapi.json_path_for(version);
+        let deprecated = version.config(app_settings).deprecated_at.is_some();
+
+        // Swagger UI needs 'static names and urls; built once per router
+        let name = if deprecated {
+            format!("{} (deprecated)", version.name())
+        } else {
+            version.name().to_string()
+        };
+        let url = format!("{}{}", app_settings.app.prefix, json_path);
+        swagger_urls.push((
+            Url::with_primary(name.leak(), url.leak(), swagger_urls.is_empty()),
+            api.clone(),
+        ));
+
+        if version == ApiVersion::LEGACY {
+            api_docs = api_docs.merge(version_router.clone());
+        }
+        api_docs = api_docs
+            .nest(&format!("/{}", version.name()), version_router)
+            .route(&json_path, get(|| async move { Json(api) }));
+    }
+
+    let api_docs = api_docs.merge(SwaggerUi::new(app_settings.openapi.ui_path.clone()).urls(swagger_urls));
+
+    let api_docs = if app_settings.graphql.enabled {
+        api_docs.merge(graphql::router(app_settings, app_state.clone()))
+    } else {
+        api_docs
+    };
+
+    let mut router = Router::new().nest(&app_settings.app.prefix, api_docs);
+    if app_settings.web.enabled {
+        router = router.merge(web::router());
+    }
+
+    let limits = &app_settings.limits;
+    let clients = Arc::new(limits.rate_limit.clone());
+    let mut router = router
+        .layer(middleware::from_fn_with_state((app_state.clone(), clients), idempotency::idempotency))
+        .layer(DefaultBodyLimit::max(limits.body_max_bytes))
+        .layer(GlobalConcurrencyLimitLayer::new(limits.max_in_flight));
+
+    if limits.rate_limit.enabled {
+        let limiter = Arc::new(RateLimiter::new(limits.rate_limit.clone()));
+        router = router.layer(middleware::from_fn_with_state(limiter, rate_limit::rate_limit));
+    }
+
+    let middleware_config = &app_settings.middleware;
+    if middleware_config.compression.enabled {
+        router = router.layer(compression::layer(&middleware_config.compression));
+    }
+    if middleware_config.security_headers.enabled {
+        router = security_headers::apply(router, &middleware_config.security_headers);
+    }
+    // Outermost so preflight requests and 429 responses also carry CORS headers
+    if middleware_config.cors.enabled {
+        router = router.layer(cors::layer(&middleware_config.cors));
+    }
+
+    router.with_state(app_state)
 }
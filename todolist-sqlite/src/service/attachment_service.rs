@@ -0,0 +1,307 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use chrono::DateTime;
+use sha2::{Digest, Sha256};
+use sqlite::Connection;
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+    config::settings::AttachmentsConfig, db::todo_exists,
+    model::attachment_response::AttachmentResponse, types::Db,
+};
+
+/// An attachment's metadata and where its content lives on disk
+#[derive(Debug, Clone)]
+pub struct StoredAttachment {
+    pub metadata: AttachmentResponse,
+    pub path: PathBuf,
+}
+
+/// Upload being written to a temporary file while its size and hash are computed.
+/// The file is removed on drop unless [`AttachmentServiceInterface::save`] keeps it.
+pub struct StagedUpload {
+    file: tokio::fs::File,
+    path: PathBuf,
+    hasher: Sha256,
+    size: u64,
+}
+
+impl StagedUpload {
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub async fn write(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.file
+            .write_all(chunk)
+            .await
+            .map_err(|e| format!("Failed to write upload: {}", e))?;
+        self.hasher.update(chunk);
+        self.size += chunk.len() as u64;
+        Ok(())
+    }
+}
+
+impl Drop for StagedUpload {
+    fn drop(&mut self) {
+        // Already gone once saved
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[async_trait::async_trait]
+pub trait AttachmentServiceInterface: Send + Sync {
+    fn limits(&self) -> &AttachmentsConfig;
+    async fn stage(&self) -> Result<StagedUpload, String>;
+    /// Returns `None`, keeping nothing, when the todo item no longer exists
+    async fn save(
+        &self,
+        db: &Db,
+        todo_id: i64,
+        file_name: String,
+        content_type: String,
+        upload: StagedUpload,
+    ) -> Result<Option<AttachmentResponse>, String>;
+    async fn list(&self, db: &Db, todo_id: i64) -> Result<Vec<AttachmentResponse>, String>;
+    async fn get(&self, db: &Db, todo_id: i64, id: i64) -> Result<StoredAttachment, String>;
+    /// Returns false when there was no such attachment
+    async fn delete(&self, db: &Db, todo_id: i64, id: i64) -> Result<bool, String>;
+    async fn delete_for_todo(&self, db: &Db, todo_id: i64) -> Result<(), String>;
+}
+
+/// Stores attachment content under `attachments.dir`, one file per distinct SHA-256,
+/// so uploading the same file twice only keeps one copy.
+///
+/// Files are moved into place and removed while holding the database lock, so a
+/// concurrent upload can never reuse a file that is being deleted as an orphan.
+pub struct AttachmentServiceImpl {
+    config: AttachmentsConfig,
+    dir: PathBuf,
+}
+
+impl AttachmentServiceImpl {
+    pub fn new(config: AttachmentsConfig) -> Self {
+        Self {
+            dir: PathBuf::from(&config.dir),
+            config,
+        }
+    }
+
+    fn content_path(&self, sha256: &str) -> PathBuf {
+        self.dir.join(&sha256[..2]).join(sha256)
+    }
+
+    fn staging_dir(&self) -> PathBuf {
+        self.dir.join("tmp")
+    }
+
+    /// Deletes the content of `sha256` once no attachment refers to it any more
+    fn remove_if_orphaned(&self, connection: &Connection, sha256: &str) -> Result<(), String> {
+        let mut statement = connection
+            .prepare("SELECT COUNT(*) FROM attachments WHERE sha256 = ?")
+            .unwrap();
+        statement.bind((1, sha256)).unwrap();
+        statement.next().unwrap();
+        if statement.read::<i64, _>(0).unwrap() > 0 {
+            return Ok(());
+        }
+
+        match fs::remove_file(self.content_path(sha256)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove attachment {}: {}", sha256, e))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AttachmentServiceInterface for AttachmentServiceImpl {
+    fn limits(&self) -> &AttachmentsConfig {
+        &self.config
+    }
+
+    async fn stage(&self) -> Result<StagedUpload, String> {
+        static NEXT_UPLOAD: AtomicU64 = AtomicU64::new(0);
+
+        let staging_dir = self.staging_dir();
+        tokio::fs::create_dir_all(&staging_dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", staging_dir.display(), e))?;
+
+        let path = staging_dir.join(format!(
+            "{}-{}-{}.part",
+            std::process::id(),
+            now(),
+            NEXT_UPLOAD.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = tokio::fs::File::create(&path)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+
+        Ok(StagedUpload {
+            file,
+            path,
+            hasher: Sha256::new(),
+            size: 0,
+        })
+    }
+
+    async fn save(
+        &self,
+        db: &Db,
+        todo_id: i64,
+        file_name: String,
+        content_type: String,
+        upload: StagedUpload,
+    ) -> Result<Option<AttachmentResponse>, String> {
+        upload
+            .file
+            .sync_all()
+            .await
+            .map_err(|e| format!("Failed to write upload: {}", e))?;
+        let sha256 = format!("{:x}", upload.hasher.clone().finalize());
+        let content_path = self.content_path(&sha256);
+        let created_at = now();
+
+        let connection = db.lock().unwrap();
+        if !todo_exists(&connection, todo_id) {
+            return Ok(None);
+        }
+
+        if !content_path.exists() {
+            fs::create_dir_all(content_path.parent().unwrap())
+                .and_then(|_| fs::rename(&upload.path, &content_path))
+                .map_err(|e| format!("Failed to store attachment {}: {}", sha256, e))?;
+        }
+
+        let mut statement = connection
+            .prepare(
+                "INSERT INTO attachments (todo_id, file_name, content_type, size, sha256, created_at)
+                VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .unwrap();
+        statement.bind((1, todo_id)).unwrap();
+        statement.bind((2, file_name.as_str())).unwrap();
+        statement.bind((3, content_type.as_str())).unwrap();
+        statement.bind((4, upload.size as i64)).unwrap();
+        statement.bind((5, sha256.as_str())).unwrap();
+        statement.bind((6, created_at)).unwrap();
+        statement.next().unwrap();
+
+        let mut statement = connection.prepare("SELECT last_insert_rowid()").unwrap();
+        statement.next().unwrap();
+        let id = statement.read::<i64, _>(0).unwrap();
+
+        Ok(Some(AttachmentResponse {
+            id,
+            todo_id,
+            file_name,
+            content_type,
+            size: upload.size as i64,
+            sha256,
+            created_at: DateTime::from_timestamp(created_at, 0).unwrap_or_default(),
+        }))
+    }
+
+    async fn list(&self, db: &Db, todo_id: i64) -> Result<Vec<AttachmentResponse>, String> {
+        let connection = db.lock().unwrap();
+        let query = "SELECT id, todo_id, file_name, content_type, size, sha256, created_at
+            FROM attachments WHERE todo_id = ? ORDER BY id";
+
+        let mut statement = connection.prepare(query).unwrap();
+        statement.bind((1, todo_id)).unwrap();
+
+        Ok(statement
+            .iter()
+            .map(|row| read_attachment(&row.unwrap()))
+            .collect())
+    }
+
+    async fn get(&self, db: &Db, todo_id: i64, id: i64) -> Result<StoredAttachment, String> {
+        let connection = db.lock().unwrap();
+        let query = "SELECT id, todo_id, file_name, content_type, size, sha256, created_at
+            FROM attachments WHERE todo_id = ? AND id = ?";
+
+        let mut statement = connection.prepare(query).unwrap();
+        statement.bind((1, todo_id)).unwrap();
+        statement.bind((2, id)).unwrap();
+
+        statement
+            .iter()
+            .map(|row| read_attachment(&row.unwrap()))
+            .next()
+            .map(|metadata| StoredAttachment {
+                path: self.content_path(&metadata.sha256),
+                metadata,
+            })
+            .ok_or_else(|| format!("Attachment {} of todo item {} not found", id, todo_id))
+    }
+
+    async fn delete(&self, db: &Db, todo_id: i64, id: i64) -> Result<bool, String> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare("DELETE FROM attachments WHERE todo_id = ? AND id = ? RETURNING sha256")
+            .unwrap();
+        statement.bind((1, todo_id)).unwrap();
+        statement.bind((2, id)).unwrap();
+
+        let Some(sha256) = statement
+            .iter()
+            .map(|row| row.unwrap().read::<&str, _>("sha256").to_string())
+            .next()
+        else {
+            return Ok(false);
+        };
+        drop(statement);
+
+        self.remove_if_orphaned(&connection, &sha256)?;
+        Ok(true)
+    }
+
+    async fn delete_for_todo(&self, db: &Db, todo_id: i64) -> Result<(), String> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare("DELETE FROM attachments WHERE todo_id = ? RETURNING sha256")
+            .unwrap();
+        statement.bind((1, todo_id)).unwrap();
+
+        let mut hashes = statement
+            .iter()
+            .map(|row| row.unwrap().read::<&str, _>("sha256").to_string())
+            .collect::<Vec<String>>();
+        drop(statement);
+        hashes.sort();
+        hashes.dedup();
+
+        for sha256 in hashes {
+            self.remove_if_orphaned(&connection, &sha256)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_attachment(row: &sqlite::Row) -> AttachmentResponse {
+    AttachmentResponse {
+        id: row.read::<i64, _>("id"),
+        todo_id: row.read::<i64, _>("todo_id"),
+        file_name: row.read::<&str, _>("file_name").to_string(),
+        content_type: row.read::<&str, _>("content_type").to_string(),
+        size: row.read::<i64, _>("size"),
+        sha256: row.read::<&str, _>("sha256").to_string(),
+        created_at: DateTime::from_timestamp(row.read::<i64, _>("created_at"), 0)
+            .unwrap_or_default(),
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
@@ -0,0 +1,200 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use sqlite::{Connection, OpenFlags};
+
+use crate::{
+    config::settings::BackupConfig,
+    db::{backup, migrations},
+    model::backup_response::{BackupResponse, RestoreResponse},
+    types::Db,
+    worker::Shutdown,
+};
+
+const NAME_PREFIX: &str = "todo-";
+const NAME_SUFFIX: &str = ".db";
+const NAME_TIME_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+
+#[async_trait::async_trait]
+pub trait BackupServiceInterface: Send + Sync {
+    fn config(&self) -> &BackupConfig;
+    /// Snapshots the live database, then deletes the oldest snapshots beyond `backup.keep`
+    async fn create(&self, db: &Db) -> Result<BackupResponse, String>;
+    /// Every snapshot, newest first
+    async fn list(&self) -> Result<Vec<BackupResponse>, String>;
+    async fn find(&self, name: &str) -> Option<BackupResponse>;
+    /// Checks the snapshot's integrity and schema version, returning the version
+    async fn validate(&self, name: &str) -> Result<i64, String>;
+    /// Replaces the live database with a validated snapshot, after snapshotting it first
+    async fn restore(&self, db: &Db, name: &str) -> Result<RestoreResponse, String>;
+}
+
+pub struct BackupServiceImpl {
+    config: BackupConfig,
+}
+
+impl BackupServiceImpl {
+    pub fn new(config: BackupConfig) -> Self {
+        Self { config }
+    }
+
+    fn dir(&self) -> &Path {
+        Path::new(&self.config.dir)
+    }
+
+    /// Path of a snapshot; only names the service generates are accepted, so a name can't
+    /// point outside `backup.dir`
+    fn path_of(&self, name: &str) -> Option<PathBuf> {
+        created_at(name).map(|_| self.dir().join(name))
+    }
+
+    fn snapshot(&self, connection: &Connection) -> Result<BackupResponse, String> {
+        fs::create_dir_all(self.dir())
+            .map_err(|e| format!("Could not create {}: {}", self.config.dir, e))?;
+
+        let mut at = Utc::now();
+        while self.dir().join(file_name(at)).exists() {
+            at += TimeDelta::milliseconds(1);
+        }
+        let name = file_name(at);
+
+        // Written under another name first, so a failed backup never looks like a snapshot
+        let partial = self.dir().join(format!("{}.partial", name));
+        let _ = fs::remove_file(&partial);
+        if let Err(e) = backup::write_snapshot(connection, &partial) {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+        fs::rename(&partial, self.dir().join(&name))
+            .map_err(|e| format!("Could not save snapshot {}: {}", name, e))?;
+
+        info(self.dir(), &name).ok_or_else(|| format!("Snapshot {} disappeared", name))
+    }
+
+    fn rotate(&self) -> Result<(), String> {
+        if self.config.keep == 0 {
+            return Ok(());
+        }
+        for expired in snapshots(self.dir())?.iter().skip(self.config.keep) {
+            fs::remove_file(self.dir().join(&expired.name))
+                .map_err(|e| format!("Could not delete snapshot {}: {}", expired.name, e))?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl BackupServiceInterface for BackupServiceImpl {
+    fn config(&self) -> &BackupConfig {
+        &self.config
+    }
+
+    async fn create(&self, db: &Db) -> Result<BackupResponse, String> {
+        let snapshot = {
+            let connection = db.lock().unwrap();
+            self.snapshot(&connection)?
+        };
+        self.rotate()?;
+        Ok(snapshot)
+    }
+
+    async fn list(&self) -> Result<Vec<BackupResponse>, String> {
+        snapshots(self.dir())
+    }
+
+    async fn find(&self, name: &str) -> Option<BackupResponse> {
+        self.path_of(name)?;
+        info(self.dir(), name)
+    }
+
+    async fn validate(&self, name: &str) -> Result<i64, String> {
+        let path = self
+            .path_of(name)
+            .ok_or_else(|| format!("{} is not a snapshot name", name))?;
+        backup::validate(&path)
+    }
+
+    async fn restore(&self, db: &Db, name: &str) -> Result<RestoreResponse, String> {
+        let schema_version = self.validate(name).await?;
+        let path = self.dir().join(name);
+        let restored = info(self.dir(), name).ok_or_else(|| format!("Snapshot {} not found", name))?;
+        let source = Connection::open_with_flags(&path, OpenFlags::new().with_read_only())
+            .map_err(|e| format!("Could not open {}: {}", path.display(), e))?;
+
+        let connection = db.lock().unwrap();
+        // Not rotated, so it survives until the next scheduled snapshot at least
+        let previous = self.snapshot(&connection)?;
+        backup::copy(&source, &connection)?;
+        migrations::run(&connection)?;
+
+        Ok(RestoreResponse {
+            restored,
+            schema_version,
+            previous,
+        })
+    }
+}
+
+fn file_name(at: DateTime<Utc>) -> String {
+    format!("{}{}{}", NAME_PREFIX, at.format(NAME_TIME_FORMAT), NAME_SUFFIX)
+}
+
+/// Time a snapshot was taken, read from its name; `None` for anything else
+fn created_at(name: &str) -> Option<DateTime<Utc>> {
+    let time = name.strip_prefix(NAME_PREFIX)?.strip_suffix(NAME_SUFFIX)?;
+    NaiveDateTime::parse_from_str(time, NAME_TIME_FORMAT)
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+fn info(dir: &Path, name: &str) -> Option<BackupResponse> {
+    let metadata = fs::metadata(dir.join(name)).ok().filter(|metadata| metadata.is_file())?;
+    Some(BackupResponse {
+        name: name.to_string(),
+        size_bytes: metadata.len(),
+        created_at: created_at(name)?,
+    })
+}
+
+fn snapshots(dir: &Path) -> Result<Vec<BackupResponse>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Could not read {}: {}", dir.display(), e)),
+    };
+
+    let mut snapshots = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| info(dir, entry.file_name().to_str()?))
+        .collect::<Vec<BackupResponse>>();
+    snapshots.sort_by_key(|snapshot| Reverse(snapshot.created_at));
+    Ok(snapshots)
+}
+
+/// Background worker taking a snapshot every `backup.interval_secs`
+pub async fn run_backups(
+    service: Arc<dyn BackupServiceInterface>,
+    db: Db,
+    every: Duration,
+    mut shutdown: Shutdown,
+) {
+    let mut ticker = tokio::time::interval(every);
+    ticker.tick().await;
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                if let Err(e) = service.create(&db).await {
+                    eprintln!("⚠️  Scheduled backup failed: {}", e);
+                }
+            }
+            _ = shutdown.requested() => break,
+        }
+    }
+}
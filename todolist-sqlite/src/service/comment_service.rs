@@ -0,0 +1,418 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, TagEnd, TextMergeStream, html};
+use sqlite::Connection;
+
+use crate::{
+    config::settings::CommentsConfig,
+    db::todo_exists,
+    model::{
+        activity_response::{ActivityKind, ActivityResponse},
+        comment_response::CommentResponse,
+        tag::TagResponse,
+        thread_entry::ThreadEntry,
+        todo_item_response::TodoItemResponse,
+    },
+    types::Db,
+};
+
+/// Longest accepted username, for authors and mentions alike
+const MAX_USERNAME_LENGTH: usize = 39;
+
+/// Columns of a comment aliased `c`, with its mentions space separated
+const COMMENT_COLUMNS: &str = "c.id, c.todo_id, c.author, c.body, c.created_at, c.edited_at,
+    (SELECT group_concat(m.username, ' ') FROM comment_mentions m WHERE m.comment_id = c.id)
+    AS mentions";
+
+#[async_trait::async_trait]
+pub trait CommentServiceInterface: Send + Sync {
+    fn config(&self) -> &CommentsConfig;
+    /// Comments and system activity of a todo item, oldest first
+    async fn thread(&self, db: &Db, todo_id: i64) -> Result<Vec<ThreadEntry>, String>;
+    /// Returns `None`, saving nothing, when the todo item doesn't exist
+    async fn create(
+        &self,
+        db: &Db,
+        todo_id: i64,
+        author: &str,
+        body: String,
+    ) -> Result<Option<CommentResponse>, String>;
+    async fn get(&self, db: &Db, todo_id: i64, id: i64) -> Result<CommentResponse, String>;
+    async fn update(
+        &self,
+        db: &Db,
+        todo_id: i64,
+        id: i64,
+        body: String,
+    ) -> Result<CommentResponse, String>;
+    /// Returns false when there was no such comment
+    async fn delete(&self, db: &Db, todo_id: i64, id: i64) -> Result<bool, String>;
+    async fn delete_for_todo(&self, db: &Db, todo_id: i64) -> Result<(), String>;
+}
+
+/// Every comment also gets a `comment` row in `todo_activity`, so ordering that table
+/// by id interleaves comments with the system entries in the order they happened.
+pub struct CommentServiceImpl {
+    config: CommentsConfig,
+}
+
+impl CommentServiceImpl {
+    pub fn new(config: CommentsConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl CommentServiceInterface for CommentServiceImpl {
+    fn config(&self) -> &CommentsConfig {
+        &self.config
+    }
+
+    async fn thread(&self, db: &Db, todo_id: i64) -> Result<Vec<ThreadEntry>, String> {
+        let connection = db.lock().unwrap();
+        let query = format!(
+            "SELECT a.kind, a.old_value, a.new_value, a.created_at AS activity_created_at,
+                {}
+            FROM todo_activity a LEFT JOIN comments c ON c.id = a.comment_id
+            WHERE a.todo_id = ? ORDER BY a.id",
+            COMMENT_COLUMNS
+        );
+
+        let mut statement = connection.prepare(query).unwrap();
+        statement.bind((1, todo_id)).unwrap();
+
+        Ok(statement
+            .iter()
+            .map(|row| row.unwrap())
+            .filter_map(|row| match row.read::<&str, _>("kind") {
+                "comment" => Some(ThreadEntry::Comment(read_comment(&row))),
+                kind => ActivityKind::parse(kind).map(|kind| {
+                    ThreadEntry::Activity(ActivityResponse {
+                        kind,
+                        from: row.read::<Option<&str>, _>("old_value").map(str::to_string),
+                        to: row.read::<Option<&str>, _>("new_value").map(str::to_string),
+                        created_at: timestamp(row.read::<i64, _>("activity_created_at")),
+                    })
+                }),
+            })
+            .collect())
+    }
+
+    async fn create(
+        &self,
+        db: &Db,
+        todo_id: i64,
+        author: &str,
+        body: String,
+    ) -> Result<Option<CommentResponse>, String> {
+        let connection = db.lock().unwrap();
+        if !todo_exists(&connection, todo_id) {
+            return Ok(None);
+        }
+        let created_at = Utc::now().timestamp();
+
+        let mut statement = connection
+            .prepare(
+                "INSERT INTO comments (todo_id, author, body, created_at) VALUES (?, ?, ?, ?)
+                RETURNING id",
+            )
+            .unwrap();
+        statement.bind((1, todo_id)).unwrap();
+        statement.bind((2, author)).unwrap();
+        statement.bind((3, body.as_str())).unwrap();
+        statement.bind((4, created_at)).unwrap();
+        statement.next().unwrap();
+        let id = statement.read::<i64, _>("id").unwrap();
+        drop(statement);
+
+        save_mentions(&connection, id, &body);
+        let mut statement = connection
+            .prepare(
+                "INSERT INTO todo_activity (todo_id, kind, comment_id, created_at)
+                VALUES (?, 'comment', ?, ?)",
+            )
+            .unwrap();
+        statement.bind((1, todo_id)).unwrap();
+        statement.bind((2, id)).unwrap();
+        statement.bind((3, created_at)).unwrap();
+        statement.next().unwrap();
+
+        find_comment(&connection, todo_id, id).map(Some)
+    }
+
+    async fn get(&self, db: &Db, todo_id: i64, id: i64) -> Result<CommentResponse, String> {
+        let connection = db.lock().unwrap();
+        find_comment(&connection, todo_id, id)
+    }
+
+    async fn update(
+        &self,
+        db: &Db,
+        todo_id: i64,
+        id: i64,
+        body: String,
+    ) -> Result<CommentResponse, String> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare("UPDATE comments SET body = ?, edited_at = ? WHERE todo_id = ? AND id = ?")
+            .unwrap();
+        statement.bind((1, body.as_str())).unwrap();
+        statement.bind((2, Utc::now().timestamp())).unwrap();
+        statement.bind((3, todo_id)).unwrap();
+        statement.bind((4, id)).unwrap();
+        statement.next().unwrap();
+        drop(statement);
+
+        if connection.change_count() > 0 {
+            delete_mentions(&connection, id);
+            save_mentions(&connection, id, &body);
+        }
+        find_comment(&connection, todo_id, id)
+    }
+
+    async fn delete(&self, db: &Db, todo_id: i64, id: i64) -> Result<bool, String> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare("DELETE FROM comments WHERE todo_id = ? AND id = ?")
+            .unwrap();
+        statement.bind((1, todo_id)).unwrap();
+        statement.bind((2, id)).unwrap();
+        statement.next().unwrap();
+        drop(statement);
+        if connection.change_count() == 0 {
+            return Ok(false);
+        }
+
+        delete_mentions(&connection, id);
+        let mut statement = connection
+            .prepare("DELETE FROM todo_activity WHERE comment_id = ?")
+            .unwrap();
+        statement.bind((1, id)).unwrap();
+        statement.next().unwrap();
+        Ok(true)
+    }
+
+    async fn delete_for_todo(&self, db: &Db, todo_id: i64) -> Result<(), String> {
+        let connection = db.lock().unwrap();
+        for query in [
+            "DELETE FROM comment_mentions
+                WHERE comment_id IN (SELECT id FROM comments WHERE todo_id = ?)",
+            "DELETE FROM comments WHERE todo_id = ?",
+            "DELETE FROM todo_activity WHERE todo_id = ?",
+        ] {
+            let mut statement = connection.prepare(query).unwrap();
+            statement.bind((1, todo_id)).unwrap();
+            statement.next().unwrap();
+        }
+        Ok(())
+    }
+}
+
+/// Adds the system entries describing how a todo item changed to its thread. Called by
+/// the todo service with the connection it updated the item through.
+pub(crate) fn record_activity(
+    connection: &Connection,
+    before: &TodoItemResponse,
+    after: &TodoItemResponse,
+) {
+    let mut changes = Vec::new();
+    if before.title != after.title {
+        changes.push((
+            ActivityKind::Renamed,
+            Some(before.title.as_str()),
+            Some(after.title.as_str()),
+        ));
+    }
+    if before.completed != after.completed {
+        let kind = if after.completed {
+            ActivityKind::Completed
+        } else {
+            ActivityKind::Reopened
+        };
+        changes.push((kind, None, None));
+    } else if before.state != after.state {
+        changes.push((
+            ActivityKind::Transitioned,
+            Some(before.state.as_str()),
+            Some(after.state.as_str()),
+        ));
+    }
+    let has = |todo: &TodoItemResponse, tag: &TagResponse| todo.tags.iter().any(|other| other.id == tag.id);
+    for tag in after.tags.iter().filter(|tag| !has(before, tag)) {
+        changes.push((ActivityKind::Tagged, None, Some(tag.name.as_str())));
+    }
+    for tag in before.tags.iter().filter(|tag| !has(after, tag)) {
+        changes.push((ActivityKind::Untagged, Some(tag.name.as_str()), None));
+    }
+
+    let created_at = Utc::now().timestamp();
+    for (kind, old_value, new_value) in changes {
+        let mut statement = connection
+            .prepare(
+                "INSERT INTO todo_activity (todo_id, kind, old_value, new_value, created_at)
+                VALUES (?, ?, ?, ?, ?)",
+            )
+            .unwrap();
+        statement.bind((1, after.id.unwrap_or_default())).unwrap();
+        statement.bind((2, kind.as_str())).unwrap();
+        statement.bind((3, old_value)).unwrap();
+        statement.bind((4, new_value)).unwrap();
+        statement.bind((5, created_at)).unwrap();
+        statement.next().unwrap();
+    }
+}
+
+/// Whether `name` can be used as an author: letters, digits, `_`, `.` and `-`,
+/// starting with a letter, digit or `_`
+pub fn is_username(name: &str) -> bool {
+    name.len() <= MAX_USERNAME_LENGTH
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+        && name.chars().all(is_username_char)
+}
+
+/// Lowercased usernames mentioned as `@name` in the markdown `body`. Mentions in code
+/// spans, code blocks and e-mail addresses don't count, nor do trailing `.` or `-`.
+pub fn parse_mentions(body: &str) -> Vec<String> {
+    let mut usernames = BTreeSet::new();
+    let mut in_code_block = false;
+
+    for event in TextMergeStream::new(Parser::new(body)) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Text(text) if !in_code_block => collect_mentions(&text, &mut usernames),
+            _ => {}
+        }
+    }
+    usernames.into_iter().collect()
+}
+
+fn collect_mentions(text: &str, usernames: &mut BTreeSet<String>) {
+    let mut previous = None;
+    for (index, c) in text.char_indices() {
+        let after_word = previous.is_some_and(|p: char| is_username_char(p) || p == '@');
+        previous = Some(c);
+        if c != '@' || after_word {
+            continue;
+        }
+
+        let rest = &text[index + 1..];
+        let end = rest.find(|c| !is_username_char(c)).unwrap_or(rest.len());
+        let name = rest[..end].trim_end_matches(['.', '-']);
+        if is_username(name) {
+            usernames.insert(name.to_lowercase());
+        }
+    }
+}
+
+fn is_username_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')
+}
+
+/// Renders the markdown `body` as HTML. Raw HTML is escaped rather than passed through,
+/// and links or images with a scheme other than http, https or mailto point nowhere.
+pub fn render_markdown(body: &str) -> String {
+    let options = Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS;
+    let events = Parser::new_ext(body, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if !is_safe_url(&dest_url) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: CowStr::Borrowed("#"),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if !is_safe_url(&dest_url) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: CowStr::Borrowed("#"),
+            title,
+            id,
+        }),
+        event => event,
+    });
+
+    let mut rendered = String::new();
+    html::push_html(&mut rendered, events);
+    rendered
+}
+
+fn is_safe_url(url: &str) -> bool {
+    let url = url.trim();
+    // Relative URLs have no scheme before the first path, query or fragment delimiter
+    match url.find([':', '/', '?', '#']) {
+        Some(index) if url[index..].starts_with(':') => {
+            let scheme = url[..index].to_ascii_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+fn save_mentions(connection: &Connection, comment_id: i64, body: &str) {
+    for username in parse_mentions(body) {
+        let mut statement = connection
+            .prepare("INSERT INTO comment_mentions (comment_id, username) VALUES (?, ?)")
+            .unwrap();
+        statement.bind((1, comment_id)).unwrap();
+        statement.bind((2, username.as_str())).unwrap();
+        statement.next().unwrap();
+    }
+}
+
+fn delete_mentions(connection: &Connection, comment_id: i64) {
+    let mut statement = connection
+        .prepare("DELETE FROM comment_mentions WHERE comment_id = ?")
+        .unwrap();
+    statement.bind((1, comment_id)).unwrap();
+    statement.next().unwrap();
+}
+
+fn find_comment(connection: &Connection, todo_id: i64, id: i64) -> Result<CommentResponse, String> {
+    let query = format!(
+        "SELECT {} FROM comments c WHERE c.todo_id = ? AND c.id = ?",
+        COMMENT_COLUMNS
+    );
+    let mut statement = connection.prepare(query).unwrap();
+    statement.bind((1, todo_id)).unwrap();
+    statement.bind((2, id)).unwrap();
+
+    statement
+        .iter()
+        .map(|row| read_comment(&row.unwrap()))
+        .next()
+        .ok_or_else(|| format!("Comment {} on todo item {} not found", id, todo_id))
+}
+
+fn read_comment(row: &sqlite::Row) -> CommentResponse {
+    let body = row.read::<&str, _>("body").to_string();
+    let mut mentions = row
+        .read::<Option<&str>, _>("mentions")
+        .map(|mentions| mentions.split(' ').map(str::to_string).collect::<Vec<String>>())
+        .unwrap_or_default();
+    mentions.sort();
+
+    CommentResponse {
+        id: row.read::<i64, _>("id"),
+        todo_id: row.read::<i64, _>("todo_id"),
+        author: row.read::<&str, _>("author").to_string(),
+        body_html: render_markdown(&body),
+        body,
+        mentions,
+        created_at: timestamp(row.read::<i64, _>("created_at")),
+        edited_at: row.read::<Option<i64>, _>("edited_at").map(timestamp),
+    }
+}
+
+fn timestamp(secs: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(secs, 0).unwrap_or_default()
+}
@@ -0,0 +1,155 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{types::Db, worker::Shutdown};
+
+/// Response recorded for an idempotency key
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Outcome of claiming an idempotency key before running the request
+#[derive(Debug)]
+pub enum Claim {
+    /// First time the key is seen, the request must run and its response be recorded
+    New,
+    /// Same key and payload as an earlier request that already completed
+    Replay(StoredResponse),
+    /// Same key and payload as a request that is still being processed
+    InProgress,
+    /// The key was already used with a different request
+    Mismatch,
+}
+
+#[async_trait::async_trait]
+pub trait IdempotencyServiceInterface: Send + Sync {
+    async fn claim(&self, db: &Db, key: &str, fingerprint: &str) -> Result<Claim, String>;
+    async fn complete(&self, db: &Db, key: &str, response: StoredResponse) -> Result<(), String>;
+    async fn release(&self, db: &Db, key: &str) -> Result<(), String>;
+    async fn purge_expired(&self, db: &Db) -> Result<usize, String>;
+}
+
+pub struct IdempotencyServiceImpl {
+    ttl: Duration,
+}
+
+impl IdempotencyServiceImpl {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl }
+    }
+
+    fn expired_before(&self) -> i64 {
+        now() - self.ttl.as_secs() as i64
+    }
+}
+
+#[async_trait::async_trait]
+impl IdempotencyServiceInterface for IdempotencyServiceImpl {
+    async fn claim(&self, db: &Db, key: &str, fingerprint: &str) -> Result<Claim, String> {
+        let connection = db.lock().unwrap();
+
+        let mut statement = connection
+            .prepare("DELETE FROM idempotency_keys WHERE key = ? AND created_at < ?")
+            .unwrap();
+        statement.bind((1, key)).unwrap();
+        statement.bind((2, self.expired_before())).unwrap();
+        statement.next().unwrap();
+
+        let query = "SELECT fingerprint, status, content_type, body FROM idempotency_keys WHERE key = ?";
+        let mut statement = connection.prepare(query).unwrap();
+        statement.bind((1, key)).unwrap();
+
+        if let Some(row) = statement.iter().map(|row| row.unwrap()).next() {
+            if row.read::<&str, _>("fingerprint") != fingerprint {
+                return Ok(Claim::Mismatch);
+            }
+            return Ok(match row.read::<Option<i64>, _>("status") {
+                Some(status) => Claim::Replay(StoredResponse {
+                    status: status as u16,
+                    content_type: row.read::<Option<&str>, _>("content_type").map(str::to_string),
+                    body: row.read::<Option<&[u8]>, _>("body").unwrap_or_default().to_vec(),
+                }),
+                None => Claim::InProgress,
+            });
+        }
+
+        let mut statement = connection
+            .prepare("INSERT INTO idempotency_keys (key, fingerprint, created_at) VALUES (?, ?, ?)")
+            .unwrap();
+        statement.bind((1, key)).unwrap();
+        statement.bind((2, fingerprint)).unwrap();
+        statement.bind((3, now())).unwrap();
+        statement.next().unwrap();
+
+        Ok(Claim::New)
+    }
+
+    async fn complete(&self, db: &Db, key: &str, response: StoredResponse) -> Result<(), String> {
+        let connection = db.lock().unwrap();
+        let query = "UPDATE idempotency_keys SET status = ?, content_type = ?, body = ? WHERE key = ?";
+
+        let mut statement = connection.prepare(query).unwrap();
+        statement.bind((1, response.status as i64)).unwrap();
+        statement.bind((2, response.content_type.as_deref())).unwrap();
+        statement.bind((3, response.body.as_slice())).unwrap();
+        statement.bind((4, key)).unwrap();
+        statement.next().unwrap();
+
+        Ok(())
+    }
+
+    async fn release(&self, db: &Db, key: &str) -> Result<(), String> {
+        let connection = db.lock().unwrap();
+        let query = "DELETE FROM idempotency_keys WHERE key = ? AND status IS NULL";
+
+        let mut statement = connection.prepare(query).unwrap();
+        statement.bind((1, key)).unwrap();
+        statement.next().unwrap();
+
+        Ok(())
+    }
+
+    async fn purge_expired(&self, db: &Db) -> Result<usize, String> {
+        let connection = db.lock().unwrap();
+        let query = "DELETE FROM idempotency_keys WHERE created_at < ?";
+
+        let mut statement = connection.prepare(query).unwrap();
+        statement.bind((1, self.expired_before())).unwrap();
+        statement.next().unwrap();
+
+        Ok(connection.change_count())
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// Background worker removing stored responses once their retention period is over
+pub async fn run_purge(
+    service: Arc<dyn IdempotencyServiceInterface>,
+    db: Db,
+    every: Duration,
+    mut shutdown: Shutdown,
+) {
+    let mut ticker = tokio::time::interval(every);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                if let Err(e) = service.purge_expired(&db).await {
+                    eprintln!("⚠️  Failed to purge idempotency keys: {}", e);
+                }
+            }
+            _ = shutdown.requested() => break,
+        }
+    }
+}
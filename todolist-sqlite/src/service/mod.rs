@@ -1 +1,9 @@
+pub mod attachment_service;
+pub mod backup_service;
+pub mod comment_service;
+pub mod idempotency_service;
+pub mod quick_add_service;
+pub mod smart_list_service;
+pub mod tag_service;
+pub mod time_service;
 pub mod todo_service;
\ No newline at end of file
@@ -0,0 +1,63 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::{
+    config::settings::QuickAddConfig,
+    model::quick_add::{QuickAddRequest, QuickAddResponse},
+    quick_add,
+};
+
+pub trait QuickAddServiceInterface: Send + Sync {
+    fn config(&self) -> &QuickAddConfig;
+    /// Reads a todo item from the text without saving it; the title left over must be
+    /// a valid todo title
+    fn parse(
+        &self,
+        request: &QuickAddRequest,
+        now: DateTime<Utc>,
+    ) -> Result<QuickAddResponse, ValidationErrors>;
+}
+
+pub struct QuickAddServiceImpl {
+    config: QuickAddConfig,
+}
+
+impl QuickAddServiceImpl {
+    pub fn new(config: QuickAddConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl QuickAddServiceInterface for QuickAddServiceImpl {
+    fn config(&self) -> &QuickAddConfig {
+        &self.config
+    }
+
+    fn parse(
+        &self,
+        request: &QuickAddRequest,
+        now: DateTime<Utc>,
+    ) -> Result<QuickAddResponse, ValidationErrors> {
+        let options = request.options(&self.config, now)?;
+        let response = QuickAddResponse::new(quick_add::parse(&request.text, &options), options.timezone);
+
+        // Problems with the title are problems with the text it came from
+        let mut errors = ValidationErrors::new();
+        if response.title.is_empty() {
+            errors.add(
+                "text",
+                ValidationError::new("no_title").with_message(Cow::from(
+                    "text must contain a title besides the date, tags, priority and recurrence",
+                )),
+            );
+        } else if let Err(title_errors) = response.todo.item.validate() {
+            for error in title_errors.field_errors().into_values().flatten() {
+                errors.add("text", error.clone());
+            }
+        }
+
+        if errors.is_empty() { Ok(response) } else { Err(errors) }
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::Utc;
+use sqlite::{Connection, Row};
+
+use crate::{
+    model::smart_list::{SmartListRequest, SmartListResponse},
+    types::Db,
+};
+
+#[async_trait::async_trait]
+pub trait SmartListServiceInterface: Send + Sync {
+    /// Every smart list, by name
+    async fn list(&self, db: &Db) -> Result<Vec<SmartListResponse>, String>;
+    async fn get(&self, db: &Db, id: i64) -> Result<SmartListResponse, String>;
+    /// The smart list with this name, ignoring case
+    async fn find_by_name(&self, db: &Db, name: &str) -> Option<SmartListResponse>;
+    async fn create(&self, db: &Db, list: SmartListRequest) -> Result<SmartListResponse, String>;
+    async fn update(&self, db: &Db, id: i64, list: SmartListRequest) -> Result<SmartListResponse, String>;
+    /// Returns false when there was no such smart list
+    async fn delete(&self, db: &Db, id: i64) -> Result<bool, String>;
+}
+
+/// Smart lists only store their query; the todo list runs it when a list is read, so
+/// the items always reflect the current state
+pub struct SmartListServiceImpl;
+
+#[async_trait::async_trait]
+impl SmartListServiceInterface for SmartListServiceImpl {
+    async fn list(&self, db: &Db) -> Result<Vec<SmartListResponse>, String> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare("SELECT id, name, query FROM smart_lists ORDER BY name, id")
+            .unwrap();
+
+        Ok(statement.iter().map(|row| read(&row.unwrap())).collect())
+    }
+
+    async fn get(&self, db: &Db, id: i64) -> Result<SmartListResponse, String> {
+        let connection = db.lock().unwrap();
+        find(&connection, id).ok_or_else(|| format!("Smart list with id {} not found", id))
+    }
+
+    async fn find_by_name(&self, db: &Db, name: &str) -> Option<SmartListResponse> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare("SELECT id, name, query FROM smart_lists WHERE name = ?")
+            .unwrap();
+        statement.bind((1, name)).unwrap();
+        statement.iter().map(|row| read(&row.unwrap())).next()
+    }
+
+    async fn create(&self, db: &Db, list: SmartListRequest) -> Result<SmartListResponse, String> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare(
+                "INSERT INTO smart_lists (name, query, created_at) VALUES (?, ?, ?)
+                RETURNING id, name, query",
+            )
+            .unwrap();
+        statement.bind((1, list.name.as_str())).unwrap();
+        statement.bind((2, list.query.as_str())).unwrap();
+        statement.bind((3, Utc::now().timestamp())).unwrap();
+
+        match statement.iter().next() {
+            Some(Ok(row)) => Ok(read(&row)),
+            Some(Err(e)) => Err(format!("Could not create smart list: {}", e)),
+            None => Err("Could not create smart list".to_string()),
+        }
+    }
+
+    async fn update(&self, db: &Db, id: i64, list: SmartListRequest) -> Result<SmartListResponse, String> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare("UPDATE smart_lists SET name = ?, query = ? WHERE id = ?")
+            .unwrap();
+        statement.bind((1, list.name.as_str())).unwrap();
+        statement.bind((2, list.query.as_str())).unwrap();
+        statement.bind((3, id)).unwrap();
+        statement
+            .next()
+            .map_err(|e| format!("Could not update smart list {}: {}", id, e))?;
+
+        Ok(SmartListResponse {
+            id,
+            name: list.name,
+            query: list.query,
+        })
+    }
+
+    async fn delete(&self, db: &Db, id: i64) -> Result<bool, String> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare("DELETE FROM smart_lists WHERE id = ?")
+            .unwrap();
+        statement.bind((1, id)).unwrap();
+        statement.next().unwrap();
+        Ok(connection.change_count() > 0)
+    }
+}
+
+fn find(connection: &Connection, id: i64) -> Option<SmartListResponse> {
+    let mut statement = connection
+        .prepare("SELECT id, name, query FROM smart_lists WHERE id = ?")
+        .unwrap();
+    statement.bind((1, id)).unwrap();
+    statement.iter().map(|row| read(&row.unwrap())).next()
+}
+
+fn read(row: &Row) -> SmartListResponse {
+    SmartListResponse {
+        id: row.read::<i64, _>("id"),
+        name: row.read::<&str, _>("name").to_string(),
+        query: row.read::<&str, _>("query").to_string(),
+    }
+}
@@ -0,0 +1,158 @@
+use sqlite::Connection;
+
+use crate::{
+    db::tags,
+    model::tag::{DEFAULT_TAG_COLOR, TagError, TagRequest, TagResponse},
+    service::todo_service,
+    types::Db,
+};
+
+#[async_trait::async_trait]
+pub trait TagServiceInterface: Send + Sync {
+    /// Every tag, by name
+    async fn list(&self, db: &Db) -> Result<Vec<TagResponse>, String>;
+    async fn get(&self, db: &Db, id: i64) -> Result<TagResponse, String>;
+    /// Refused when another tag has the name, ignoring case
+    async fn create(&self, db: &Db, tag: TagRequest) -> Result<TagResponse, TagError>;
+    /// Renames or recolours a tag; the todo items carrying it are synced again
+    async fn update(&self, db: &Db, id: i64, tag: TagRequest) -> Result<TagResponse, TagError>;
+    /// Moves the todo items of tag `id` over to tag `into` and deletes it
+    async fn merge(&self, db: &Db, id: i64, into: i64) -> Result<TagResponse, String>;
+    /// Returns false when there was no such tag
+    async fn delete(&self, db: &Db, id: i64) -> Result<bool, String>;
+}
+
+/// Tags are only stored once, so renaming one renames it on every todo item. Each
+/// change that alters the tags of items stamps them with a new change for sync.
+pub struct TagServiceImpl;
+
+#[async_trait::async_trait]
+impl TagServiceInterface for TagServiceImpl {
+    async fn list(&self, db: &Db) -> Result<Vec<TagResponse>, String> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare("SELECT id, name, color FROM tags ORDER BY name, id")
+            .unwrap();
+
+        Ok(statement
+            .iter()
+            .map(|row| tags::read_tag(&row.unwrap()))
+            .collect())
+    }
+
+    async fn get(&self, db: &Db, id: i64) -> Result<TagResponse, String> {
+        let connection = db.lock().unwrap();
+        find(&connection, id).ok_or_else(|| format!("Tag with id {} not found", id))
+    }
+
+    async fn create(&self, db: &Db, tag: TagRequest) -> Result<TagResponse, TagError> {
+        let connection = db.lock().unwrap();
+        if let Some(existing) = tags::find_by_name(&connection, &tag.name) {
+            return Err(TagError::Taken(existing.name));
+        }
+
+        let color = tag.color.as_deref().unwrap_or(DEFAULT_TAG_COLOR);
+        tags::insert(&connection, &tag.name, color)
+            .map_err(TagError::Failed)?
+            .ok_or(TagError::Taken(tag.name))
+    }
+
+    async fn update(&self, db: &Db, id: i64, tag: TagRequest) -> Result<TagResponse, TagError> {
+        let connection = db.lock().unwrap();
+        let current = find(&connection, id)
+            .ok_or_else(|| TagError::NotFound(format!("Tag with id {} not found", id)))?;
+        // Changing only the case of its own name is a rename too
+        if let Some(existing) = tags::find_by_name(&connection, &tag.name)
+            && existing.id != id
+        {
+            return Err(TagError::Taken(existing.name));
+        }
+        let color = tag.color.map_or(current.color, |color| color.to_lowercase());
+
+        let mut statement = connection
+            .prepare("UPDATE tags SET name = ?, folded_name = ?, color = ? WHERE id = ?")
+            .unwrap();
+        statement.bind((1, tag.name.as_str())).unwrap();
+        statement.bind((2, tags::fold(&tag.name).as_str())).unwrap();
+        statement.bind((3, color.as_str())).unwrap();
+        statement.bind((4, id)).unwrap();
+        statement
+            .next()
+            .map_err(|e| TagError::Failed(format!("Could not update tag {}: {}", id, e)))?;
+
+        for todo_id in tags::todos_with(&connection, id) {
+            todo_service::touch(&connection, todo_id);
+        }
+        Ok(TagResponse {
+            id,
+            name: tag.name,
+            color,
+        })
+    }
+
+    async fn merge(&self, db: &Db, id: i64, into: i64) -> Result<TagResponse, String> {
+        let connection = db.lock().unwrap();
+        let target = find(&connection, into).ok_or_else(|| format!("Tag with id {} not found", into))?;
+        let todo_ids = tags::todos_with(&connection, id);
+
+        connection
+            .execute("BEGIN")
+            .map_err(|e| format!("Could not merge tag {}: {}", id, e))?;
+        let mut statement = connection
+            .prepare(
+                "INSERT OR IGNORE INTO todo_tags (todo_id, tag_id)
+                SELECT todo_id, ?2 FROM todo_tags WHERE tag_id = ?1",
+            )
+            .unwrap();
+        statement.bind((1, id)).unwrap();
+        statement.bind((2, into)).unwrap();
+        statement.next().unwrap();
+        remove(&connection, id);
+        for todo_id in todo_ids {
+            todo_service::touch(&connection, todo_id);
+        }
+        connection
+            .execute("COMMIT")
+            .map_err(|e| format!("Could not merge tag {}: {}", id, e))?;
+
+        Ok(target)
+    }
+
+    async fn delete(&self, db: &Db, id: i64) -> Result<bool, String> {
+        let connection = db.lock().unwrap();
+        let todo_ids = tags::todos_with(&connection, id);
+        if !remove(&connection, id) {
+            return Ok(false);
+        }
+
+        for todo_id in todo_ids {
+            todo_service::touch(&connection, todo_id);
+        }
+        Ok(true)
+    }
+}
+
+fn find(connection: &Connection, id: i64) -> Option<TagResponse> {
+    let mut statement = connection
+        .prepare("SELECT id, name, color FROM tags WHERE id = ?")
+        .unwrap();
+    statement.bind((1, id)).unwrap();
+    statement.iter().map(|row| tags::read_tag(&row.unwrap())).next()
+}
+
+/// Deletes the tag and takes it off every todo item. Returns false when it didn't exist.
+fn remove(connection: &Connection, id: i64) -> bool {
+    let mut statement = connection.prepare("DELETE FROM tags WHERE id = ?").unwrap();
+    statement.bind((1, id)).unwrap();
+    statement.next().unwrap();
+    if connection.change_count() == 0 {
+        return false;
+    }
+
+    let mut statement = connection
+        .prepare("DELETE FROM todo_tags WHERE tag_id = ?")
+        .unwrap();
+    statement.bind((1, id)).unwrap();
+    statement.next().unwrap();
+    true
+}
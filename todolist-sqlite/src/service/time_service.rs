@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use sqlite::{Connection, Row};
+
+use crate::{
+    db::{tags, todo_exists},
+    model::time_entry::{
+        TimeEntryRequest, TimeEntryResponse, TimeGroup, TimeReportRange, TimeReportResponse,
+        TimeReportRow,
+    },
+    types::Db,
+};
+
+const COLUMNS: &str = "id, todo_id, user, started_at, ended_at, note";
+
+/// Why a timer could not be started
+#[derive(Debug, PartialEq, Eq)]
+pub enum TimerError {
+    NotFound(String),
+    /// The user's timer already runs, on this todo or the one given
+    AlreadyRunning(TimeEntryResponse),
+    Failed(String),
+}
+
+#[async_trait::async_trait]
+pub trait TimeServiceInterface: Send + Sync {
+    /// The user's running timer, if any
+    async fn running(&self, db: &Db, user: &str) -> Option<TimeEntryResponse>;
+    async fn start(&self, db: &Db, todo_id: i64, user: &str) -> Result<TimeEntryResponse, TimerError>;
+    /// Stops the user's timer on this todo, returning None when none was running or the
+    /// todo doesn't exist
+    async fn stop(&self, db: &Db, todo_id: i64, user: &str) -> Result<Option<TimeEntryResponse>, String>;
+    /// Time entries of a todo, latest first
+    async fn list(&self, db: &Db, todo_id: i64) -> Result<Vec<TimeEntryResponse>, String>;
+    /// Returns `None`, saving nothing, when the todo item doesn't exist
+    async fn create(
+        &self,
+        db: &Db,
+        todo_id: i64,
+        user: &str,
+        entry: TimeEntryRequest,
+    ) -> Result<Option<TimeEntryResponse>, String>;
+    /// The time entry with this id, if it is on the todo
+    async fn get(&self, db: &Db, todo_id: i64, id: i64) -> Option<TimeEntryResponse>;
+    async fn delete(&self, db: &Db, id: i64) -> Result<(), String>;
+    async fn report(&self, db: &Db, range: &TimeReportRange) -> Result<TimeReportResponse, String>;
+}
+
+/// Timers are time entries without an end; a partial unique index keeps one per user
+pub struct TimeServiceImpl;
+
+#[async_trait::async_trait]
+impl TimeServiceInterface for TimeServiceImpl {
+    async fn running(&self, db: &Db, user: &str) -> Option<TimeEntryResponse> {
+        let connection = db.lock().unwrap();
+        running(&connection, user)
+    }
+
+    async fn start(&self, db: &Db, todo_id: i64, user: &str) -> Result<TimeEntryResponse, TimerError> {
+        let connection = db.lock().unwrap();
+        if !todo_exists(&connection, todo_id) {
+            return Err(TimerError::NotFound(format!("Todo item with id {} not found", todo_id)));
+        }
+        // Timers left running on deleted todos, before todos were checked here, would
+        // otherwise block every timer the user starts
+        let mut statement = connection
+            .prepare(
+                "DELETE FROM time_entries WHERE user = ? AND ended_at IS NULL
+                AND NOT EXISTS (SELECT 1 FROM todos t WHERE t.id = time_entries.todo_id)",
+            )
+            .unwrap();
+        statement.bind((1, user)).unwrap();
+        statement.next().map_err(|e| TimerError::Failed(format!("Could not start timer: {}", e)))?;
+        drop(statement);
+
+        if let Some(entry) = running(&connection, user) {
+            return Err(TimerError::AlreadyRunning(entry));
+        }
+
+        let now = Utc::now().timestamp();
+        let mut statement = connection
+            .prepare(format!(
+                "INSERT INTO time_entries (todo_id, user, started_at, created_at) VALUES (?, ?, ?, ?)
+                RETURNING {}",
+                COLUMNS
+            ))
+            .unwrap();
+        statement.bind((1, todo_id)).unwrap();
+        statement.bind((2, user)).unwrap();
+        statement.bind((3, now)).unwrap();
+        statement.bind((4, now)).unwrap();
+
+        match statement.iter().next() {
+            Some(Ok(row)) => Ok(read(&row, now)),
+            Some(Err(e)) => Err(TimerError::Failed(format!("Could not start timer: {}", e))),
+            None => Err(TimerError::Failed("Could not start timer".to_string())),
+        }
+    }
+
+    async fn stop(&self, db: &Db, todo_id: i64, user: &str) -> Result<Option<TimeEntryResponse>, String> {
+        let connection = db.lock().unwrap();
+        if !todo_exists(&connection, todo_id) {
+            return Ok(None);
+        }
+        let now = Utc::now().timestamp();
+        let mut statement = connection
+            .prepare(format!(
+                "UPDATE time_entries SET ended_at = ? WHERE todo_id = ? AND user = ? AND ended_at IS NULL
+                RETURNING {}",
+                COLUMNS
+            ))
+            .unwrap();
+        statement.bind((1, now)).unwrap();
+        statement.bind((2, todo_id)).unwrap();
+        statement.bind((3, user)).unwrap();
+
+        match statement.iter().next() {
+            Some(Ok(row)) => Ok(Some(read(&row, now))),
+            Some(Err(e)) => Err(format!("Could not stop timer: {}", e)),
+            None => Ok(None),
+        }
+    }
+
+    async fn list(&self, db: &Db, todo_id: i64) -> Result<Vec<TimeEntryResponse>, String> {
+        let connection = db.lock().unwrap();
+        let now = Utc::now().timestamp();
+        let mut statement = connection
+            .prepare(format!(
+                "SELECT {} FROM time_entries WHERE todo_id = ? ORDER BY started_at DESC, id DESC",
+                COLUMNS
+            ))
+            .unwrap();
+        statement.bind((1, todo_id)).unwrap();
+
+        Ok(statement.iter().map(|row| read(&row.unwrap(), now)).collect())
+    }
+
+    async fn create(
+        &self,
+        db: &Db,
+        todo_id: i64,
+        user: &str,
+        entry: TimeEntryRequest,
+    ) -> Result<Option<TimeEntryResponse>, String> {
+        let connection = db.lock().unwrap();
+        if !todo_exists(&connection, todo_id) {
+            return Ok(None);
+        }
+        let now = Utc::now().timestamp();
+        let mut statement = connection
+            .prepare(format!(
+                "INSERT INTO time_entries (todo_id, user, started_at, ended_at, note, created_at)
+                VALUES (?, ?, ?, ?, ?, ?) RETURNING {}",
+                COLUMNS
+            ))
+            .unwrap();
+        statement.bind((1, todo_id)).unwrap();
+        statement.bind((2, user)).unwrap();
+        statement.bind((3, entry.started_at.timestamp())).unwrap();
+        statement.bind((4, entry.ended_at.timestamp())).unwrap();
+        statement.bind((5, entry.note.as_deref())).unwrap();
+        statement.bind((6, now)).unwrap();
+
+        match statement.iter().next() {
+            Some(Ok(row)) => Ok(Some(read(&row, now))),
+            Some(Err(e)) => Err(format!("Could not create time entry: {}", e)),
+            None => Err("Could not create time entry".to_string()),
+        }
+    }
+
+    async fn get(&self, db: &Db, todo_id: i64, id: i64) -> Option<TimeEntryResponse> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare(format!(
+                "SELECT {} FROM time_entries WHERE id = ? AND todo_id = ?",
+                COLUMNS
+            ))
+            .unwrap();
+        statement.bind((1, id)).unwrap();
+        statement.bind((2, todo_id)).unwrap();
+        let now = Utc::now().timestamp();
+        statement.iter().map(|row| read(&row.unwrap(), now)).next()
+    }
+
+    async fn delete(&self, db: &Db, id: i64) -> Result<(), String> {
+        let connection = db.lock().unwrap();
+        let mut statement = connection
+            .prepare("DELETE FROM time_entries WHERE id = ?")
+            .unwrap();
+        statement.bind((1, id)).unwrap();
+        statement
+            .next()
+            .map(|_| ())
+            .map_err(|e| format!("Could not delete time entry {}: {}", id, e))
+    }
+
+    async fn report(&self, db: &Db, range: &TimeReportRange) -> Result<TimeReportResponse, String> {
+        let connection = db.lock().unwrap();
+        let now = Utc::now().timestamp();
+        let (start, end) = range.bounds();
+        let mut statement = connection
+            .prepare(format!(
+                "SELECT e.todo_id, e.started_at, e.ended_at, t.title, {}
+                FROM time_entries e JOIN todos t ON t.id = e.todo_id {}
+                WHERE e.started_at < ?1 AND (e.ended_at IS NULL OR e.ended_at > ?2)
+                    AND (?3 IS NULL OR e.user = ?3)
+                GROUP BY e.id",
+                tags::COLUMN,
+                tags::JOIN
+            ))
+            .unwrap();
+        statement.bind((1, end)).unwrap();
+        statement.bind((2, start)).unwrap();
+        statement.bind((3, range.user.as_deref())).unwrap();
+
+        let mut totals: HashMap<String, (String, i64)> = HashMap::new();
+        let mut total_secs = 0;
+        for row in statement.iter() {
+            let row = row.map_err(|e| format!("Could not read time entries: {}", e))?;
+            // Only the part of the entry inside the range counts
+            let from = row.read::<i64, _>("started_at").max(start);
+            let to = row.read::<Option<i64>, _>("ended_at").unwrap_or(now).min(end);
+            if to <= from {
+                continue;
+            }
+            total_secs += to - from;
+
+            let mut add = |key: String, label: String, seconds: i64| {
+                totals.entry(key).or_insert((label, 0)).1 += seconds;
+            };
+            match range.group_by {
+                TimeGroup::Todo => add(
+                    row.read::<i64, _>("todo_id").to_string(),
+                    row.read::<&str, _>("title").to_string(),
+                    to - from,
+                ),
+                TimeGroup::Tag => {
+                    let tags = tags::read(&row);
+                    if tags.is_empty() {
+                        add(String::new(), "No tag".to_string(), to - from);
+                    }
+                    for tag in tags {
+                        add(tag.name.clone(), tag.name, to - from);
+                    }
+                }
+                TimeGroup::Day => {
+                    for (day, seconds) in split_by_day(from, to) {
+                        add(day.to_string(), day.to_string(), seconds);
+                    }
+                }
+            }
+        }
+
+        let mut rows: Vec<TimeReportRow> = totals
+            .into_iter()
+            .map(|(key, (label, seconds))| TimeReportRow { key, label, seconds })
+            .collect();
+        match range.group_by {
+            TimeGroup::Day => rows.sort_by(|a, b| a.key.cmp(&b.key)),
+            _ => rows.sort_by(|a, b| b.seconds.cmp(&a.seconds).then_with(|| a.label.cmp(&b.label))),
+        }
+
+        Ok(TimeReportResponse {
+            from: range.from,
+            to: range.to,
+            group_by: range.group_by,
+            total_secs,
+            rows,
+        })
+    }
+}
+
+/// Deletes the time tracked on a todo, as it is deleted
+pub(crate) fn delete_for_todo(connection: &Connection, todo_id: i64) {
+    let mut statement = connection
+        .prepare("DELETE FROM time_entries WHERE todo_id = ?")
+        .unwrap();
+    statement.bind((1, todo_id)).unwrap();
+    statement.next().unwrap();
+}
+
+fn running(connection: &Connection, user: &str) -> Option<TimeEntryResponse> {
+    let mut statement = connection
+        .prepare(format!(
+            "SELECT {} FROM time_entries WHERE user = ? AND ended_at IS NULL",
+            COLUMNS
+        ))
+        .unwrap();
+    statement.bind((1, user)).unwrap();
+    let now = Utc::now().timestamp();
+    statement.iter().map(|row| read(&row.unwrap(), now)).next()
+}
+
+/// Seconds of `from..to` falling on each UTC day
+fn split_by_day(from: i64, to: i64) -> Vec<(NaiveDate, i64)> {
+    let mut days = Vec::new();
+    let mut start = from;
+    while start < to {
+        let day = timestamp(start).date_naive();
+        let next = (day + Days::new(1)).and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
+        days.push((day, next.min(to) - start));
+        start = next;
+    }
+    days
+}
+
+fn timestamp(secs: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(secs, 0).unwrap_or_default()
+}
+
+/// Reads a time entry, a running one lasting until `now`
+fn read(row: &Row, now: i64) -> TimeEntryResponse {
+    let started_at = row.read::<i64, _>("started_at");
+    let ended_at = row.read::<Option<i64>, _>("ended_at");
+    TimeEntryResponse {
+        id: row.read::<i64, _>("id"),
+        todo_id: row.read::<i64, _>("todo_id"),
+        user: row.read::<&str, _>("user").to_string(),
+        started_at: timestamp(started_at),
+        ended_at: ended_at.map(timestamp),
+        duration_secs: (ended_at.unwrap_or(now) - started_at).max(0),
+        note: row.read::<Option<&str>, _>("note").map(str::to_string),
+    }
+}
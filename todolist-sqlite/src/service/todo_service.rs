@@ -1,19 +1,173 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::Serialize;
+use sqlite::Connection;
+use validator::{Validate, ValidationErrors};
+
 use crate::{
-    model::{todo_item_request::TodoItemRequest, todo_item_response::TodoItemResponse},
+    config::settings::WorkflowConfig,
+    db::{
+        dependencies,
+        ordering::{self, Placement},
+        tags,
+    },
+    events::{EventBus, TodoEvent, TodoEventKind},
+    query,
+    model::{
+        dependency::{DependenciesResponse, DependencyError},
+        tag::{TagMatch, TodoTagsRequest},
+        todo_filter::TodoFilter, todo_item_request::TodoItemRequest,
+        todo_item_response::TodoItemResponse,
+        todo_stats::{StatsPeriod, StatsRange, TodoStatsResponse},
+        todo_sync::{
+            ChangeResult, ClientChange, FieldChange, SyncModifiedAt, SyncResponse, SyncTodo,
+            SyncTombstone, encode_token,
+        },
+        validation_error_response::{FieldError, ValidationErrorResponse},
+        workflow::{TransitionRequest, completion_refused},
+    },
+    service::{
+        attachment_service::AttachmentServiceInterface,
+        comment_service::{self, CommentServiceInterface},
+        time_service,
+    },
     types::Db,
 };
 
+/// Why a todo item could not be updated
+#[derive(Debug, PartialEq)]
+pub enum UpdateError {
+    NotFound(String),
+    /// The workflow doesn't allow the move, or completing or reopening the item
+    Transition(ValidationErrors),
+}
+
+impl std::fmt::Display for UpdateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateError::NotFound(e) => f.write_str(e),
+            UpdateError::Transition(errors) => write!(f, "{}", errors),
+        }
+    }
+}
+
 #[async_trait::async_trait]
 pub trait TodoServiceInterface: Send + Sync {
     async fn create(&self, db: &Db, todo_item: TodoItemRequest)
     -> Result<TodoItemResponse, String>;
-    async fn get_all(&self, db: &Db) -> Result<Vec<TodoItemResponse>, String>;
+    async fn get_all(&self, db: &Db, filter: TodoFilter) -> Result<Vec<TodoItemResponse>, String>;
     async fn get_by_id(&self, db: &Db, id: i64) -> Result<TodoItemResponse, String>;
-    async fn update(&self, db: &Db, id: i64, todo_item: TodoItemRequest) -> Result<TodoItemResponse, String>;
+    /// Completing or reopening the item moves it along its workflow, and is refused when
+    /// its state can't move to a state that agrees
+    async fn update(&self, db: &Db, id: i64, todo_item: TodoItemRequest) -> Result<TodoItemResponse, UpdateError>;
     async fn delete_by_id(&self, db: &Db, id: i64) -> Result<(), String>;
+    async fn toggle(&self, db: &Db, id: i64) -> Result<TodoItemResponse, UpdateError>;
+    async fn move_to(&self, db: &Db, id: i64, placement: Placement) -> Result<TodoItemResponse, String>;
+    async fn stats(&self, db: &Db, range: StatsRange) -> Result<TodoStatsResponse, String>;
+    /// Changes after position `since` of the change sequence, `None` when `since` is
+    /// ahead of it, e.g. after the database was restored from an older copy
+    async fn changes_since(&self, db: &Db, since: i64, limit: usize) -> Result<Option<SyncResponse>, String>;
+    /// Merges one offline change from a client, keeping the newer value of each field
+    async fn apply_change(&self, db: &Db, change: ClientChange) -> Result<ChangeResult, String>;
+    /// Tags an existing item, creating the tags that don't exist yet
+    async fn add_tags(&self, db: &Db, id: i64, names: Vec<String>) -> Result<TodoItemResponse, String>;
+    /// Removes a tag from an existing item; `None` when it didn't have it
+    async fn remove_tag(&self, db: &Db, id: i64, name: &str) -> Result<Option<TodoItemResponse>, String>;
+    fn workflow(&self) -> &WorkflowConfig;
+    /// Moves an existing item to workflow state `to`, completing or reopening it to match,
+    /// and is refused when the workflow doesn't allow the move from its current state
+    async fn transition(&self, db: &Db, id: i64, to: &str) -> Result<TodoItemResponse, UpdateError>;
+    /// Moves items whose state isn't configured or disagrees with `completed` to the
+    /// default state, e.g. after the workflow changed or a backup was restored
+    async fn align_states(&self, db: &Db) -> Result<usize, String>;
+    /// Items an existing item is blocked by, and those it blocks
+    async fn dependencies(&self, db: &Db, id: i64) -> Result<DependenciesResponse, String>;
+    /// Blocks an existing item by another existing one, unless that would close a cycle
+    async fn add_dependency(&self, db: &Db, id: i64, blocked_by: i64) -> Result<DependenciesResponse, DependencyError>;
+    /// Unblocks an item; `None` when it wasn't blocked by `blocked_by`
+    async fn remove_dependency(&self, db: &Db, id: i64, blocked_by: i64) -> Result<Option<DependenciesResponse>, String>;
+    /// Open items, each after the items blocking it and otherwise in manual order
+    async fn plan(&self, db: &Db) -> Result<Vec<TodoItemResponse>, String>;
 }
 
-pub struct TodoServiceImpl;
+pub struct TodoServiceImpl {
+    events: EventBus,
+    attachments: Arc<dyn AttachmentServiceInterface>,
+    comments: Arc<dyn CommentServiceInterface>,
+    workflow: WorkflowConfig,
+}
+
+impl TodoServiceImpl {
+    pub fn new(
+        events: EventBus,
+        attachments: Arc<dyn AttachmentServiceInterface>,
+        comments: Arc<dyn CommentServiceInterface>,
+        workflow: WorkflowConfig,
+    ) -> Self {
+        Self {
+            events,
+            attachments,
+            comments,
+            workflow,
+        }
+    }
+
+    fn publish(&self, kind: TodoEventKind, id: i64, todo: Option<&TodoItemResponse>) {
+        self.events.publish(TodoEvent {
+            kind,
+            id,
+            todo: todo.cloned(),
+        });
+    }
+
+    /// Updates an item with the database already locked, so callers can read it first
+    fn update_locked(
+        &self,
+        connection: &Connection,
+        id: i64,
+        todo_item: TodoItemRequest,
+    ) -> Result<TodoItemResponse, UpdateError> {
+        let mut rs = TodoItemResponse {
+            id: Some(id),
+            title: todo_item.title,
+            completed: todo_item.completed,
+            state: String::new(),
+            due_at: None,
+            tags: Vec::new(),
+            is_blocked: false,
+        };
+        let Some(before) = find_synced(connection, id) else {
+            return Err(UpdateError::NotFound(format!("Todo item with id {} not found", id)));
+        };
+        // An omitted due date keeps the stored one
+        rs.due_at = todo_item.due_at.unwrap_or(before.todo.due_at);
+        rs.tags = before.todo.tags.clone();
+        rs.is_blocked = before.todo.is_blocked;
+
+        // Only fields whose value changes get a new modification time
+        let now = now();
+        let mut after = before.clone();
+        set_field(&mut after.todo.title, &mut after.modified_at.title, rs.title.clone(), now);
+        set_field(&mut after.todo.completed, &mut after.modified_at.completed, rs.completed, now);
+        set_field(&mut after.todo.due_at, &mut after.modified_at.due_at, rs.due_at, now);
+        let Some(state) = self.workflow.follow(&after.todo.state, after.todo.completed) else {
+            let errors = completion_refused(&self.workflow, &after.todo.state, after.todo.completed);
+            return Err(UpdateError::Transition(errors));
+        };
+        after.todo.state = state.to_string();
+        rs.state = after.todo.state.clone();
+
+        // Writing the same values again isn't a change to sync, record or announce
+        if after != before {
+            write(connection, &after);
+            comment_service::record_activity(connection, &before.todo, &rs);
+            self.publish(TodoEventKind::Updated, id, Some(&rs));
+        }
+
+        Ok(rs)
+    }
+}
 
 #[async_trait::async_trait]
 impl TodoServiceInterface for TodoServiceImpl {
@@ -22,97 +176,841 @@ impl TodoServiceInterface for TodoServiceImpl {
         db: &Db,
         todo_item: TodoItemRequest,
     ) -> Result<TodoItemResponse, String> {
+        let now = now();
+        let mut todo = SyncTodo {
+            todo: TodoItemResponse {
+                id: None,
+                title: todo_item.title,
+                completed: todo_item.completed,
+                state: self.workflow.default_state(todo_item.completed).to_string(),
+                due_at: todo_item.due_at.flatten(),
+                tags: Vec::new(),
+                is_blocked: false,
+            },
+            modified_at: SyncModifiedAt {
+                title: now,
+                completed: now,
+                due_at: now,
+            },
+        };
+        let id = {
+            let connection = db.lock().unwrap();
+            insert(&connection, &todo)
+        };
+
+        todo.todo.id = Some(id);
+        self.publish(TodoEventKind::Created, id, Some(&todo.todo));
+
+        Ok(todo.todo)
+    }
+
+    async fn get_all(&self, db: &Db, filter: TodoFilter) -> Result<Vec<TodoItemResponse>, String> {
+        let expr = query::parse(filter.q.as_deref().unwrap_or_default()).map_err(|e| e.to_string())?;
+        let condition = query::compile(&expr, now().date_naive(), 5);
+
         let connection = db.lock().unwrap();
-        let mut statement = connection
-            .prepare("INSERT INTO todos (title, completed) VALUES (?, ?)")
-            .unwrap();
-        statement.bind((1, todo_item.title.as_str())).unwrap();
+        // Items having at least ?4 of the tags named in the JSON array ?3, and matching
+        // the query, whose parameters start at ?5
+        let query = format!(
+            "SELECT t.id, t.title, t.completed, t.state, t.due_at, {}, {}
+            FROM todos t {}
+            WHERE (?1 IS NULL OR t.completed = ?1)
+            AND (?2 IS NULL OR instr(lower(t.title), lower(?2)) > 0)
+            AND (?4 = 0 OR t.id IN (
+                SELECT ft.todo_id FROM todo_tags ft JOIN tags fg ON fg.id = ft.tag_id
+                WHERE fg.folded_name IN (SELECT value FROM json_each(?3))
+                GROUP BY ft.todo_id HAVING COUNT(*) >= ?4))
+            AND ({})
+            GROUP BY t.id
+            ORDER BY t.position, t.id",
+            tags::COLUMN,
+            dependencies::IS_BLOCKED,
+            tags::JOIN,
+            condition.sql
+        );
+        let names: Vec<String> = TodoTagsRequest { tags: filter.tag }
+            .names()
+            .iter()
+            .map(|name| tags::fold(name))
+            .collect();
+        let required = match filter.tag_match.unwrap_or_default() {
+            _ if names.is_empty() => 0,
+            TagMatch::Any => 1,
+            TagMatch::All => names.len() as i64,
+        };
+
+        // Queries are capped in size, but an SQL error must never panic under the lock
+        let failed = |e: sqlite::Error| format!("Could not run the query: {}", e);
+        let mut statement = connection.prepare(query).map_err(failed)?;
         statement
-            .bind((2, if todo_item.completed { 1 } else { 0 }))
-            .unwrap();
-        statement.next().unwrap();
+            .bind((1, filter.completed.map(|completed| completed as i64)))
+            .map_err(failed)?;
+        statement.bind((2, filter.search.as_deref())).map_err(failed)?;
+        statement
+            .bind((3, serde_json::to_string(&names).unwrap().as_str()))
+            .map_err(failed)?;
+        statement.bind((4, required)).map_err(failed)?;
+        for (i, value) in condition.params.iter().enumerate() {
+            statement.bind((5 + i, value)).map_err(failed)?;
+        }
 
-        // obtain the last inserted id
-        let mut statement = connection.prepare("SELECT last_insert_rowid()").unwrap();
-        statement.next().unwrap();
+        statement
+            .iter()
+            .map(|row| row.map(|row| read_todo(&row)).map_err(failed))
+            .collect::<Result<Vec<TodoItemResponse>, String>>()
+    }
 
-        let response_payload = TodoItemResponse {
-            id: Option::Some(statement.read::<i64, _>(0).unwrap()),
-            title: todo_item.title,
-            completed: todo_item.completed,
+    async fn get_by_id(&self, db: &Db, id: i64) -> Result<TodoItemResponse, String> {
+        let connection = db.lock().unwrap();
+        find(&connection, id).ok_or_else(|| format!("Todo item with id {} not found", id))
+    }
+
+    async fn update(&self, db: &Db, id: i64, todo_item: TodoItemRequest) -> Result<TodoItemResponse, UpdateError> {
+        let connection = db.lock().unwrap();
+        self.update_locked(&connection, id, todo_item)
+    }
+
+    async fn delete_by_id(&self, db: &Db, id: i64) -> Result<(), String> {
+        let deleted = {
+            let connection = db.lock().unwrap();
+            delete(&connection, id)
         };
 
-        Ok(response_payload)
+        // Files shared with other todos are kept
+        self.attachments.delete_for_todo(db, id).await?;
+        self.comments.delete_for_todo(db, id).await?;
+        if deleted {
+            self.publish(TodoEventKind::Deleted, id, None);
+        }
+
+        Ok(())
     }
 
-    async fn get_all(&self, db: &Db) -> Result<Vec<TodoItemResponse>, String> {
+    async fn toggle(&self, db: &Db, id: i64) -> Result<TodoItemResponse, UpdateError> {
+        // Read and written under one lock, so a concurrent toggle can't flip it back
         let connection = db.lock().unwrap();
-        let query = "SELECT id, title, completed FROM todos";
+        let current = find(&connection, id)
+            .ok_or_else(|| UpdateError::NotFound(format!("Todo item with id {} not found", id)))?;
+        let toggled = TodoItemRequest {
+            id: Some(id),
+            title: current.title,
+            completed: !current.completed,
+            due_at: None,
+        };
+
+        self.update_locked(&connection, id, toggled)
+    }
 
+    async fn move_to(&self, db: &Db, id: i64, placement: Placement) -> Result<TodoItemResponse, String> {
+        let moved = {
+            let connection = db.lock().unwrap();
+            ordering::move_to(&connection, id, placement)
+        };
+        if !moved {
+            return Err(format!(
+                "Todo item with id {} or {} not found",
+                id,
+                placement.target()
+            ));
+        }
+
+        let todo = self.get_by_id(db, id).await?;
+        self.publish(TodoEventKind::Updated, id, Some(&todo));
+        Ok(todo)
+    }
+
+    async fn stats(&self, db: &Db, range: StatsRange) -> Result<TodoStatsResponse, String> {
+        let connection = db.lock().unwrap();
+        let from = range.from.to_string();
+        let to = range.to.to_string();
+
+        let query = "SELECT COUNT(*) AS total,
+                COALESCE(SUM(completed), 0) AS completed,
+                COALESCE(SUM(NOT completed AND due_at < ?1), 0) AS overdue,
+                (SELECT AVG(completed_at - created_at) FROM todos
+                    WHERE created_at > 0
+                    AND completed_at >= CAST(strftime('%s', ?2) AS INTEGER)
+                    AND completed_at < CAST(strftime('%s', ?3, '+1 day') AS INTEGER))
+                AS average_completion_secs
+            FROM todos";
         let mut statement = connection.prepare(query).unwrap();
+        statement.bind((1, Utc::now().timestamp())).unwrap();
+        statement.bind((2, from.as_str())).unwrap();
+        statement.bind((3, to.as_str())).unwrap();
         statement.next().unwrap();
+        let total = statement.read::<i64, _>("total").unwrap();
+        let completed = statement.read::<i64, _>("completed").unwrap();
+        let overdue = statement.read::<i64, _>("overdue").unwrap();
+        let average_completion_secs = statement
+            .read::<Option<f64>, _>("average_completion_secs")
+            .unwrap();
+        drop(statement);
 
-        let todos = statement
+        // One row per period, generated in SQL so empty periods are reported too; items
+        // created at an unknown time (0) aren't counted as created in any
+        let query = "WITH RECURSIVE periods(start) AS (
+                SELECT ?1
+                UNION ALL
+                SELECT date(start, ?3) FROM periods WHERE date(start, ?3) <= ?2
+            ),
+            bounds AS (
+                SELECT start,
+                    CAST(strftime('%s', start) AS INTEGER) AS period_from,
+                    CAST(strftime('%s', start, ?3) AS INTEGER) AS period_to
+                FROM periods
+            )
+            SELECT start,
+                (SELECT COUNT(*) FROM todos
+                    WHERE created_at > 0 AND created_at >= period_from AND created_at < period_to) AS created,
+                (SELECT COUNT(*) FROM todos
+                    WHERE completed_at >= period_from AND completed_at < period_to) AS completed
+            FROM bounds ORDER BY start";
+        let mut statement = connection.prepare(query).unwrap();
+        statement.bind((1, from.as_str())).unwrap();
+        statement.bind((2, to.as_str())).unwrap();
+        statement
+            .bind((3, format!("+{} days", range.interval.days()).as_str()))
+            .unwrap();
+        let periods = statement
             .iter()
             .map(|row| row.unwrap())
-            .map(|row| TodoItemResponse {
-                id: Option::Some(row.read::<i64, _>("id")),
-                title: row.read::<&str, _>("title").to_string(),
-                completed: row.read::<i64, _>("completed") != 0,
+            .map(|row| StatsPeriod {
+                start: row.read::<&str, _>("start").parse().unwrap_or_default(),
+                created: row.read::<i64, _>("created"),
+                completed: row.read::<i64, _>("completed"),
             })
-            .collect::<Vec<TodoItemResponse>>();
-        Ok(todos)
+            .collect::<Vec<StatsPeriod>>();
+
+        Ok(TodoStatsResponse {
+            total,
+            open: total - completed,
+            completed,
+            overdue,
+            completion_rate: if total > 0 {
+                completed as f64 / total as f64
+            } else {
+                0.0
+            },
+            average_completion_secs,
+            interval: range.interval,
+            from: range.from,
+            to: range.to,
+            periods,
+        })
     }
 
-    async fn get_by_id(&self, db: &Db, id: i64) -> Result<TodoItemResponse, String> {
+    async fn changes_since(&self, db: &Db, since: i64, limit: usize) -> Result<Option<SyncResponse>, String> {
         let connection = db.lock().unwrap();
-        let query = "SELECT id, title, completed FROM todos WHERE id = ?";
+        let current = current_change(&connection);
+        if since > current {
+            return Ok(None);
+        }
 
+        // Both tables in the order their rows changed, plus one row to tell whether more are waiting
+        let query = format!(
+            "SELECT t.id, t.title, t.completed, t.state, t.due_at, t.title_modified_at,
+                t.completed_modified_at, t.due_at_modified_at, {}, {}, NULL AS deleted_at, t.change_seq
+            FROM todos t {} WHERE t.change_seq > ?1 GROUP BY t.id
+            UNION ALL
+            SELECT id, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, deleted_at, change_seq
+            FROM todo_tombstones WHERE change_seq > ?1
+            ORDER BY change_seq
+            LIMIT ?2",
+            tags::COLUMN,
+            dependencies::IS_BLOCKED,
+            tags::JOIN
+        );
         let mut statement = connection.prepare(query).unwrap();
-        statement.bind((1, id)).unwrap();
+        statement.bind((1, since)).unwrap();
+        statement.bind((2, limit as i64 + 1)).unwrap();
 
-        let todo_item = statement
-            .iter()
-            .map(|row| row.unwrap())
-            .map(|row| TodoItemResponse {
-                id: Option::Some(row.read::<i64, _>("id")),
-                title: row.read::<&str, _>("title").to_string(),
-                completed: row.read::<i64, _>("completed") != 0,
-            })
-            .last();
+        let mut response = SyncResponse {
+            token: encode_token(current),
+            has_more: false,
+            changes: Vec::new(),
+            deleted: Vec::new(),
+        };
+        let mut last = since;
+        for (index, row) in statement.iter().enumerate() {
+            let row = row.unwrap();
+            if index == limit {
+                response.has_more = true;
+                response.token = encode_token(last);
+                break;
+            }
+
+            last = row.read::<i64, _>("change_seq");
+            match row.read::<Option<i64>, _>("deleted_at") {
+                Some(deleted_at) => response.deleted.push(SyncTombstone {
+                    id: row.read::<i64, _>("id"),
+                    deleted_at: from_millis(deleted_at),
+                }),
+                None => response.changes.push(read_synced(&row)),
+            }
+        }
+
+        Ok(Some(response))
+    }
+
+    async fn apply_change(&self, db: &Db, change: ClientChange) -> Result<ChangeResult, String> {
+        let (result, event) = {
+            let connection = db.lock().unwrap();
+            merge(&connection, change, now(), &self.workflow)
+        };
+
+        if let (Some(kind), Some(id)) = (event, result.id) {
+            if kind == TodoEventKind::Deleted {
+                self.attachments.delete_for_todo(db, id).await?;
+                self.comments.delete_for_todo(db, id).await?;
+            }
+            self.publish(kind, id, result.todo.as_ref().map(|todo| &todo.todo));
+        }
+
+        Ok(result)
+    }
+
+    async fn add_tags(&self, db: &Db, id: i64, names: Vec<String>) -> Result<TodoItemResponse, String> {
+        let todo = {
+            let connection = db.lock().unwrap();
+            let before = find(&connection, id).ok_or_else(|| format!("Todo item with id {} not found", id))?;
+            if !tags::attach(&connection, id, &names)? {
+                return Ok(before);
+            }
+
+            let after = touch(&connection, id);
+            comment_service::record_activity(&connection, &before, &after);
+            after
+        };
+
+        self.publish(TodoEventKind::Updated, id, Some(&todo));
+        Ok(todo)
+    }
 
-        todo_item.ok_or_else(|| format!("Todo item with id {} not found", id))
+    async fn remove_tag(&self, db: &Db, id: i64, name: &str) -> Result<Option<TodoItemResponse>, String> {
+        let todo = {
+            let connection = db.lock().unwrap();
+            let before = find(&connection, id).ok_or_else(|| format!("Todo item with id {} not found", id))?;
+            let Some(tag) = before.tags.iter().find(|tag| tags::fold(&tag.name) == tags::fold(name)) else {
+                return Ok(None);
+            };
+            tags::detach(&connection, id, tag.id);
+
+            let after = touch(&connection, id);
+            comment_service::record_activity(&connection, &before, &after);
+            after
+        };
+
+        self.publish(TodoEventKind::Updated, id, Some(&todo));
+        Ok(Some(todo))
     }
 
-    async fn update(&self, db: &Db, id: i64, todo_item: TodoItemRequest) -> Result<TodoItemResponse, String> {
+    fn workflow(&self) -> &WorkflowConfig {
+        &self.workflow
+    }
+
+    async fn transition(&self, db: &Db, id: i64, to: &str) -> Result<TodoItemResponse, UpdateError> {
+        let todo = {
+            let connection = db.lock().unwrap();
+            let before = find_synced(&connection, id)
+                .ok_or_else(|| UpdateError::NotFound(format!("Todo item with id {} not found", id)))?;
+            // Checked against the state read under the write lock, so concurrent moves
+            // can't both start from a state that one of them has already left
+            let request = TransitionRequest { to: to.to_string() };
+            request.check(&self.workflow, &before.todo.state).map_err(UpdateError::Transition)?;
+            if before.todo.state == to {
+                return Ok(before.todo);
+            }
+
+            let completed = self.workflow.state(to).is_some_and(|state| state.completed);
+            let mut after = before.clone();
+            set_field(&mut after.todo.completed, &mut after.modified_at.completed, completed, now());
+            after.todo.state = to.to_string();
+            write(&connection, &after);
+            comment_service::record_activity(&connection, &before.todo, &after.todo);
+            after.todo
+        };
+
+        self.publish(TodoEventKind::Updated, id, Some(&todo));
+        Ok(todo)
+    }
+
+    async fn align_states(&self, db: &Db) -> Result<usize, String> {
         let connection = db.lock().unwrap();
-        let query = "UPDATE todos SET title = ?, completed = ? WHERE id = ?";
+        Ok(align_states(&connection, &self.workflow))
+    }
 
-        let mut statement = connection.prepare(query).unwrap();
-        statement.bind((1, todo_item.title.as_str())).unwrap();
-        statement
-            .bind((2, if todo_item.completed { 1 } else { 0 }))
+    async fn dependencies(&self, db: &Db, id: i64) -> Result<DependenciesResponse, String> {
+        let connection = db.lock().unwrap();
+        Ok(dependencies_of(&connection, id))
+    }
+
+    async fn add_dependency(&self, db: &Db, id: i64, blocked_by: i64) -> Result<DependenciesResponse, DependencyError> {
+        let (response, todo) = {
+            let connection = db.lock().unwrap();
+            // Checked under the same lock as the insert, so concurrent requests can't close a cycle
+            if let Some(path) = dependencies::cycle(&connection, id, blocked_by) {
+                return Err(DependencyError::Cycle(path));
+            }
+            if !dependencies::add(&connection, id, blocked_by) {
+                return Ok(dependencies_of(&connection, id));
+            }
+            (dependencies_of(&connection, id), find(&connection, id))
+        };
+
+        self.publish(TodoEventKind::Updated, id, todo.as_ref());
+        Ok(response)
+    }
+
+    async fn remove_dependency(&self, db: &Db, id: i64, blocked_by: i64) -> Result<Option<DependenciesResponse>, String> {
+        let (response, todo) = {
+            let connection = db.lock().unwrap();
+            if !dependencies::remove(&connection, id, blocked_by) {
+                return Ok(None);
+            }
+            (dependencies_of(&connection, id), find(&connection, id))
+        };
+
+        self.publish(TodoEventKind::Updated, id, todo.as_ref());
+        Ok(Some(response))
+    }
+
+    async fn plan(&self, db: &Db) -> Result<Vec<TodoItemResponse>, String> {
+        let open = TodoFilter {
+            completed: Some(false),
+            ..TodoFilter::default()
+        };
+        let todos = self.get_all(db, open).await?;
+        let edges = {
+            let connection = db.lock().unwrap();
+            dependencies::edges(&connection)
+        };
+
+        let ids: Vec<i64> = todos.iter().filter_map(|todo| todo.id).collect();
+        let mut by_id: HashMap<i64, TodoItemResponse> =
+            todos.into_iter().filter_map(|todo| Some((todo.id?, todo))).collect();
+        Ok(dependencies::topological_order(&ids, &edges)
+            .into_iter()
+            .filter_map(|id| by_id.remove(&id))
+            .collect())
+    }
+}
+
+/// Moves every item whose state isn't configured, or is on the other side of `completed`,
+/// to the default state, stamping each with a new change for sync. Returns how many moved.
+pub fn align_states(connection: &Connection, workflow: &WorkflowConfig) -> usize {
+    let mut statement = connection
+        .prepare("SELECT id, completed, state FROM todos")
+        .unwrap();
+    let misaligned: Vec<(i64, String)> = statement
+        .iter()
+        .map(|row| row.unwrap())
+        .filter_map(|row| {
+            let completed = row.read::<i64, _>("completed") != 0;
+            let state = row.read::<Option<&str>, _>("state").unwrap_or_default();
+            let aligned = workflow.align(state, completed);
+            (aligned != state).then(|| (row.read::<i64, _>("id"), aligned.to_string()))
+        })
+        .collect();
+
+    for (id, state) in &misaligned {
+        let change = next_change(connection);
+        let mut statement = connection
+            .prepare("UPDATE todos SET state = ?, change_seq = ? WHERE id = ?")
             .unwrap();
-        statement.bind((3, id)).unwrap();
+        statement.bind((1, state.as_str())).unwrap();
+        statement.bind((2, change)).unwrap();
+        statement.bind((3, *id)).unwrap();
         statement.next().unwrap();
+    }
+    misaligned.len()
+}
 
-        let rs = TodoItemResponse {
-            id: Some(id),
-            title: todo_item.title,
-            completed: todo_item.completed,
-        };
+fn read_todo(row: &sqlite::Row) -> TodoItemResponse {
+    TodoItemResponse {
+        id: Option::Some(row.read::<i64, _>("id")),
+        title: row.read::<&str, _>("title").to_string(),
+        completed: row.read::<i64, _>("completed") != 0,
+        state: row
+            .read::<Option<&str>, _>("state")
+            .unwrap_or_default()
+            .to_string(),
+        due_at: row
+            .read::<Option<i64>, _>("due_at")
+            .and_then(|due_at| DateTime::from_timestamp(due_at, 0)),
+        tags: tags::read(row),
+        is_blocked: dependencies::read_is_blocked(row),
+    }
+}
 
-        Ok(rs)
+fn read_synced(row: &sqlite::Row) -> SyncTodo {
+    SyncTodo {
+        todo: read_todo(row),
+        modified_at: SyncModifiedAt {
+            title: from_millis(row.read::<i64, _>("title_modified_at")),
+            completed: from_millis(row.read::<i64, _>("completed_modified_at")),
+            due_at: from_millis(row.read::<i64, _>("due_at_modified_at")),
+        },
     }
+}
 
-    async fn delete_by_id(&self, db: &Db, id: i64) -> Result<(), String> {
-        let connection = db.lock().unwrap();
-        let query = "DELETE FROM todos WHERE id = ?";
+fn from_millis(millis: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(millis).unwrap_or_default()
+}
+
+/// Current time at the millisecond precision modification times are stored with
+fn now() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(3)
+}
+
+/// Time a client change is merged at; clocks running ahead of the server's can't make a
+/// change win against every later one
+fn received_at(modified_at: DateTime<Utc>, now: DateTime<Utc>) -> DateTime<Utc> {
+    modified_at.trunc_subsecs(3).min(now)
+}
+
+/// Advances the change sequence and returns the new position, stamped on the changed row
+fn next_change(connection: &Connection) -> i64 {
+    let mut statement = connection
+        .prepare("UPDATE change_sequence SET value = value + 1 RETURNING value")
+        .unwrap();
+    statement.next().unwrap();
+    statement.read::<i64, _>(0).unwrap()
+}
+
+fn current_change(connection: &Connection) -> i64 {
+    let mut statement = connection.prepare("SELECT value FROM change_sequence").unwrap();
+    statement.next().unwrap();
+    statement.read::<i64, _>(0).unwrap()
+}
+
+fn find(connection: &Connection, id: i64) -> Option<TodoItemResponse> {
+    find_synced(connection, id).map(|todo| todo.todo)
+}
 
+/// Items on either end of an item's dependencies, in manual order
+fn dependencies_of(connection: &Connection, id: i64) -> DependenciesResponse {
+    let related = |column: &str, key: &str| {
+        let query = format!(
+            "SELECT t.id, t.title, t.completed, t.state, t.due_at, {}, {}
+            FROM todos t {}
+            WHERE t.id IN (SELECT {} FROM todo_dependencies WHERE {} = ?)
+            GROUP BY t.id
+            ORDER BY t.position, t.id",
+            tags::COLUMN,
+            dependencies::IS_BLOCKED,
+            tags::JOIN,
+            column,
+            key
+        );
         let mut statement = connection.prepare(query).unwrap();
         statement.bind((1, id)).unwrap();
-        statement.next().unwrap();
+        statement
+            .iter()
+            .map(|row| read_todo(&row.unwrap()))
+            .collect::<Vec<TodoItemResponse>>()
+    };
 
-        Ok(())
+    DependenciesResponse::new(related("blocked_by", "todo_id"), related("todo_id", "blocked_by"))
+}
+
+fn find_synced(connection: &Connection, id: i64) -> Option<SyncTodo> {
+    let query = format!(
+        "SELECT t.id, t.title, t.completed, t.state, t.due_at, t.title_modified_at,
+            t.completed_modified_at, t.due_at_modified_at, {}, {}
+        FROM todos t {} WHERE t.id = ? GROUP BY t.id",
+        tags::COLUMN,
+        dependencies::IS_BLOCKED,
+        tags::JOIN
+    );
+    let mut statement = connection.prepare(query).unwrap();
+    statement.bind((1, id)).unwrap();
+    statement.iter().map(|row| read_synced(&row.unwrap())).next()
+}
+
+/// Stamps an item whose tags changed with a new change, so the next sync pull returns it,
+/// and reads it back
+pub(crate) fn touch(connection: &Connection, id: i64) -> TodoItemResponse {
+    let change = next_change(connection);
+    let mut statement = connection
+        .prepare("UPDATE todos SET change_seq = ? WHERE id = ?")
+        .unwrap();
+    statement.bind((1, change)).unwrap();
+    statement.bind((2, id)).unwrap();
+    statement.next().unwrap();
+    find(connection, id).unwrap_or_default()
+}
+
+fn insert(connection: &Connection, todo: &SyncTodo) -> i64 {
+    let change = next_change(connection);
+    let position = ordering::next_position(connection);
+    let created_at = Utc::now().timestamp();
+
+    let mut statement = connection
+        .prepare(
+            "INSERT INTO todos (title, completed, position, due_at, created_at, completed_at,
+                title_modified_at, completed_modified_at, due_at_modified_at, change_seq, state)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .unwrap();
+    statement.bind((1, todo.todo.title.as_str())).unwrap();
+    statement.bind((2, todo.todo.completed as i64)).unwrap();
+    statement.bind((3, position)).unwrap();
+    statement
+        .bind((4, todo.todo.due_at.map(|due_at| due_at.timestamp())))
+        .unwrap();
+    statement.bind((5, created_at)).unwrap();
+    statement
+        .bind((6, todo.todo.completed.then_some(created_at)))
+        .unwrap();
+    statement.bind((7, todo.modified_at.title.timestamp_millis())).unwrap();
+    statement.bind((8, todo.modified_at.completed.timestamp_millis())).unwrap();
+    statement.bind((9, todo.modified_at.due_at.timestamp_millis())).unwrap();
+    statement.bind((10, change)).unwrap();
+    statement.bind((11, todo.todo.state.as_str())).unwrap();
+    statement.next().unwrap();
+
+    let mut statement = connection.prepare("SELECT last_insert_rowid()").unwrap();
+    statement.next().unwrap();
+    statement.read::<i64, _>(0).unwrap()
+}
+
+fn write(connection: &Connection, todo: &SyncTodo) {
+    let change = next_change(connection);
+
+    // completed_at keeps the time an item was first completed until it is reopened
+    let query = "UPDATE todos SET title = ?1, completed = ?2, due_at = ?3,
+        completed_at = CASE WHEN ?2 = 0 THEN NULL WHEN completed = 0 THEN ?4 ELSE completed_at END,
+        title_modified_at = ?5, completed_modified_at = ?6, due_at_modified_at = ?7,
+        change_seq = ?8, state = ?10
+        WHERE id = ?9";
+
+    let mut statement = connection.prepare(query).unwrap();
+    statement.bind((1, todo.todo.title.as_str())).unwrap();
+    statement.bind((2, todo.todo.completed as i64)).unwrap();
+    statement
+        .bind((3, todo.todo.due_at.map(|due_at| due_at.timestamp())))
+        .unwrap();
+    statement.bind((4, Utc::now().timestamp())).unwrap();
+    statement.bind((5, todo.modified_at.title.timestamp_millis())).unwrap();
+    statement.bind((6, todo.modified_at.completed.timestamp_millis())).unwrap();
+    statement.bind((7, todo.modified_at.due_at.timestamp_millis())).unwrap();
+    statement.bind((8, change)).unwrap();
+    statement.bind((9, todo.todo.id)).unwrap();
+    statement.bind((10, todo.todo.state.as_str())).unwrap();
+    statement.next().unwrap();
+}
+
+/// Deletes the item and leaves a tombstone for clients to sync. Returns false when it didn't exist.
+fn delete(connection: &Connection, id: i64) -> bool {
+    let mut statement = connection.prepare("DELETE FROM todos WHERE id = ?").unwrap();
+    statement.bind((1, id)).unwrap();
+    statement.next().unwrap();
+    if connection.change_count() == 0 {
+        return false;
+    }
+    tags::detach_all(connection, id);
+    dependencies::remove_all(connection, id);
+    time_service::delete_for_todo(connection, id);
+
+    let change = next_change(connection);
+    let mut statement = connection
+        .prepare("INSERT OR REPLACE INTO todo_tombstones (id, change_seq, deleted_at) VALUES (?, ?, ?)")
+        .unwrap();
+    statement.bind((1, id)).unwrap();
+    statement.bind((2, change)).unwrap();
+    statement.bind((3, now().timestamp_millis())).unwrap();
+    statement.next().unwrap();
+    true
+}
+
+fn tombstone_of(connection: &Connection, id: i64) -> Option<DateTime<Utc>> {
+    let mut statement = connection
+        .prepare("SELECT deleted_at FROM todo_tombstones WHERE id = ?")
+        .unwrap();
+    statement.bind((1, id)).unwrap();
+    statement
+        .iter()
+        .map(|row| from_millis(row.unwrap().read::<i64, _>("deleted_at")))
+        .next()
+}
+
+fn set_field<T: PartialEq>(value: &mut T, modified_at: &mut DateTime<Utc>, new: T, at: DateTime<Utc>) {
+    if *value != new {
+        *value = new;
+        *modified_at = at;
     }
 }
+
+/// Last writer wins: the client's value replaces the server's only when it was changed later,
+/// otherwise the server's value is reported back as a conflict
+fn merge_field<T: PartialEq + Serialize>(
+    result: &mut ChangeResult,
+    field: &str,
+    value: &mut T,
+    modified_at: &mut DateTime<Utc>,
+    change: Option<FieldChange<T>>,
+    now: DateTime<Utc>,
+) {
+    let Some(change) = change else {
+        return;
+    };
+    if change.value == *value {
+        return;
+    }
+
+    let at = received_at(change.modified_at, now);
+    if at > *modified_at {
+        *value = change.value;
+        *modified_at = at;
+    } else {
+        result.add_conflict(field, value, *modified_at);
+    }
+}
+
+/// Same rules as the REST API, by validating the merged item as a request
+fn validate(todo: &TodoItemResponse) -> Result<(), ValidationErrorResponse> {
+    let request = TodoItemRequest {
+        id: None,
+        title: todo.title.clone(),
+        completed: todo.completed,
+        due_at: Some(todo.due_at),
+    };
+    request.validate().map_err(ValidationErrorResponse::from)
+}
+
+/// Applies one client change under the database lock and returns its result,
+/// with the event to publish when something was written
+fn merge(
+    connection: &Connection,
+    change: ClientChange,
+    now: DateTime<Utc>,
+    workflow: &WorkflowConfig,
+) -> (ChangeResult, Option<TodoEventKind>) {
+    let mut result = ChangeResult::new(&change);
+    let Some(id) = change.id else {
+        return merge_created(connection, change, now, workflow, result);
+    };
+
+    let Some(before) = find_synced(connection, id) else {
+        match tombstone_of(connection, id) {
+            // Deleting it again changes nothing
+            Some(_) if change.deleted_at.is_some() => {}
+            Some(deleted_at) => result.add_conflict("deleted", &true, deleted_at),
+            None => result.reject(FieldError {
+                field: "id".to_string(),
+                code: "not_found".to_string(),
+                message: format!("Todo item with id {} not found", id),
+                position: None,
+                path: None,
+            }),
+        }
+        return (result, None);
+    };
+
+    if let Some(deleted_at) = change.deleted_at {
+        // A delete loses against any field changed after it
+        let deleted_at = received_at(deleted_at, now);
+        let modified_at = before.modified_at;
+        if modified_at.title > deleted_at {
+            result.add_conflict("title", &before.todo.title, modified_at.title);
+        }
+        if modified_at.completed > deleted_at {
+            result.add_conflict("completed", &before.todo.completed, modified_at.completed);
+        }
+        if modified_at.due_at > deleted_at {
+            result.add_conflict("due_at", &before.todo.due_at, modified_at.due_at);
+        }
+
+        if result.conflicts.is_empty() {
+            delete(connection, id);
+            return (result, Some(TodoEventKind::Deleted));
+        }
+        result.todo = Some(before);
+        return (result, None);
+    }
+
+    let mut after = before.clone();
+    let modified_at = &mut after.modified_at;
+    merge_field(&mut result, "title", &mut after.todo.title, &mut modified_at.title, change.title, now);
+    merge_field(&mut result, "completed", &mut after.todo.completed, &mut modified_at.completed, change.completed, now);
+    merge_field(&mut result, "due_at", &mut after.todo.due_at, &mut modified_at.due_at, change.due_at, now);
+    let Some(state) = workflow.follow(&after.todo.state, after.todo.completed) else {
+        let errors = completion_refused(workflow, &after.todo.state, after.todo.completed);
+        result.reject_all(ValidationErrorResponse::from(errors).errors);
+        result.todo = Some(before);
+        return (result, None);
+    };
+    after.todo.state = state.to_string();
+
+    if let Err(errors) = validate(&after.todo) {
+        result.reject_all(errors.errors);
+        result.todo = Some(before);
+        return (result, None);
+    }
+
+    let mut event = None;
+    if after != before {
+        write(connection, &after);
+        comment_service::record_activity(connection, &before.todo, &after.todo);
+        event = Some(TodoEventKind::Updated);
+    }
+    result.todo = Some(after);
+    (result, event)
+}
+
+fn merge_created(
+    connection: &Connection,
+    change: ClientChange,
+    now: DateTime<Utc>,
+    workflow: &WorkflowConfig,
+    mut result: ChangeResult,
+) -> (ChangeResult, Option<TodoEventKind>) {
+    let required = |field: &str, message: &str| FieldError {
+        field: field.to_string(),
+        code: "required".to_string(),
+        message: message.to_string(),
+        position: None,
+        path: None,
+    };
+    if change.deleted_at.is_some() {
+        result.reject(required("id", "id is required to delete an item"));
+        return (result, None);
+    }
+    let Some(title) = change.title else {
+        result.reject(required("title", "title is required to create an item"));
+        return (result, None);
+    };
+
+    let (completed, completed_at) = change
+        .completed
+        .map_or((false, now), |completed| (completed.value, received_at(completed.modified_at, now)));
+    let (due_at, due_at_modified_at) = change
+        .due_at
+        .map_or((None, now), |due_at| (due_at.value, received_at(due_at.modified_at, now)));
+    let mut todo = SyncTodo {
+        todo: TodoItemResponse {
+            id: None,
+            title: title.value,
+            completed,
+            state: workflow.default_state(completed).to_string(),
+            due_at,
+            tags: Vec::new(),
+            is_blocked: false,
+        },
+        modified_at: SyncModifiedAt {
+            title: received_at(title.modified_at, now),
+            completed: completed_at,
+            due_at: due_at_modified_at,
+        },
+    };
+    if let Err(errors) = validate(&todo.todo) {
+        result.reject_all(errors.errors);
+        return (result, None);
+    }
+
+    let id = insert(connection, &todo);
+    todo.todo.id = Some(id);
+    result.id = Some(id);
+    result.todo = Some(todo);
+    (result, Some(TodoEventKind::Created))
+}
@@ -2,7 +2,17 @@ use std::sync::{Arc, Mutex};
 
 use sqlite::Connection;
 
-use crate::service::todo_service::TodoServiceInterface;
+use crate::{
+    events::EventBus,
+    service::{
+        attachment_service::AttachmentServiceInterface, backup_service::BackupServiceInterface,
+        comment_service::CommentServiceInterface,
+        idempotency_service::IdempotencyServiceInterface,
+        quick_add_service::QuickAddServiceInterface, smart_list_service::SmartListServiceInterface,
+        tag_service::TagServiceInterface, time_service::TimeServiceInterface,
+        todo_service::TodoServiceInterface,
+    },
+};
 
 
 pub type Db = Arc<Mutex<Connection>>;
@@ -11,10 +21,13 @@ pub type Db = Arc<Mutex<Connection>>;
 pub struct AppState {
     pub db: Db,
     pub todo_service: Arc<dyn TodoServiceInterface>,
+    pub idempotency_service: Arc<dyn IdempotencyServiceInterface>,
+    pub attachment_service: Arc<dyn AttachmentServiceInterface>,
+    pub comment_service: Arc<dyn CommentServiceInterface>,
+    pub backup_service: Arc<dyn BackupServiceInterface>,
+    pub quick_add_service: Arc<dyn QuickAddServiceInterface>,
+    pub tag_service: Arc<dyn TagServiceInterface>,
+    pub smart_list_service: Arc<dyn SmartListServiceInterface>,
+    pub time_service: Arc<dyn TimeServiceInterface>,
+    pub events: EventBus,
 }
-
-impl AppState {
-    pub fn new(db: Db, todo_service: Arc<dyn TodoServiceInterface>) -> Self {
-        Self { db, todo_service }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,263 @@
+//! Server-rendered HTML interface for people who don't use the API directly.
+//!
+//! Every page works as plain links and form posts. `ui.js` enhances elements carrying
+//! `data-target` so they fetch just the changed fragment and swap it into the page, in
+//! the style of htmx; such requests are sent with an `HX-Request` header and get a
+//! partial instead of a redirect or a full page.
+
+pub mod views;
+
+use askama::Template;
+use axum::{
+    Form, Router,
+    extract::{FromRequestParts, Path, Query, State},
+    http::{HeaderValue, StatusCode, header, request::Parts},
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::{get, post},
+};
+
+use crate::{
+    model::{todo_filter::TodoFilter, todo_item_response::TodoItemResponse},
+    service::todo_service::UpdateError,
+    types::AppState,
+    web::views::{
+        EditView, ErrorPage, IndexPage, ListQuery, TodoApp, TodoAppPartial, TodoEditPartial,
+        TodoForm, TodoRowPartial,
+    },
+};
+
+const SCRIPT: &str = include_str!("ui.js");
+const STYLESHEET: &str = include_str!("ui.css");
+
+/// Routes of the HTML interface, mounted at `/` outside of `app.prefix`
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(index))
+        .route("/todos", post(create))
+        .route("/todos/{id}", get(row).post(update))
+        .route("/todos/{id}/edit", get(edit))
+        .route("/todos/{id}/toggle", post(toggle))
+        .route("/todos/{id}/delete", post(delete))
+        .route("/assets/ui.js", get(|| asset("text/javascript; charset=utf-8", SCRIPT)))
+        .route("/assets/ui.css", get(|| asset("text/css; charset=utf-8", STYLESHEET)))
+}
+
+/// Whether the request was sent by `ui.js` and only wants the swapped fragment
+pub struct Partial(pub bool);
+
+impl<S: Send + Sync> FromRequestParts<S> for Partial {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Partial(parts.headers.get("hx-request").is_some_and(|value| value == "true")))
+    }
+}
+
+async fn asset(content_type: &'static str, body: &'static str) -> Response {
+    ([(header::CONTENT_TYPE, content_type)], body).into_response()
+}
+
+fn render(status: StatusCode, template: impl Template) -> Response {
+    match template.render() {
+        Ok(html) => (status, Html(html)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+fn failed(status: StatusCode, message: String) -> Response {
+    render(status, ErrorPage { message })
+}
+
+async fn load(app_state: &AppState, query: ListQuery) -> Result<TodoApp, Response> {
+    let filter = TodoFilter {
+        completed: query.show.completed(),
+        search: Some(query.search.clone()).filter(|search| !search.is_empty()),
+        ..Default::default()
+    };
+    app_state
+        .todo_service
+        .get_all(&app_state.db, filter)
+        .await
+        .map(|todos| TodoApp::new(todos, query))
+        .map_err(|e| failed(StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+/// The whole page, or only the list when switching tabs or searching
+fn page(partial: bool, status: StatusCode, app: TodoApp) -> Response {
+    if partial {
+        let mut response = render(status, TodoAppPartial { app: app.clone() });
+        // Lets the address bar follow the list shown, as a full page load would
+        if let Ok(url) = HeaderValue::from_str(&app.query.href()) {
+            response.headers_mut().insert("hx-push-url", url);
+        }
+        response
+    } else {
+        render(status, IndexPage { app })
+    }
+}
+
+/// The changed row, or nothing once it no longer belongs to the list shown
+fn shown_row(query: &ListQuery, todo: TodoItemResponse) -> Response {
+    match query.show.completed() {
+        Some(completed) if completed != todo.completed => Html("").into_response(),
+        _ => render(StatusCode::OK, TodoRowPartial { todo: todo.into(), query: query.clone() }),
+    }
+}
+
+async fn index(
+    State(app_state): State<AppState>,
+    Partial(partial): Partial,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    match load(&app_state, query).await {
+        Ok(app) => page(partial, StatusCode::OK, app),
+        Err(response) => response,
+    }
+}
+
+async fn create(
+    State(app_state): State<AppState>,
+    Partial(partial): Partial,
+    Form(form): Form<TodoForm>,
+) -> Response {
+    let query = form.query();
+    let request = match form.to_request() {
+        Ok(request) => request,
+        Err(errors) => {
+            return match load(&app_state, query).await {
+                Ok(mut app) => {
+                    app.form = form;
+                    app.errors = errors;
+                    page(partial, StatusCode::UNPROCESSABLE_ENTITY, app)
+                }
+                Err(response) => response,
+            };
+        }
+    };
+
+    if let Err(e) = app_state.todo_service.create(&app_state.db, request).await {
+        return failed(StatusCode::INTERNAL_SERVER_ERROR, e);
+    }
+    if !partial {
+        return Redirect::to(&query.href()).into_response();
+    }
+    match load(&app_state, query).await {
+        Ok(app) => page(partial, StatusCode::OK, app),
+        Err(response) => response,
+    }
+}
+
+/// A row as shown in the list, used to cancel editing it
+async fn row(
+    State(app_state): State<AppState>,
+    Partial(partial): Partial,
+    Path(id): Path<i64>,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    if !partial {
+        return Redirect::to(&query.href()).into_response();
+    }
+    match app_state.todo_service.get_by_id(&app_state.db, id).await {
+        Ok(todo) => render(StatusCode::OK, TodoRowPartial { todo: todo.into(), query }),
+        Err(e) => failed(StatusCode::NOT_FOUND, e),
+    }
+}
+
+async fn edit(
+    State(app_state): State<AppState>,
+    Partial(partial): Partial,
+    Path(id): Path<i64>,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    let todo = match app_state.todo_service.get_by_id(&app_state.db, id).await {
+        Ok(todo) => todo,
+        Err(e) => return failed(StatusCode::NOT_FOUND, e),
+    };
+    let edit = EditView {
+        id,
+        form: TodoForm { show: query.show, search: query.search.clone(), ..TodoForm::edit(&todo) },
+        errors: Default::default(),
+    };
+
+    if partial {
+        return render(StatusCode::OK, TodoEditPartial { edit });
+    }
+    match load(&app_state, query).await {
+        Ok(mut app) => {
+            app.edit = Some(edit);
+            render(StatusCode::OK, IndexPage { app })
+        }
+        Err(response) => response,
+    }
+}
+
+async fn update(
+    State(app_state): State<AppState>,
+    Partial(partial): Partial,
+    Path(id): Path<i64>,
+    Form(form): Form<TodoForm>,
+) -> Response {
+    let service = &app_state.todo_service;
+    if let Err(e) = service.get_by_id(&app_state.db, id).await {
+        return failed(StatusCode::NOT_FOUND, e);
+    }
+
+    let query = form.query();
+    let request = match form.to_request() {
+        Ok(request) => request,
+        Err(errors) => {
+            let edit = EditView { id, form, errors };
+            if partial {
+                return render(StatusCode::UNPROCESSABLE_ENTITY, TodoEditPartial { edit });
+            }
+            return match load(&app_state, query).await {
+                Ok(mut app) => {
+                    app.edit = Some(edit);
+                    render(StatusCode::UNPROCESSABLE_ENTITY, IndexPage { app })
+                }
+                Err(response) => response,
+            };
+        }
+    };
+
+    match service.update(&app_state.db, id, request).await {
+        Ok(todo) if partial => shown_row(&query, todo),
+        Ok(_) => Redirect::to(&query.href()).into_response(),
+        Err(e) => update_failed(e),
+    }
+}
+
+async fn toggle(
+    State(app_state): State<AppState>,
+    Partial(partial): Partial,
+    Path(id): Path<i64>,
+    Form(query): Form<ListQuery>,
+) -> Response {
+    match app_state.todo_service.toggle(&app_state.db, id).await {
+        Ok(todo) if partial => shown_row(&query, todo),
+        Ok(_) => Redirect::to(&query.href()).into_response(),
+        Err(e) => update_failed(e),
+    }
+}
+
+fn update_failed(error: UpdateError) -> Response {
+    let status = match error {
+        UpdateError::NotFound(_) => StatusCode::NOT_FOUND,
+        UpdateError::Transition(_) => StatusCode::UNPROCESSABLE_ENTITY,
+    };
+    failed(status, error.to_string())
+}
+
+async fn delete(
+    State(app_state): State<AppState>,
+    Partial(partial): Partial,
+    Path(id): Path<i64>,
+    Form(query): Form<ListQuery>,
+) -> Response {
+    match app_state.todo_service.delete_by_id(&app_state.db, id).await {
+        // Swapping in nothing removes the row
+        Ok(()) if partial => Html("").into_response(),
+        Ok(()) => Redirect::to(&query.href()).into_response(),
+        Err(e) => failed(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
@@ -0,0 +1,84 @@
+// Progressive enhancement for the todo pages, in the style of htmx.
+//
+// Forms and links with `data-target="<selector>"` are sent with fetch() and an
+// `HX-Request: true` header. The server answers with an HTML fragment that replaces the
+// closest element matching the selector (or the first one in the page); an empty answer
+// removes it. `data-confirm` asks before sending. Without JavaScript the same forms and
+// links load whole pages.
+(function () {
+    function targetOf(element) {
+        var selector = element.getAttribute('data-target');
+        return element.closest(selector) || document.querySelector(selector);
+    }
+
+    function swap(element, url, options) {
+        var target = targetOf(element);
+        if (!target) {
+            return;
+        }
+        options.headers = { 'HX-Request': 'true' };
+        target.setAttribute('aria-busy', 'true');
+
+        fetch(url, options)
+            .then(function (response) {
+                var pushUrl = response.headers.get('HX-Push-Url');
+                return response.text().then(function (html) {
+                    // Anything but a fragment or a rejected form is a whole error page
+                    if (!response.ok && response.status !== 422) {
+                        document.open();
+                        document.write(html);
+                        document.close();
+                        return;
+                    }
+                    var template = document.createElement('template');
+                    template.innerHTML = html;
+                    var focus = template.content.querySelector('[autofocus]');
+                    target.replaceWith(template.content);
+                    if (focus) {
+                        focus.focus();
+                    }
+                    if (pushUrl && pushUrl !== location.pathname + location.search) {
+                        history.pushState(null, '', pushUrl);
+                    }
+                });
+            })
+            .catch(function () {
+                // Fall back to a full page load, which shows the error
+                target.removeAttribute('aria-busy');
+                location.href = url;
+            });
+    }
+
+    document.addEventListener('submit', function (event) {
+        var form = event.target;
+        if (!form.hasAttribute('data-target')) {
+            return;
+        }
+        event.preventDefault();
+        var confirmation = form.getAttribute('data-confirm');
+        if (confirmation && !window.confirm(confirmation)) {
+            return;
+        }
+
+        var data = new URLSearchParams(new FormData(form));
+        if (form.method.toLowerCase() === 'get') {
+            swap(form, form.action.split('?')[0] + '?' + data, { method: 'GET' });
+        } else {
+            swap(form, form.action, { method: 'POST', body: data });
+        }
+    });
+
+    document.addEventListener('click', function (event) {
+        var link = event.target.closest('a[data-target]');
+        if (!link || event.ctrlKey || event.metaKey || event.shiftKey) {
+            return;
+        }
+        event.preventDefault();
+        swap(link, link.href, { method: 'GET' });
+    });
+
+    // Lists pushed onto the history are reloaded in full when going back
+    window.addEventListener('popstate', function () {
+        location.reload();
+    });
+})();
@@ -0,0 +1,288 @@
+use askama::Template;
+use chrono::{NaiveDateTime, Utc};
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::model::{
+    todo_item_request::TodoItemRequest, todo_item_response::TodoItemResponse,
+    validation_error_response::{FieldError, ValidationErrorResponse},
+};
+
+/// Format of `<input type="datetime-local">` values, read as UTC
+const INPUT_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// Which part of the list is shown
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Show {
+    #[default]
+    All,
+    Open,
+    Done,
+}
+
+impl Show {
+    pub fn completed(self) -> Option<bool> {
+        match self {
+            Show::All => None,
+            Show::Open => Some(false),
+            Show::Done => Some(true),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Show::All => "all",
+            Show::Open => "open",
+            Show::Done => "done",
+        }
+    }
+}
+
+/// Query string of the list page
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ListQuery {
+    #[serde(default)]
+    pub show: Show,
+    #[serde(default)]
+    pub search: String,
+}
+
+impl ListQuery {
+    pub fn show_name(&self) -> &'static str {
+        self.show.name()
+    }
+
+    /// `?show=..&search=..` for links that keep this list, leaving out defaults
+    pub fn query_string(&self) -> String {
+        let mut params = Vec::new();
+        if self.show != Show::All {
+            params.push(format!("show={}", self.show.name()));
+        }
+        if !self.search.is_empty() {
+            params.push(format!("search={}", urlencode(&self.search)));
+        }
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", params.join("&"))
+        }
+    }
+
+    /// Link to the list page showing this list
+    pub fn href(&self) -> String {
+        format!("/{}", self.query_string())
+    }
+}
+
+fn urlencode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Fields of the add and edit forms, as typed
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct TodoForm {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub due_at: String,
+    /// Checkbox, only sent when ticked
+    pub completed: Option<String>,
+    /// List shown when the form was sent, kept when the page is rendered again
+    #[serde(default)]
+    pub show: Show,
+    #[serde(default)]
+    pub search: String,
+}
+
+impl TodoForm {
+    pub fn edit(todo: &TodoItemResponse) -> Self {
+        Self {
+            title: todo.title.clone(),
+            due_at: todo
+                .due_at
+                .map(|due_at| due_at.format(INPUT_FORMAT).to_string())
+                .unwrap_or_default(),
+            completed: todo.completed.then(|| "on".to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn query(&self) -> ListQuery {
+        ListQuery {
+            show: self.show,
+            search: self.search.clone(),
+        }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.completed.is_some()
+    }
+
+    /// Checks the form with the same rules as the API. Surrounding whitespace is
+    /// trimmed from the title first, which the API leaves to its clients.
+    pub fn to_request(&self) -> Result<TodoItemRequest, FormErrors> {
+        let due_at = match self.due_at.trim() {
+            "" => Ok(None),
+            due_at => NaiveDateTime::parse_from_str(due_at, INPUT_FORMAT)
+                .map(|due_at| Some(due_at.and_utc()))
+                .map_err(|_| FieldError {
+                    field: "due_at".to_string(),
+                    code: "invalid_date".to_string(),
+                    message: "due date must be a date and time".to_string(),
+                    position: None,
+                    path: None,
+                }),
+        };
+        let request = TodoItemRequest {
+            id: None,
+            title: self.title.trim().to_string(),
+            completed: self.is_completed(),
+            due_at: Some(due_at.clone().unwrap_or_default()),
+        };
+
+        let mut errors = match request.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => ValidationErrorResponse::from(errors).errors,
+        };
+        errors.extend(due_at.err());
+        if errors.is_empty() {
+            Ok(request)
+        } else {
+            Err(FormErrors(errors))
+        }
+    }
+}
+
+/// Validation messages shown next to the form fields
+#[derive(Clone, Debug, Default)]
+pub struct FormErrors(pub Vec<FieldError>);
+
+impl FormErrors {
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|error| error.field == field)
+            .map(|error| error.message.as_str())
+    }
+}
+
+/// A todo item as shown in the list
+#[derive(Clone, Debug)]
+pub struct TodoView {
+    pub id: i64,
+    pub title: String,
+    pub completed: bool,
+    pub due: Option<String>,
+    pub overdue: bool,
+}
+
+impl From<TodoItemResponse> for TodoView {
+    fn from(todo: TodoItemResponse) -> Self {
+        Self {
+            id: todo.id.unwrap_or_default(),
+            overdue: !todo.completed && todo.due_at.is_some_and(|due_at| due_at < Utc::now()),
+            due: todo
+                .due_at
+                .map(|due_at| due_at.format("%b %-d, %Y %H:%M UTC").to_string()),
+            title: todo.title,
+            completed: todo.completed,
+        }
+    }
+}
+
+/// The edit form of one row
+#[derive(Clone, Debug)]
+pub struct EditView {
+    pub id: i64,
+    pub form: TodoForm,
+    pub errors: FormErrors,
+}
+
+/// One of the All / Open / Done links above the list
+#[derive(Clone, Debug)]
+pub struct Tab {
+    pub label: &'static str,
+    pub href: String,
+    pub active: bool,
+}
+
+/// The add form and the list, swapped as a whole when the list changes
+#[derive(Clone, Debug)]
+pub struct TodoApp {
+    pub todos: Vec<TodoView>,
+    pub query: ListQuery,
+    pub tabs: Vec<Tab>,
+    pub form: TodoForm,
+    pub errors: FormErrors,
+    /// Row shown as an edit form, for browsers without JavaScript
+    pub edit: Option<EditView>,
+}
+
+impl TodoApp {
+    pub fn new(todos: Vec<TodoItemResponse>, query: ListQuery) -> Self {
+        let tabs = [("All", Show::All), ("Open", Show::Open), ("Done", Show::Done)]
+            .into_iter()
+            .map(|(label, show)| Tab {
+                label,
+                href: ListQuery { show, search: query.search.clone() }.href(),
+                active: query.show == show,
+            })
+            .collect();
+        let form = TodoForm {
+            show: query.show,
+            search: query.search.clone(),
+            ..TodoForm::default()
+        };
+
+        Self {
+            todos: todos.into_iter().map(TodoView::from).collect(),
+            tabs,
+            form,
+            query,
+            errors: FormErrors::default(),
+            edit: None,
+        }
+    }
+
+    pub fn is_editing(&self, id: &i64) -> bool {
+        self.edit.as_ref().is_some_and(|edit| edit.id == *id)
+    }
+}
+
+#[derive(Template)]
+#[template(path = "web/index.html")]
+pub struct IndexPage {
+    pub app: TodoApp,
+}
+
+#[derive(Template)]
+#[template(path = "web/todo_app.html")]
+pub struct TodoAppPartial {
+    pub app: TodoApp,
+}
+
+#[derive(Template)]
+#[template(path = "web/todo_row.html")]
+pub struct TodoRowPartial {
+    pub todo: TodoView,
+    /// List the row is shown in, kept by its forms and links
+    pub query: ListQuery,
+}
+
+#[derive(Template)]
+#[template(path = "web/todo_edit.html")]
+pub struct TodoEditPartial {
+    pub edit: EditView,
+}
+
+#[derive(Template)]
+#[template(path = "web/error.html")]
+pub struct ErrorPage {
+    pub message: String,
+}
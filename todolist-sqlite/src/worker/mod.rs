@@ -0,0 +1,60 @@
+use std::{future::Future, sync::Mutex, time::Duration};
+
+use tokio::{sync::watch, task::JoinHandle, time::Instant};
+
+/// Handle passed to every background worker so it can stop when the server shuts down.
+#[derive(Clone)]
+pub struct Shutdown(watch::Receiver<bool>);
+
+impl Shutdown {
+    /// Resolves once shutdown has been requested.
+    pub async fn requested(&mut self) {
+        let _ = self.0.wait_for(|stop| *stop).await;
+    }
+}
+
+/// Registry of the background tasks spawned next to the HTTP server.
+pub struct Workers {
+    shutdown: watch::Sender<bool>,
+    handles: Mutex<Vec<(&'static str, JoinHandle<()>)>>,
+}
+
+impl Default for Workers {
+    fn default() -> Self {
+        let (shutdown, _) = watch::channel(false);
+        Self {
+            shutdown,
+            handles: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Workers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn<F, Fut>(&self, name: &'static str, task: F)
+    where
+        F: FnOnce(Shutdown) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handle = tokio::spawn(task(Shutdown(self.shutdown.subscribe())));
+        self.handles.lock().unwrap().push((name, handle));
+    }
+
+    /// Signals every worker to stop and waits for them to flush, aborting the ones
+    /// still running once `deadline` has elapsed.
+    pub async fn shutdown(&self, deadline: Duration) {
+        self.shutdown.send_replace(true);
+
+        let deadline = Instant::now() + deadline;
+        let handles = std::mem::take(&mut *self.handles.lock().unwrap());
+        for (name, mut handle) in handles {
+            if tokio::time::timeout_at(deadline, &mut handle).await.is_err() {
+                eprintln!("⚠️  Worker '{}' did not stop in time, aborting", name);
+                handle.abort();
+            }
+        }
+    }
+}
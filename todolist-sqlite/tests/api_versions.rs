@@ -0,0 +1,123 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::config::api_version::ApiVersion;
+use common::{TestApp, assert_json_include};
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn versions_share_data_through_their_own_dtos() {
+    let app = TestApp::new();
+
+    let v1 = app
+        .post("/v1/todo", json!({ "title": "From v1", "completed": false }))
+        .await;
+    v1.assert_status(StatusCode::CREATED);
+    app.assert_documented_in(ApiVersion::V1, Method::POST, "/todo", &v1);
+
+    let v2 = app.post("/v2/todo", json!({ "title": "From v2" })).await;
+    v2.assert_status(StatusCode::CREATED);
+    assert_json_include(&v2.json(), &json!({ "title": "From v2", "completed": false }));
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo", &v2);
+
+    let list = app.get("/v2/todo").await;
+    list.assert_status(StatusCode::OK);
+    assert_json_include(
+        &list.json(),
+        &json!({ "total": 2, "items": [{ "title": "From v1" }, { "title": "From v2" }] }),
+    );
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo", &list);
+
+    let id = v2.json()["id"].as_i64().unwrap();
+    let v1_view = app.get(&format!("/v1/todo/{}", id)).await;
+    assert_json_include(&v1_view.json(), &json!({ "id": id, "title": "From v2" }));
+}
+
+#[tokio::test]
+async fn v2_validates_with_its_own_rules() {
+    let app = TestApp::new();
+
+    let response = app.post("/v2/todo", json!({ "title": " padded " })).await;
+
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_json_include(
+        &response.json(),
+        &json!({ "errors": [{ "field": "title", "code": "trimmed" }] }),
+    );
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo", &response);
+}
+
+#[tokio::test]
+async fn deprecated_version_announces_its_sunset() {
+    let app = TestApp::new();
+
+    let v1 = app.get("/v1/todo").await;
+    let legacy = app.get("/todo").await;
+    let v2 = app.get("/v2/todo").await;
+
+    for response in [&v1, &legacy] {
+        response.assert_status(StatusCode::OK);
+        assert_eq!(response.header("deprecation"), Some("@1790812800"));
+        assert_eq!(response.header("sunset"), Some("Thu, 01 Apr 2027 00:00:00 GMT"));
+        assert_eq!(response.header("link"), Some("</api/v2/todo>; rel=\"successor-version\""));
+    }
+    assert_eq!(v2.header("deprecation"), None);
+    assert_eq!(v2.header("sunset"), None);
+    assert_eq!(v2.header("link"), None);
+
+    // Links point at the same resource in the successor version
+    let item = app.get("/v1/todo/7?verbose=true").await;
+    assert_eq!(
+        item.header("link"),
+        Some("</api/v2/todo/7?verbose=true>; rel=\"successor-version\"")
+    );
+}
+
+#[tokio::test]
+async fn each_version_serves_its_own_openapi_document() {
+    let app = TestApp::new();
+
+    let v1 = app.get(&app.settings.openapi.json_path_for(ApiVersion::V1)).await.json();
+    let v2 = app.get(&app.settings.openapi.json_path_for(ApiVersion::V2)).await.json();
+
+    assert_eq!(v1["paths"]["/todo"]["get"]["deprecated"], json!(true));
+    assert!(v1["servers"][0]["url"].as_str().unwrap().ends_with("/api/v1"));
+    assert!(v2["paths"]["/todo"]["get"]["deprecated"].is_null());
+    assert!(v2["servers"][0]["url"].as_str().unwrap().ends_with("/api/v2"));
+    assert!(v2["components"]["schemas"]["TodoListResponse"].is_object());
+}
+
+#[tokio::test]
+async fn versions_without_deprecation_send_no_headers() {
+    let app = TestApp::with_settings(|settings| settings.app.versions.v1.deprecated_at = None);
+
+    let response = app.get("/v1/todo").await;
+
+    response.assert_status(StatusCode::OK);
+    assert_eq!(response.header("deprecation"), None);
+}
+
+#[tokio::test]
+async fn v1_updates_without_a_due_date_keep_the_deadline() {
+    let app = TestApp::new();
+    let created = app
+        .post("/v2/todo", json!({ "title": "File taxes", "due_at": "2027-04-30T17:00:00Z" }))
+        .await
+        .json();
+    let id = created["id"].as_i64().unwrap();
+
+    let renamed = app
+        .put(&format!("/v1/todo/{}", id), json!({ "title": "File the taxes", "completed": false }))
+        .await;
+
+    renamed.assert_status(StatusCode::OK);
+    assert_eq!(renamed.json()["due_at"], "2027-04-30T17:00:00Z");
+    assert_eq!(app.get(&format!("/v2/todo/{}", id)).await.json()["due_at"], "2027-04-30T17:00:00Z");
+
+    // null still removes it
+    let cleared = app
+        .put(&format!("/v1/todo/{}", id), json!({ "title": "File the taxes", "completed": false, "due_at": null }))
+        .await
+        .json();
+    assert_eq!(cleared["due_at"], Value::Null);
+}
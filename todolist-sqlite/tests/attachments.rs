@@ -0,0 +1,260 @@
+mod common;
+
+use std::path::Path;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use axum_tutorial::config::api_version::ApiVersion;
+use common::{TestApp, TestResponse, assert_json_include, create_todo};
+use serde_json::json;
+
+const BOUNDARY: &str = "todo-test-boundary";
+
+/// Uploads `files` as `(file name, content type, content)` parts named `file`
+async fn upload(app: &TestApp, todo_id: i64, files: &[(&str, &str, &[u8])]) -> TestResponse {
+    app.send(upload_request(app, todo_id, files)).await
+}
+
+fn upload_request(app: &TestApp, todo_id: i64, files: &[(&str, &str, &[u8])]) -> Request<Body> {
+    let mut body = Vec::new();
+    for (file_name, content_type, content) in files {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                BOUNDARY, file_name, content_type
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(content);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+
+    Request::builder()
+        .method(Method::POST)
+        .uri(app.url(&format!("/v2/todo/{}/attachments", todo_id)))
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", BOUNDARY),
+        )
+        .body(Body::from(body))
+        .unwrap()
+}
+
+async fn download(app: &TestApp, todo_id: i64, id: i64, range: Option<&str>) -> TestResponse {
+    let mut builder = Request::builder().uri(app.url(&format!(
+        "/v2/todo/{}/attachments/{}",
+        todo_id, id
+    )));
+    if let Some(range) = range {
+        builder = builder.header(header::RANGE, range);
+    }
+    app.send(builder.body(Body::empty()).unwrap()).await
+}
+
+fn stored_files(app: &TestApp) -> usize {
+    let dir = Path::new(&app.settings.attachments.dir);
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.file_name().unwrap() != "tmp")
+        .flat_map(|shard| std::fs::read_dir(shard).unwrap())
+        .count()
+}
+
+#[tokio::test]
+async fn upload_list_and_download_attachments() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "With files", &[]).await;
+
+    let response = upload(
+        &app,
+        id,
+        &[
+            ("notes.txt", "text/plain", b"hello attachments"),
+            ("pixel.png", "image/png", b"\x89PNG fake"),
+        ],
+    )
+    .await;
+    response.assert_status(StatusCode::CREATED);
+    assert_json_include(
+        &response.json(),
+        &json!([
+            { "todo_id": id, "file_name": "notes.txt", "content_type": "text/plain", "size": 17 },
+            { "todo_id": id, "file_name": "pixel.png", "content_type": "image/png" }
+        ]),
+    );
+    app.assert_documented_in(
+        ApiVersion::V2,
+        Method::POST,
+        "/todo/{id}/attachments",
+        &response,
+    );
+    let attachment_id = response.json()[0]["id"].as_i64().unwrap();
+
+    let list = app.get(&format!("/v2/todo/{}/attachments", id)).await;
+    list.assert_status(StatusCode::OK);
+    assert_eq!(list.json().as_array().unwrap().len(), 2);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo/{id}/attachments", &list);
+
+    let file = download(&app, id, attachment_id, None).await;
+    file.assert_status(StatusCode::OK);
+    assert_eq!(file.text(), "hello attachments");
+    assert_eq!(file.header("content-type"), Some("text/plain"));
+    assert_eq!(
+        file.header("content-disposition"),
+        Some("attachment; filename=\"notes.txt\"; filename*=UTF-8''notes.txt")
+    );
+}
+
+#[tokio::test]
+async fn uploads_with_an_idempotency_key_keep_their_own_size_limit() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Large file", &[]).await;
+    let content = vec![b'x'; 100 * 1024];
+    let mut request = upload_request(&app, id, &[("large.txt", "text/plain", &content)]);
+    request
+        .headers_mut()
+        .insert("idempotency-key", "upload-1".parse().unwrap());
+
+    let response = app.send(request).await;
+
+    response.assert_status(StatusCode::CREATED);
+    assert_eq!(response.json()[0]["size"], 100 * 1024);
+}
+
+#[tokio::test]
+async fn download_honours_range_requests() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Ranged", &[]).await;
+    let attachment_id = upload(&app, id, &[("digits.txt", "text/plain", b"0123456789")])
+        .await
+        .json()[0]["id"]
+        .as_i64()
+        .unwrap();
+
+    let partial = download(&app, id, attachment_id, Some("bytes=2-5")).await;
+    partial.assert_status(StatusCode::PARTIAL_CONTENT);
+    assert_eq!(partial.text(), "2345");
+    assert_eq!(partial.header("content-range"), Some("bytes 2-5/10"));
+
+    let unsatisfiable = download(&app, id, attachment_id, Some("bytes=50-60")).await;
+    unsatisfiable.assert_status(StatusCode::RANGE_NOT_SATISFIABLE);
+}
+
+#[tokio::test]
+async fn identical_content_is_stored_once_and_cleaned_up_with_its_todos() {
+    let app = TestApp::new();
+    let first = create_todo(&app, "First", &[]).await;
+    let second = create_todo(&app, "Second", &[]).await;
+
+    upload(&app, first, &[("a.txt", "text/plain", b"shared")]).await;
+    upload(&app, second, &[("b.txt", "text/plain", b"shared")]).await;
+    upload(&app, second, &[("c.txt", "text/plain", b"only second")]).await;
+    assert_eq!(stored_files(&app), 2);
+
+    app.delete(&format!("/v2/todo/{}", first)).await;
+    assert_eq!(stored_files(&app), 2, "content still used by the second todo");
+    assert_eq!(
+        app.get(&format!("/v2/todo/{}/attachments", first))
+            .await
+            .json(),
+        json!([])
+    );
+
+    app.delete(&format!("/v2/todo/{}", second)).await;
+    assert_eq!(stored_files(&app), 0);
+}
+
+#[tokio::test]
+async fn delete_attachment_removes_unshared_content() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Delete me", &[]).await;
+    let attachment_id = upload(&app, id, &[("gone.txt", "text/plain", b"bye")])
+        .await
+        .json()[0]["id"]
+        .as_i64()
+        .unwrap();
+
+    let response = app
+        .delete(&format!("/v2/todo/{}/attachments/{}", id, attachment_id))
+        .await;
+
+    response.assert_status(StatusCode::NO_CONTENT);
+    assert_eq!(stored_files(&app), 0);
+    download(&app, id, attachment_id, None)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    app.delete(&format!("/v2/todo/{}/attachments/{}", id, attachment_id))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn rejects_disallowed_types_and_oversized_files() {
+    let app = TestApp::with_settings(|settings| settings.attachments.max_file_bytes = 8);
+    let id = create_todo(&app, "Limits", &[]).await;
+
+    let executable = upload(
+        &app,
+        id,
+        &[("tool.exe", "application/x-msdownload", b"MZ")],
+    )
+    .await;
+    executable.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let too_large = upload(&app, id, &[("big.txt", "text/plain", b"0123456789")]).await;
+    too_large.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+
+    // A rejected part attaches nothing, not even the parts before it
+    let mixed = upload(
+        &app,
+        id,
+        &[
+            ("ok.txt", "text/plain", b"fine"),
+            ("big.txt", "text/plain", b"0123456789"),
+        ],
+    )
+    .await;
+    mixed.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(
+        app.get(&format!("/v2/todo/{}/attachments", id))
+            .await
+            .json(),
+        json!([])
+    );
+    assert_eq!(stored_files(&app), 0);
+}
+
+#[tokio::test]
+async fn upload_to_missing_todo_is_not_found() {
+    let app = TestApp::new();
+
+    upload(&app, 999, &[("a.txt", "text/plain", b"x")])
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn uploads_finishing_after_their_todo_is_deleted_keep_nothing() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Short-lived", &[]).await;
+    let service = &app.app_state.attachment_service;
+    let mut upload = service.stage().await.unwrap();
+    upload.write(b"late").await.unwrap();
+
+    app.delete(&format!("/v2/todo/{}", id)).await;
+    let saved = service
+        .save(&app.app_state.db, id, "late.txt".to_string(), "text/plain".to_string(), upload)
+        .await
+        .unwrap();
+
+    assert!(saved.is_none());
+    assert!(service.list(&app.app_state.db, id).await.unwrap().is_empty());
+    assert_eq!(stored_files(&app), 0);
+    let staging = Path::new(&app.settings.attachments.dir).join("tmp");
+    assert_eq!(std::fs::read_dir(staging).unwrap().count(), 0);
+}
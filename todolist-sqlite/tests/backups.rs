@@ -0,0 +1,177 @@
+mod common;
+
+use std::path::Path;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::{
+    config::{api_version::ApiVersion, settings::BackupConfig},
+    db::migrations,
+};
+use common::{TestApp, TestResponse, assert_json_include};
+use serde_json::{Value, json};
+
+const TOKEN: &str = "s3cret-admin-token";
+
+fn admin_app(keep: usize) -> TestApp {
+    TestApp::with_settings(|settings| {
+        settings.backup.admin_token = TOKEN.to_string();
+        settings.backup.keep = keep;
+    })
+}
+
+async fn admin(app: &TestApp, method: Method, path: &str, token: Option<&str>) -> TestResponse {
+    let authorization = token.map(|token| format!("Bearer {}", token));
+    let headers = authorization
+        .as_deref()
+        .map(|authorization| vec![("authorization", authorization)])
+        .unwrap_or_default();
+    app.request_with_headers(method, &format!("/v2/admin{}", path), None, &headers)
+        .await
+}
+
+async fn create_backup(app: &TestApp) -> String {
+    let response = admin(app, Method::POST, "/backups", Some(TOKEN)).await;
+    response.assert_status(StatusCode::CREATED);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/admin/backups", &response);
+    response.json()["name"].as_str().unwrap().to_string()
+}
+
+async fn titles(app: &TestApp) -> Vec<String> {
+    app.get("/v2/todo").await.json()["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|todo| todo["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+fn names(backups: &Value) -> Vec<&str> {
+    backups
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|backup| backup["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn snapshots_are_listed_newest_first_and_rotated() {
+    let app = admin_app(2);
+    app.post("/v2/todo", json!({ "title": "Backed up" })).await;
+
+    let mut created = Vec::new();
+    for _ in 0..3 {
+        created.push(create_backup(&app).await);
+    }
+
+    let listed = admin(&app, Method::GET, "/backups", Some(TOKEN)).await;
+    listed.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/admin/backups", &listed);
+    assert_eq!(names(&listed.json()), [created[2].as_str(), created[1].as_str()]);
+    assert!(listed.json()[0]["size_bytes"].as_u64().unwrap() > 0);
+    assert!(!Path::new(&app.settings.backup.dir).join(&created[0]).exists());
+}
+
+#[tokio::test]
+async fn restore_brings_back_the_snapshot() {
+    let app = admin_app(7);
+    let kept = app.post("/v2/todo", json!({ "title": "Keep me" })).await.json()["id"]
+        .as_i64()
+        .unwrap();
+    let name = create_backup(&app).await;
+
+    app.post("/v2/todo", json!({ "title": "Added later" })).await;
+    app.delete(&format!("/v2/todo/{}", kept)).await;
+    let token = app.get("/v2/sync").await.json()["token"].as_str().unwrap().to_string();
+
+    let restored = admin(&app, Method::POST, &format!("/backups/{}/restore", name), Some(TOKEN)).await;
+    restored.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/admin/backups/{name}/restore", &restored);
+    assert_json_include(
+        &restored.json(),
+        &json!({ "restored": { "name": name }, "schema_version": migrations::latest_version() }),
+    );
+    assert_eq!(titles(&app).await, ["Keep me"]);
+
+    // Sync clients that saw the discarded changes have to start over
+    app.get(&format!("/v2/sync?since={}", token))
+        .await
+        .assert_status(StatusCode::GONE);
+
+    // The database as it was before the restore can be restored in turn
+    let previous = restored.json()["previous"]["name"].as_str().unwrap().to_string();
+    let backups = admin(&app, Method::GET, "/backups", Some(TOKEN)).await.json();
+    assert_eq!(names(&backups), [previous.as_str(), name.as_str()]);
+    admin(&app, Method::POST, &format!("/backups/{}/restore", previous), Some(TOKEN))
+        .await
+        .assert_status(StatusCode::OK);
+    assert_eq!(titles(&app).await, ["Added later"]);
+}
+
+#[tokio::test]
+async fn rejects_corrupt_unknown_and_newer_snapshots() {
+    let app = admin_app(7);
+    create_backup(&app).await;
+    let dir = Path::new(&app.settings.backup.dir);
+
+    let corrupt = "todo-20260101-000000-000.db";
+    std::fs::write(dir.join(corrupt), b"definitely not a database").unwrap();
+    let newer = "todo-20260102-000000-000.db";
+    let connection = sqlite::open(dir.join(newer)).unwrap();
+    connection
+        .execute("CREATE TABLE todos (id INTEGER PRIMARY KEY); PRAGMA user_version = 999;")
+        .unwrap();
+    drop(connection);
+
+    for name in [corrupt, newer] {
+        let response = admin(&app, Method::POST, &format!("/backups/{}/restore", name), Some(TOKEN)).await;
+        response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        app.assert_documented_in(ApiVersion::V2, Method::POST, "/admin/backups/{name}/restore", &response);
+    }
+    assert!(
+        admin(&app, Method::POST, &format!("/backups/{}/restore", newer), Some(TOKEN))
+            .await
+            .text()
+            .contains("999")
+    );
+
+    for name in ["todo-20200101-000000-000.db", "..%2Ftodo.db", "application.yaml"] {
+        admin(&app, Method::POST, &format!("/backups/{}/restore", name), Some(TOKEN))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+}
+
+#[tokio::test]
+async fn admin_endpoints_require_the_token() {
+    let app = admin_app(7);
+
+    for token in [None, Some("wrong"), Some("")] {
+        let response = admin(&app, Method::POST, "/backups", token).await;
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        app.assert_documented_in(ApiVersion::V2, Method::POST, "/admin/backups", &response);
+    }
+
+    let disabled = TestApp::new();
+    admin(&disabled, Method::GET, "/backups", Some(""))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    assert!(!Path::new(&disabled.settings.backup.dir).exists());
+}
+
+#[test]
+fn scheduled_backups_need_an_interval() {
+    let config = |enabled: bool, interval_secs: u64| {
+        serde_json::from_value::<BackupConfig>(json!({
+            "enabled": enabled,
+            "dir": "backups",
+            "interval_secs": interval_secs,
+            "keep": 7,
+            "admin_token": ""
+        }))
+    };
+
+    assert!(config(true, 3600).is_ok());
+    assert!(config(true, 0).is_err());
+    assert!(config(false, 0).is_ok());
+}
@@ -0,0 +1,225 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::config::api_version::ApiVersion;
+use common::{TestApp, TestResponse, assert_json_include, create_todo};
+use serde_json::{Value, json};
+
+async fn comment(
+    app: &TestApp,
+    method: Method,
+    path: &str,
+    author: Option<&str>,
+    body: Option<Value>,
+) -> TestResponse {
+    let headers = author.map(|author| vec![("x-user", author)]).unwrap_or_default();
+    app.request_with_headers(method, &format!("/v2/todo{}", path), body, &headers)
+        .await
+}
+
+#[tokio::test]
+async fn thread_interleaves_comments_with_activity() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Buy milk", &[]).await;
+
+    let created = comment(
+        &app,
+        Method::POST,
+        &format!("/{}/comments", id),
+        Some("Alice"),
+        Some(json!({ "body": "On it, **@Bob** can you check the fridge?" })),
+    )
+    .await;
+    created.assert_status(StatusCode::CREATED);
+    assert_json_include(
+        &created.json(),
+        &json!({
+            "todo_id": id,
+            "author": "alice",
+            "body_html": "<p>On it, <strong>@Bob</strong> can you check the fridge?</p>\n",
+            "mentions": ["bob"],
+            "edited_at": null
+        }),
+    );
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/comments", &created);
+
+    app.put(
+        &format!("/v2/todo/{}", id),
+        json!({ "title": "Buy oat milk", "completed": true }),
+    )
+    .await
+    .assert_status(StatusCode::OK);
+    comment(
+        &app,
+        Method::POST,
+        &format!("/{}/comments", id),
+        Some("bob"),
+        Some(json!({ "body": "Done" })),
+    )
+    .await
+    .assert_status(StatusCode::CREATED);
+
+    let thread = app.get(&format!("/v2/todo/{}/comments", id)).await;
+    thread.assert_status(StatusCode::OK);
+    assert_json_include(
+        &thread.json(),
+        &json!([
+            { "type": "comment", "author": "alice" },
+            { "type": "activity", "kind": "renamed", "from": "Buy milk", "to": "Buy oat milk" },
+            { "type": "activity", "kind": "completed", "from": null, "to": null },
+            { "type": "comment", "author": "bob", "body": "Done" }
+        ]),
+    );
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo/{id}/comments", &thread);
+}
+
+#[tokio::test]
+async fn mentions_skip_code_emails_and_duplicates() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Mentions", &[]).await;
+
+    let created = comment(
+        &app,
+        Method::POST,
+        &format!("/{}/comments", id),
+        Some("alice"),
+        Some(json!({
+            "body": "@carol and @Dave, thanks @carol.\nMail dave@example.com, run `@ignored`\n\n```\n@also_ignored\n```"
+        })),
+    )
+    .await;
+
+    created.assert_status(StatusCode::CREATED);
+    assert_eq!(created.json()["mentions"], json!(["carol", "dave"]));
+}
+
+#[tokio::test]
+async fn markdown_is_rendered_without_raw_html() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Markdown", &[]).await;
+
+    let created = comment(
+        &app,
+        Method::POST,
+        &format!("/{}/comments", id),
+        Some("alice"),
+        Some(json!({ "body": "See <script>alert(1)</script> [docs](https://example.com) [x](javascript:alert(1))" })),
+    )
+    .await;
+
+    assert_eq!(
+        created.json()["body_html"],
+        "<p>See &lt;script&gt;alert(1)&lt;/script&gt; <a href=\"https://example.com\">docs</a> <a href=\"#\">x</a></p>\n"
+    );
+}
+
+#[tokio::test]
+async fn only_the_author_can_edit_or_delete() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Ownership", &[]).await;
+    let comment_id = comment(
+        &app,
+        Method::POST,
+        &format!("/{}/comments", id),
+        Some("alice"),
+        Some(json!({ "body": "Hi @bob" })),
+    )
+    .await
+    .json()["id"]
+        .as_i64()
+        .unwrap();
+    let path = format!("/{}/comments/{}", id, comment_id);
+
+    comment(&app, Method::PUT, &path, Some("bob"), Some(json!({ "body": "Mine now" })))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    comment(&app, Method::DELETE, &path, Some("bob"), None)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let edited = comment(&app, Method::PUT, &path, Some("ALICE"), Some(json!({ "body": "Hi @carol" }))).await;
+    edited.assert_status(StatusCode::OK);
+    assert_json_include(&edited.json(), &json!({ "body": "Hi @carol", "mentions": ["carol"] }));
+    assert!(edited.json()["edited_at"].is_string());
+    app.assert_documented_in(
+        ApiVersion::V2,
+        Method::PUT,
+        "/todo/{id}/comments/{comment_id}",
+        &edited,
+    );
+
+    let deleted = comment(&app, Method::DELETE, &path, Some("alice"), None).await;
+    deleted.assert_status(StatusCode::NO_CONTENT);
+    assert_eq!(app.get(&format!("/v2/todo/{}/comments", id)).await.json(), json!([]));
+    comment(&app, Method::DELETE, &path, Some("alice"), None)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn rejects_missing_authors_and_invalid_bodies() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Validation", &[]).await;
+    let path = format!("/{}/comments", id);
+
+    comment(&app, Method::POST, &path, None, Some(json!({ "body": "Anonymous" })))
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    comment(&app, Method::POST, &path, Some("no spaces"), Some(json!({ "body": "Hi" })))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let blank = comment(&app, Method::POST, &path, Some("alice"), Some(json!({ "body": "  " }))).await;
+    blank.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/comments", &blank);
+
+    comment(&app, Method::POST, "/999/comments", Some("alice"), Some(json!({ "body": "Hi" })))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    app.get("/v2/todo/999/comments")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn deleting_the_todo_removes_its_thread() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Short lived", &[]).await;
+    comment(
+        &app,
+        Method::POST,
+        &format!("/{}/comments", id),
+        Some("alice"),
+        Some(json!({ "body": "Bye @bob" })),
+    )
+    .await;
+
+    app.delete(&format!("/v2/todo/{}", id))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let connection = app.app_state.db.lock().unwrap();
+    for table in ["comments", "comment_mentions", "todo_activity"] {
+        let mut statement = connection
+            .prepare(format!("SELECT COUNT(*) FROM {}", table))
+            .unwrap();
+        statement.next().unwrap();
+        assert_eq!(statement.read::<i64, _>(0).unwrap(), 0, "{} is not empty", table);
+    }
+}
+
+#[tokio::test]
+async fn comments_on_a_todo_deleted_meanwhile_save_nothing() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Gone", &[]).await;
+    app.delete(&format!("/v2/todo/{}", id)).await;
+
+    let service = &app.app_state.comment_service;
+    let saved = service
+        .create(&app.app_state.db, id, "alice", "Too late".to_string())
+        .await
+        .unwrap();
+
+    assert!(saved.is_none());
+    assert!(service.thread(&app.app_state.db, id).await.unwrap().is_empty());
+}
@@ -0,0 +1,341 @@
+//! Test support: builds the full application router against a fresh in-memory
+//! database and sends requests to it in-process, without binding a socket.
+#![allow(dead_code)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use axum::{
+    Router,
+    body::{Body, Bytes, to_bytes},
+    http::{HeaderMap, Method, Request, StatusCode, header},
+};
+use axum_tutorial::{
+    app,
+    config::{api_version::ApiVersion, settings::AppSettings},
+    db, routes,
+    types::AppState,
+};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+pub struct TestApp {
+    pub router: Router,
+    pub settings: AppSettings,
+    pub app_state: AppState,
+    /// The generated OpenAPI document of every API version, as served at `openapi.json_path`
+    pub openapi: Vec<(ApiVersion, Value)>,
+}
+
+impl TestApp {
+    pub fn new() -> Self {
+        Self::with_settings(|_| {})
+    }
+
+    /// Builds the app after letting the test adjust the settings loaded from `application.yaml`
+    pub fn with_settings(configure: impl FnOnce(&mut AppSettings)) -> Self {
+        static NEXT_APP: AtomicUsize = AtomicUsize::new(0);
+
+        let mut settings = AppSettings::new().expect("Failed to load app settings");
+        settings.limits.rate_limit.enabled = false;
+        let data_dir = std::env::temp_dir().join(format!(
+            "todo-test-{}-{}",
+            std::process::id(),
+            NEXT_APP.fetch_add(1, Ordering::Relaxed)
+        ));
+        settings.attachments.dir = data_dir.join("attachments").to_string_lossy().into_owned();
+        settings.backup.dir = data_dir.join("backups").to_string_lossy().into_owned();
+        configure(&mut settings);
+
+        let app_state = app::build_state(&settings, db::open(":memory:"));
+        let router = routes::build_router(&settings, app_state.clone());
+        let openapi = ApiVersion::ALL
+            .into_iter()
+            .map(|version| {
+                let (_, openapi) = routes::create_routes(&settings, version);
+                (version, serde_json::to_value(openapi).unwrap())
+            })
+            .collect();
+
+        Self {
+            router,
+            openapi,
+            settings,
+            app_state,
+        }
+    }
+
+    /// Prefixes `path` with the configured `app.prefix`
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.settings.app.prefix, path)
+    }
+
+    pub async fn send(&self, request: Request<Body>) -> TestResponse {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        TestResponse { status, headers, body }
+    }
+
+    pub async fn request(&self, method: Method, path: &str, body: Option<Value>) -> TestResponse {
+        self.request_with_headers(method, path, body, &[]).await
+    }
+
+    pub async fn request_with_headers(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+        headers: &[(&str, &str)],
+    ) -> TestResponse {
+        let mut builder = Request::builder().method(method).uri(self.url(path));
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+
+        let request = match body {
+            Some(body) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        };
+        self.send(request.unwrap()).await
+    }
+
+    pub async fn get(&self, path: &str) -> TestResponse {
+        self.request(Method::GET, path, None).await
+    }
+
+    pub async fn post(&self, path: &str, body: Value) -> TestResponse {
+        self.request(Method::POST, path, Some(body)).await
+    }
+
+    pub async fn put(&self, path: &str, body: Value) -> TestResponse {
+        self.request(Method::PUT, path, Some(body)).await
+    }
+
+    pub async fn delete(&self, path: &str) -> TestResponse {
+        self.request(Method::DELETE, path, None).await
+    }
+
+    pub fn openapi(&self, version: ApiVersion) -> &Value {
+        self.openapi
+            .iter()
+            .find_map(|(documented, openapi)| (*documented == version).then_some(openapi))
+            .unwrap()
+    }
+
+    /// Asserts that `response` is documented for `method` on the OpenAPI `path_template`
+    /// (e.g. `/todo/{id}`) of the legacy, unversioned routes and, when it is JSON, that the
+    /// body matches the documented schema.
+    pub fn assert_documented(&self, method: Method, path_template: &str, response: &TestResponse) {
+        self.assert_documented_in(ApiVersion::LEGACY, method, path_template, response);
+    }
+
+    /// Same as [`TestApp::assert_documented`] against the document of `version`
+    pub fn assert_documented_in(
+        &self,
+        version: ApiVersion,
+        method: Method,
+        path_template: &str,
+        response: &TestResponse,
+    ) {
+        let openapi = self.openapi(version);
+        let operation = &openapi["paths"][path_template][method.as_str().to_lowercase()];
+        assert!(
+            operation.is_object(),
+            "{} {} is not in the OpenAPI document",
+            method,
+            path_template
+        );
+
+        let documented = &operation["responses"][response.status.as_str()];
+        assert!(
+            documented.is_object(),
+            "{} {} responded {} which is not documented",
+            method,
+            path_template,
+            response.status
+        );
+
+        if let Some(schema) = documented.pointer("/content/application~1json/schema") {
+            let body = response.json();
+            if let Err(e) = validate(openapi, schema, &body, "$") {
+                panic!("{} {} {}: {}\nbody: {}", method, path_template, response.status, e, body);
+            }
+        }
+    }
+}
+
+/// Minimal JSON Schema check covering the keywords utoipa emits for our models
+fn validate(openapi: &Value, schema: &Value, value: &Value, at: &str) -> Result<(), String> {
+    if let Some(reference) = schema["$ref"].as_str() {
+        let pointer = reference.trim_start_matches('#');
+        let resolved = openapi
+            .pointer(pointer)
+            .ok_or_else(|| format!("unresolved $ref {}", reference))?;
+        return validate(openapi, resolved, value, at);
+    }
+
+    if let Some(parts) = schema["allOf"].as_array() {
+        for part in parts {
+            validate(openapi, part, value, at)?;
+        }
+        return Ok(());
+    }
+
+    if let Some(variants) = schema["oneOf"].as_array().or(schema["anyOf"].as_array()) {
+        return variants
+            .iter()
+            .find(|variant| validate(openapi, variant, value, at).is_ok())
+            .map(|_| ())
+            .ok_or_else(|| format!("{} matches none of the allowed schemas", at));
+    }
+
+    let types = match &schema["type"] {
+        Value::String(t) => vec![t.as_str()],
+        Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    if !types.is_empty() && !types.iter().any(|t| matches_type(t, value)) {
+        return Err(format!("{} should be {:?} but was {}", at, types, value));
+    }
+
+    if let Some(allowed) = schema["enum"].as_array()
+        && !allowed.contains(value)
+    {
+        return Err(format!("{} should be one of {:?} but was {}", at, allowed, value));
+    }
+
+    match value {
+        Value::Object(object) => {
+            for required in schema["required"].as_array().into_iter().flatten() {
+                let name = required.as_str().unwrap();
+                if !object.contains_key(name) {
+                    return Err(format!("{} is missing required property '{}'", at, name));
+                }
+            }
+            if let Some(properties) = schema["properties"].as_object() {
+                for (name, property) in object {
+                    match properties.get(name) {
+                        Some(property_schema) => validate(
+                            openapi,
+                            property_schema,
+                            property,
+                            &format!("{}.{}", at, name),
+                        )?,
+                        None if schema["additionalProperties"] == Value::Bool(false) => {
+                            return Err(format!("{} has undocumented property '{}'", at, name));
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                validate(openapi, &schema["items"], item, &format!("{}[{}]", at, index))?;
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if schema["minLength"].as_u64().is_some_and(|min| length < min)
+                || schema["maxLength"].as_u64().is_some_and(|max| length > max)
+            {
+                return Err(format!("{} has a length outside the documented bounds", at));
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.settings.attachments.dir);
+    }
+}
+
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl TestResponse {
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or_else(|e| {
+            panic!("Response body is not JSON ({}): {}", e, self.text())
+        })
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).to_string()
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    #[track_caller]
+    pub fn assert_status(&self, expected: StatusCode) -> &Self {
+        assert_eq!(self.status, expected, "unexpected status, body: {}", self.text());
+        self
+    }
+}
+
+/// Creates a todo item through the v2 API, tagged with `tags`, returning its id
+pub async fn create_todo(app: &TestApp, title: &str, tags: &[&str]) -> i64 {
+    create_todo_with(app, json!({ "title": title }), tags).await
+}
+
+/// Like [`create_todo`], with more fields than the title in `body`
+pub async fn create_todo_with(app: &TestApp, body: Value, tags: &[&str]) -> i64 {
+    let response = app.post("/v2/todo", body).await;
+    response.assert_status(StatusCode::CREATED);
+    let id = response.json()["id"].as_i64().unwrap();
+    if !tags.is_empty() {
+        app.post(&format!("/v2/todo/{}/tags", id), json!({ "tags": tags }))
+            .await
+            .assert_status(StatusCode::OK);
+    }
+    id
+}
+
+/// Asserts that every field of `expected` is present in `actual` with the same value.
+/// Fields of `actual` not mentioned in `expected` are ignored.
+#[track_caller]
+pub fn assert_json_include(actual: &Value, expected: &Value) {
+    match (actual, expected) {
+        (Value::Object(actual_fields), Value::Object(expected_fields)) => {
+            for (name, expected_value) in expected_fields {
+                let actual_value = actual_fields
+                    .get(name)
+                    .unwrap_or_else(|| panic!("missing field '{}' in {}", name, actual));
+                assert_json_include(actual_value, expected_value);
+            }
+        }
+        (Value::Array(actual_items), Value::Array(expected_items)) => {
+            assert_eq!(actual_items.len(), expected_items.len(), "array length differs in {}", actual);
+            for (actual_item, expected_item) in actual_items.iter().zip(expected_items) {
+                assert_json_include(actual_item, expected_item);
+            }
+        }
+        _ => assert_eq!(actual, expected),
+    }
+}
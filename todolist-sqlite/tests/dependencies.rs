@@ -0,0 +1,140 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::config::api_version::ApiVersion;
+use common::{TestApp, TestResponse, assert_json_include, create_todo};
+use serde_json::{Value, json};
+
+async fn block(app: &TestApp, id: i64, blocked_by: i64) -> TestResponse {
+    app.post(&format!("/v2/todo/{}/dependencies", id), json!({ "blocked_by": blocked_by }))
+        .await
+}
+
+fn titles(todos: &Value) -> Vec<&str> {
+    todos
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|todo| todo["title"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn todos_are_blocked_until_their_dependencies_are_completed() {
+    let app = TestApp::new();
+    let design = create_todo(&app, "Design", &[]).await;
+    let build = create_todo(&app, "Build", &[]).await;
+
+    let added = block(&app, build, design).await;
+    added.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/dependencies", &added);
+    assert_eq!(titles(&added.json()["blocked_by"]), ["Design"]);
+    assert_eq!(app.get(&format!("/v2/todo/{}", build)).await.json()["is_blocked"], true);
+    assert_eq!(app.get(&format!("/v1/todo/{}", build)).await.json()["is_blocked"], true);
+    let blocked = app.get("/v2/todo").await.json();
+    assert_json_include(&blocked["items"], &json!([{ "is_blocked": false }, { "is_blocked": true }]));
+
+    let design_side = app.get(&format!("/v2/todo/{}/dependencies", design)).await;
+    design_side.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo/{id}/dependencies", &design_side);
+    assert_eq!(titles(&design_side.json()["blocks"]), ["Build"]);
+    assert_eq!(titles(&design_side.json()["blocked_by"]), Vec::<&str>::new());
+
+    app.put(&format!("/v2/todo/{}", design), json!({ "title": "Design", "completed": true }))
+        .await
+        .assert_status(StatusCode::OK);
+    assert_eq!(app.get(&format!("/v2/todo/{}", build)).await.json()["is_blocked"], false);
+
+    let removed = app.delete(&format!("/v2/todo/{}/dependencies/{}", build, design)).await;
+    removed.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::DELETE, "/todo/{id}/dependencies/{blocked_by}", &removed);
+    assert_eq!(titles(&removed.json()["blocked_by"]), Vec::<&str>::new());
+    let again = app.delete(&format!("/v2/todo/{}/dependencies/{}", build, design)).await;
+    again.assert_status(StatusCode::NOT_FOUND);
+    app.assert_documented_in(ApiVersion::V2, Method::DELETE, "/todo/{id}/dependencies/{blocked_by}", &again);
+}
+
+#[tokio::test]
+async fn cycles_are_refused_with_their_path() {
+    let app = TestApp::new();
+    let a = create_todo(&app, "A", &[]).await;
+    let b = create_todo(&app, "B", &[]).await;
+    let c = create_todo(&app, "C", &[]).await;
+    block(&app, a, b).await.assert_status(StatusCode::OK);
+    block(&app, b, c).await.assert_status(StatusCode::OK);
+    // Adding an existing dependency again changes nothing
+    block(&app, a, b).await.assert_status(StatusCode::OK);
+
+    let cycle = block(&app, c, a).await;
+
+    cycle.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/dependencies", &cycle);
+    assert_json_include(
+        &cycle.json(),
+        &json!({ "errors": [{
+            "field": "blocked_by",
+            "code": "cycle",
+            "message": format!("would create a cycle: {} → {} → {} → {}", c, a, b, c),
+            "path": [c, a, b, c]
+        }] }),
+    );
+    let itself = block(&app, a, a).await;
+    assert_json_include(&itself.json(), &json!({ "errors": [{ "code": "cycle", "path": [a, a] }] }));
+
+    let unknown = block(&app, a, 99).await;
+    unknown.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_json_include(&unknown.json(), &json!({ "errors": [{ "field": "blocked_by", "code": "not_found" }] }));
+    let missing = block(&app, 99, a).await;
+    missing.assert_status(StatusCode::NOT_FOUND);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/dependencies", &missing);
+    assert!(app.get(&format!("/v2/todo/{}/dependencies", c)).await.json()["blocked_by"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn the_plan_lists_open_todos_after_their_blockers() {
+    let app = TestApp::new();
+    let deploy = create_todo(&app, "Deploy", &[]).await;
+    let docs = create_todo(&app, "Docs", &[]).await;
+    let test = create_todo(&app, "Test", &[]).await;
+    let build = create_todo(&app, "Build", &[]).await;
+    let design = create_todo(&app, "Design", &[]).await;
+    block(&app, deploy, test).await.assert_status(StatusCode::OK);
+    block(&app, deploy, docs).await.assert_status(StatusCode::OK);
+    block(&app, test, build).await.assert_status(StatusCode::OK);
+    block(&app, build, design).await.assert_status(StatusCode::OK);
+
+    let plan = app.get("/v2/todo/plan").await;
+
+    plan.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo/plan", &plan);
+    // Independent todos keep their manual order
+    assert_eq!(titles(&plan.json()), ["Docs", "Design", "Build", "Test", "Deploy"]);
+
+    // Completed todos leave the plan and no longer hold up the rest
+    app.put(&format!("/v2/todo/{}", design), json!({ "title": "Design", "completed": true }))
+        .await
+        .assert_status(StatusCode::OK);
+    let plan = app.get("/v2/todo/plan").await.json();
+    assert_eq!(titles(&plan), ["Docs", "Build", "Test", "Deploy"]);
+    let blocked: Vec<bool> = plan.as_array().unwrap().iter().map(|todo| todo["is_blocked"].as_bool().unwrap()).collect();
+    assert_eq!(blocked, [false, false, true, true]);
+}
+
+#[tokio::test]
+async fn deleting_a_todo_removes_its_dependencies() {
+    let app = TestApp::new();
+    let first = create_todo(&app, "First", &[]).await;
+    let second = create_todo(&app, "Second", &[]).await;
+    block(&app, second, first).await.assert_status(StatusCode::OK);
+
+    app.delete(&format!("/v2/todo/{}", first))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let second_side = app.get(&format!("/v2/todo/{}/dependencies", second)).await.json();
+    assert!(second_side["blocked_by"].as_array().unwrap().is_empty());
+    assert_eq!(app.get(&format!("/v2/todo/{}", second)).await.json()["is_blocked"], false);
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use async_graphql::futures_util::StreamExt;
+use axum::http::StatusCode;
+use axum_tutorial::graphql::schema::build_schema;
+use common::{TestApp, assert_json_include};
+use serde_json::{Value, json};
+
+async fn graphql(app: &TestApp, query: &str, variables: Value) -> Value {
+    let response = app
+        .post("/graphql", json!({ "query": query, "variables": variables }))
+        .await;
+    response.assert_status(StatusCode::OK);
+    response.json()
+}
+
+#[tokio::test]
+async fn mutations_and_queries_share_the_rest_data() {
+    let app = TestApp::new();
+    app.post("/todo", json!({ "title": "From REST", "completed": false }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let created = graphql(
+        &app,
+        "mutation($title: String!) { createTodo(input: { title: $title }) { id title completed } }",
+        json!({ "title": "From GraphQL" }),
+    )
+    .await;
+    let id = created["data"]["createTodo"]["id"].as_i64().unwrap();
+
+    let toggled = graphql(
+        &app,
+        "mutation($id: Int!) { toggleTodo(id: $id) { completed } }",
+        json!({ "id": id }),
+    )
+    .await;
+    assert_eq!(toggled["data"]["toggleTodo"]["completed"], json!(true));
+
+    let open = graphql(
+        &app,
+        "{ todos(filter: { completed: false }) { title } }",
+        json!({}),
+    )
+    .await;
+    assert_json_include(&open["data"]["todos"], &json!([{ "title": "From REST" }]));
+
+    let rest = app.get(&format!("/todo/{}", id)).await.json();
+    assert_json_include(&rest, &json!({ "title": "From GraphQL", "completed": true }));
+}
+
+#[tokio::test]
+async fn update_and_delete_mutations() {
+    let app = TestApp::new();
+    let id = app
+        .post("/todo", json!({ "title": "Draft", "completed": false }))
+        .await
+        .json()["id"]
+        .as_i64()
+        .unwrap();
+
+    let updated = graphql(
+        &app,
+        "mutation($id: Int!) { updateTodo(id: $id, input: { title: \"Final\" }) { title completed } }",
+        json!({ "id": id }),
+    )
+    .await;
+    assert_json_include(&updated["data"]["updateTodo"], &json!({ "title": "Final", "completed": false }));
+
+    let deleted = graphql(
+        &app,
+        "mutation($id: Int!) { deleteTodo(id: $id) }",
+        json!({ "id": id }),
+    )
+    .await;
+    assert_eq!(deleted["data"]["deleteTodo"], json!(true));
+
+    let missing = graphql(&app, "query($id: Int!) { todo(id: $id) { id } }", json!({ "id": id })).await;
+    assert_eq!(missing["data"]["todo"], Value::Null);
+}
+
+#[tokio::test]
+async fn create_mutation_reports_validation_errors() {
+    let app = TestApp::new();
+
+    let response = graphql(
+        &app,
+        "mutation { createTodo(input: { title: \"\" }) { id } }",
+        json!({}),
+    )
+    .await;
+
+    assert_json_include(
+        &response["errors"][0]["extensions"],
+        &json!({ "code": "VALIDATION_FAILED", "fields": [{ "field": "title", "code": "length" }] }),
+    );
+}
+
+#[tokio::test]
+async fn subscription_streams_changes_made_over_rest() {
+    let app = TestApp::new();
+    let schema = build_schema(app.app_state.clone());
+    let mut changes =
+        schema.execute_stream("subscription { todoChanged(kinds: [CREATED]) { kind todo { title } } }");
+
+    // Polling once registers the subscriber before the change is published
+    tokio::select! {
+        _ = changes.next() => panic!("no change was made yet"),
+        _ = tokio::task::yield_now() => {}
+    }
+    app.post("/todo", json!({ "title": "Live", "completed": false }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let change = changes.next().await.unwrap().into_result().unwrap();
+    assert_eq!(
+        change.data.into_json().unwrap(),
+        json!({ "todoChanged": { "kind": "CREATED", "todo": { "title": "Live" } } })
+    );
+}
+
+#[tokio::test]
+async fn serves_graphiql_playground() {
+    let app = TestApp::new();
+
+    let response = app.get(&app.settings.graphql.playground_path.clone()).await;
+
+    response.assert_status(StatusCode::OK);
+    assert!(response.text().contains("graphiql"));
+}
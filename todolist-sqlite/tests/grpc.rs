@@ -0,0 +1,176 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use axum_tutorial::{
+    grpc::{
+        self,
+        proto::{
+            ChangeKind, CreateTodoRequest, DeleteTodoRequest, GetTodoRequest, ListTodosRequest,
+            UpdateTodoRequest, WatchTodosRequest, todo_service_client::TodoServiceClient,
+        },
+    },
+    worker::Workers,
+};
+use common::TestApp;
+use serde_json::json;
+use tonic::{
+    Code,
+    transport::{Channel, server::TcpIncoming},
+};
+use tonic_health::pb::{
+    HealthCheckRequest, health_check_response::ServingStatus, health_client::HealthClient,
+};
+use tonic_types::StatusExt;
+
+/// Serves the gRPC API of `app` on a free local port
+struct GrpcServer {
+    channel: Channel,
+    workers: Workers,
+}
+
+impl GrpcServer {
+    async fn start(app: &TestApp) -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let workers = Workers::new();
+        let app_state = app.app_state.clone();
+        workers.spawn("grpc-server", move |shutdown| async move {
+            grpc::serve_with_incoming(TcpIncoming::from(listener), app_state, true, shutdown)
+                .await
+                .unwrap();
+        });
+
+        let channel = Channel::from_shared(format!("http://{}", addr))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        Self { channel, workers }
+    }
+
+    fn client(&self) -> TodoServiceClient<Channel> {
+        TodoServiceClient::new(self.channel.clone())
+    }
+}
+
+#[tokio::test]
+async fn crud_over_grpc_shares_the_rest_data() {
+    let app = TestApp::new();
+    let server = GrpcServer::start(&app).await;
+    let mut client = server.client();
+
+    let created = client
+        .create_todo(CreateTodoRequest {
+            title: "From gRPC".to_string(),
+            completed: false,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    app.get(&format!("/todo/{}", created.id))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let updated = client
+        .update_todo(UpdateTodoRequest {
+            id: created.id,
+            title: None,
+            completed: Some(true),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        (updated.title.as_str(), updated.completed),
+        ("From gRPC", true)
+    );
+
+    app.post("/todo", json!({ "title": "From REST", "completed": false }))
+        .await;
+    let open = client
+        .list_todos(ListTodosRequest {
+            completed: Some(false),
+            search: None,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        open.todos
+            .iter()
+            .map(|todo| todo.title.as_str())
+            .collect::<Vec<_>>(),
+        ["From REST"]
+    );
+
+    client
+        .delete_todo(DeleteTodoRequest { id: created.id })
+        .await
+        .unwrap();
+    let missing = client
+        .get_todo(GetTodoRequest { id: created.id })
+        .await
+        .unwrap_err();
+    assert_eq!(missing.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn invalid_requests_report_field_violations() {
+    let app = TestApp::new();
+    let server = GrpcServer::start(&app).await;
+
+    let status = server
+        .client()
+        .create_todo(CreateTodoRequest {
+            title: " padded ".to_string(),
+            completed: false,
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(status.code(), Code::InvalidArgument);
+    let violations = status.get_details_bad_request().unwrap().field_violations;
+    assert_eq!(violations[0].field, "title");
+}
+
+#[tokio::test]
+async fn watch_streams_changes_until_shutdown() {
+    let app = TestApp::new();
+    let server = GrpcServer::start(&app).await;
+    let mut changes = server
+        .client()
+        .watch_todos(WatchTodosRequest {
+            kinds: vec![ChangeKind::Created.into()],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    app.post("/todo", json!({ "title": "Watched", "completed": false }))
+        .await;
+    let change = changes.message().await.unwrap().unwrap();
+    assert_eq!(change.kind(), ChangeKind::Created);
+    assert_eq!(change.todo.unwrap().title, "Watched");
+
+    server.workers.shutdown(Duration::from_secs(5)).await;
+    assert!(changes.message().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn reports_serving_health() {
+    let app = TestApp::new();
+    let server = GrpcServer::start(&app).await;
+
+    let response = HealthClient::new(server.channel.clone())
+        .check(HealthCheckRequest {
+            service: "todo.v1.TodoService".to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(response.status(), ServingStatus::Serving);
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::{
+    config::api_version::ApiVersion,
+    db::ordering::{self, STEP},
+};
+use common::{TestApp, TestResponse, assert_json_include, create_todo};
+use serde_json::{Value, json};
+
+async fn create_todos(app: &TestApp, titles: &[&str]) -> Vec<i64> {
+    let mut ids = Vec::new();
+    for title in titles {
+        ids.push(create_todo(app, title, &[]).await);
+    }
+    ids
+}
+
+async fn move_todo(app: &TestApp, id: i64, body: Value) -> TestResponse {
+    app.post(&format!("/v2/todo/{}/move", id), body).await
+}
+
+async fn titles(app: &TestApp) -> Vec<String> {
+    app.get("/v2/todo").await.json()["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|todo| todo["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+fn positions(app: &TestApp) -> Vec<f64> {
+    let connection = app.app_state.db.lock().unwrap();
+    connection
+        .prepare("SELECT position FROM todos ORDER BY position")
+        .unwrap()
+        .iter()
+        .map(|row| row.unwrap().read::<f64, _>("position"))
+        .collect()
+}
+
+#[tokio::test]
+async fn lists_follow_creation_order_until_moved() {
+    let app = TestApp::new();
+    let [a, _, c] = create_todos(&app, &["A", "B", "C"]).await[..] else {
+        unreachable!()
+    };
+    assert_eq!(titles(&app).await, ["A", "B", "C"]);
+
+    let moved = move_todo(&app, c, json!({ "before": a })).await;
+    moved.assert_status(StatusCode::OK);
+    assert_json_include(&moved.json(), &json!({ "id": c, "title": "C" }));
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/move", &moved);
+    assert_eq!(titles(&app).await, ["C", "A", "B"]);
+
+    move_todo(&app, c, json!({ "after": a }))
+        .await
+        .assert_status(StatusCode::OK);
+    assert_eq!(titles(&app).await, ["A", "C", "B"]);
+
+    move_todo(&app, a, json!({ "after": c }))
+        .await
+        .assert_status(StatusCode::OK);
+    assert_eq!(titles(&app).await, ["C", "A", "B"]);
+}
+
+#[tokio::test]
+async fn moves_only_rewrite_the_moved_item() {
+    let app = TestApp::new();
+    let ids = create_todos(&app, &["A", "B", "C", "D"]).await;
+    let before = positions(&app);
+
+    move_todo(&app, ids[3], json!({ "after": ids[0] })).await;
+
+    let after = positions(&app);
+    assert_eq!(after, [before[0], 1.5 * STEP, before[1], before[2]]);
+}
+
+#[tokio::test]
+async fn running_out_of_room_rebalances_and_keeps_the_order() {
+    let app = TestApp::new();
+    let ids = create_todos(&app, &["first", "last"]).await;
+
+    // Each new item lands halfway between "first" and the previous one, halving the gap
+    let mut expected = vec!["first".to_string()];
+    for n in (0..60).rev() {
+        let title = format!("item {}", n);
+        let id = create_todos(&app, &[&title]).await[0];
+        move_todo(&app, id, json!({ "after": ids[0] }))
+            .await
+            .assert_status(StatusCode::OK);
+        expected.insert(1, title);
+    }
+    expected.push("last".to_string());
+
+    assert_eq!(titles(&app).await, expected);
+    assert!(positions(&app).windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[tokio::test]
+async fn neighbours_without_a_float_between_them_are_rebalanced_at_any_magnitude() {
+    let app = TestApp::new();
+    let ids = create_todos(&app, &["A", "B", "C"]).await;
+    // Adjacent floats, far apart by an absolute epsilon
+    app.app_state
+        .db
+        .lock()
+        .unwrap()
+        .execute(format!(
+            "UPDATE todos SET position = 1e15 WHERE id = {}; UPDATE todos SET position = 1e15 + 0.125 WHERE id = {}",
+            ids[0], ids[1]
+        ))
+        .unwrap();
+
+    move_todo(&app, ids[2], json!({ "after": ids[0] }))
+        .await
+        .assert_status(StatusCode::OK);
+
+    assert_eq!(titles(&app).await, ["A", "C", "B"]);
+    assert!(positions(&app).windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[tokio::test]
+async fn periodic_rebalance_spreads_crowded_positions() {
+    let app = TestApp::new();
+    let ids = create_todos(&app, &["A", "B", "C"]).await;
+    assert!(!ordering::rebalance_if_needed(&app.app_state.db));
+
+    for _ in 0..20 {
+        move_todo(&app, ids[2], json!({ "before": ids[1] })).await;
+        move_todo(&app, ids[1], json!({ "before": ids[2] })).await;
+    }
+    let order = titles(&app).await;
+
+    assert!(ordering::rebalance_if_needed(&app.app_state.db));
+    assert_eq!(positions(&app), [STEP, 2.0 * STEP, 3.0 * STEP]);
+    assert_eq!(titles(&app).await, order);
+}
+
+#[tokio::test]
+async fn rejects_invalid_moves() {
+    let app = TestApp::new();
+    let ids = create_todos(&app, &["A", "B"]).await;
+
+    for (body, field, code) in [
+        (json!({}), "before", "required"),
+        (json!({ "before": ids[1], "after": ids[1] }), "after", "exclusive"),
+        (json!({ "after": ids[0] }), "after", "self"),
+        (json!({ "before": 999 }), "before", "not_found"),
+    ] {
+        let response = move_todo(&app, ids[0], body).await;
+        response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        assert_json_include(
+            &response.json(),
+            &json!({ "errors": [{ "field": field, "code": code }] }),
+        );
+        app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/move", &response);
+    }
+
+    move_todo(&app, 999, json!({ "before": ids[0] }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(titles(&app).await, ["A", "B"]);
+}
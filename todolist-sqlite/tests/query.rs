@@ -0,0 +1,312 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::{
+    config::api_version::ApiVersion,
+    query::{self, Comparison, DateFilter, Day, Expr, Term},
+};
+use chrono::NaiveDate;
+use common::{TestApp, assert_json_include, create_todo, create_todo_with};
+use serde_json::{Value, json};
+
+/// Percent-encodes a query for the `q` parameter
+fn encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn titles(list: &Value) -> Vec<&str> {
+    list["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|todo| todo["title"].as_str().unwrap())
+        .collect()
+}
+
+async fn search(app: &TestApp, q: &str) -> Value {
+    let response = app.get(&format!("/v2/todo?q={}", encode(q))).await;
+    response.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo", &response);
+    response.json()
+}
+
+async fn seed(app: &TestApp) {
+    create_todo_with(
+        app,
+        json!({ "title": "Write release notes", "due_at": "2026-10-30T09:00:00Z" }),
+        &["work"],
+    )
+    .await;
+    create_todo_with(
+        app,
+        json!({ "title": "Review release plan", "due_at": "2026-11-15T09:00:00Z" }),
+        &["work", "urgent"],
+    )
+    .await;
+    create_todo_with(app, json!({ "title": "Buy milk", "completed": true }), &["home"]).await;
+    create_todo(app, "Call the bank", &[]).await;
+}
+
+#[test]
+fn parses_fields_phrases_and_operators() {
+    let expr = query::parse(r#"completed:false tag:work due<2026-11-01 "release notes""#).unwrap();
+
+    assert_eq!(
+        expr,
+        Expr::And(vec![
+            Expr::Term(Term::Completed(false)),
+            Expr::Term(Term::Tag(Some("work".to_string()))),
+            Expr::Term(Term::Due(DateFilter::Compare(
+                Comparison::Before,
+                Day::Date(NaiveDate::from_ymd_opt(2026, 11, 1).unwrap()),
+            ))),
+            Expr::Term(Term::Text("release notes".to_string())),
+        ])
+    );
+    // OR binds looser than the implicit AND, and - negates
+    assert_eq!(
+        query::parse("a b OR -tag:home").unwrap(),
+        Expr::Or(vec![
+            Expr::And(vec![
+                Expr::Term(Term::Text("a".to_string())),
+                Expr::Term(Term::Text("b".to_string())),
+            ]),
+            Expr::Not(Box::new(Expr::Term(Term::Tag(Some("home".to_string()))))),
+        ])
+    );
+    assert_eq!(
+        query::parse(r#"title:"bank call" created>=yesterday"#).unwrap(),
+        Expr::And(vec![
+            Expr::Term(Term::Text("bank call".to_string())),
+            Expr::Term(Term::Created(DateFilter::Compare(Comparison::OnOrAfter, Day::Today(-1)))),
+        ])
+    );
+    assert_eq!(query::parse("  ").unwrap(), Expr::And(vec![]));
+}
+
+#[test]
+fn syntax_errors_point_at_their_position() {
+    let cases = [
+        ("colour:red", 0, "unknown field \"colour\""),
+        ("tag:work (a OR b", 9, "unclosed \"(\""),
+        ("a)", 1, "unexpected \")\""),
+        ("a OR", 4, "expected a search term"),
+        ("() a", 0, "empty parentheses"),
+        ("\"release notes", 0, "unterminated quote"),
+        ("due<soon", 4, "is not a date"),
+        ("completed:maybe", 10, "expected true or false"),
+        ("tag<work", 3, "only supports"),
+        ("created<none", 8, "without comparing"),
+    ];
+
+    for (text, position, message) in cases {
+        let error = query::parse(text).unwrap_err();
+        assert_eq!(error.position, position, "{}", text);
+        assert!(error.message.contains(message), "{}: {}", text, error.message);
+    }
+}
+
+#[test]
+fn compiles_to_bound_parameters() {
+    let expr = query::parse(r#"tag:work "'; DROP TABLE todos; --""#).unwrap();
+    let condition = query::compile(&expr, NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(), 5);
+
+    assert!(condition.sql.contains("qg.folded_name = ?5"), "{}", condition.sql);
+    assert!(condition.sql.contains("lower(?6)"), "{}", condition.sql);
+    assert!(!condition.sql.contains("DROP"), "{}", condition.sql);
+    assert_eq!(condition.params.len(), 2);
+
+    // Days are UTC day ranges in seconds
+    let expr = query::parse("due:tomorrow").unwrap();
+    let condition = query::compile(&expr, NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(), 1);
+    assert_eq!(condition.sql, "t.due_at >= ?1 AND t.due_at < ?2");
+    assert_eq!(
+        condition.params,
+        [sqlite::Value::Integer(1_792_454_400), sqlite::Value::Integer(1_792_540_800)]
+    );
+}
+
+#[tokio::test]
+async fn q_filters_the_todo_list() {
+    let app = TestApp::new();
+    seed(&app).await;
+
+    let found = search(&app, r#"completed:false tag:work due<2026-11-01 "release notes""#).await;
+    assert_eq!(titles(&found), ["Write release notes"]);
+
+    assert_eq!(titles(&search(&app, "release").await), ["Write release notes", "Review release plan"]);
+    assert_eq!(titles(&search(&app, "tag:urgent OR tag:home").await), ["Review release plan", "Buy milk"]);
+    assert_eq!(titles(&search(&app, "tag:work -tag:urgent").await), ["Write release notes"]);
+    assert_eq!(titles(&search(&app, "tag:none").await), ["Call the bank"]);
+    assert_eq!(titles(&search(&app, "due:none completed:no").await), ["Call the bank"]);
+    assert_eq!(titles(&search(&app, "due>=2026-10-30 AND due<=2026-10-30").await), ["Write release notes"]);
+    assert_eq!(titles(&search(&app, "due:2026-11-15").await), ["Review release plan"]);
+    // Items without a due date aren't before it, nor is NOT of that unknown
+    assert_eq!(
+        titles(&search(&app, "NOT due<2026-11-01").await),
+        ["Review release plan", "Buy milk", "Call the bank"]
+    );
+    assert_eq!(titles(&search(&app, "created:today").await).len(), 4);
+    assert_eq!(titles(&search(&app, "created<today").await).len(), 0);
+    // Items from before creation times were recorded have none, and match no comparison
+    app.app_state
+        .db
+        .lock()
+        .unwrap()
+        .execute("UPDATE todos SET created_at = 0 WHERE title = 'Call the bank'")
+        .unwrap();
+    assert_eq!(titles(&search(&app, "created:none").await), ["Call the bank"]);
+    assert_eq!(titles(&search(&app, "created<today").await).len(), 0);
+    assert_eq!(titles(&search(&app, "created:any").await).len(), 3);
+    assert_eq!(titles(&search(&app, "(milk OR bank) completed:true").await), ["Buy milk"]);
+
+    // The other filters still apply
+    let combined = app.get(&format!("/v2/todo?tag=work&q={}", encode("plan"))).await.json();
+    assert_eq!(titles(&combined), ["Review release plan"]);
+    let v1 = app.get(&format!("/v1/todo?q={}", encode("tag:home"))).await;
+    v1.assert_status(StatusCode::OK);
+    assert_eq!(v1.json().as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn q_values_are_never_sql() {
+    let app = TestApp::new();
+    seed(&app).await;
+
+    let found = search(&app, r#""') OR 1=1 --" title:"x' OR 'a'='a""#).await;
+
+    assert_eq!(titles(&found), Vec::<&str>::new());
+    assert_eq!(titles(&search(&app, "").await).len(), 4);
+}
+
+#[tokio::test]
+async fn q_syntax_errors_are_reported_with_position() {
+    let app = TestApp::new();
+
+    let response = app.get(&format!("/v2/todo?q={}", encode("tag:work (due<today"))).await;
+
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo", &response);
+    assert_json_include(
+        &response.json(),
+        &json!({
+            "errors": [{
+                "field": "q",
+                "code": "syntax",
+                "message": "unclosed \"(\" at position 9",
+                "position": 9
+            }]
+        }),
+    );
+    // Other validation errors don't carry a position
+    let invalid = app.post("/v2/todo", json!({ "title": "" })).await.json();
+    assert!(invalid["errors"][0].get("position").is_none());
+}
+
+#[tokio::test]
+async fn oversized_queries_are_refused() {
+    let app = TestApp::new();
+    let words = vec!["a"; 1001].join(" ");
+
+    let long = app.get(&format!("/v2/todo?q={}", encode(&words))).await;
+    long.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo", &long);
+    assert_json_include(&long.json(), &json!({ "errors": [{ "field": "q", "code": "length" }] }));
+    app.get(&format!("/v2/todo/board?q={}", encode(&words)))
+        .await
+        .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+
+    let many = app.get(&format!("/v2/todo?q={}", encode(&vec!["a"; 101].join(" ")))).await;
+    assert_json_include(&many.json(), &json!({ "errors": [{ "code": "syntax", "position": 200 }] }));
+    let deep = format!("{}a{}", "(".repeat(17), ")".repeat(17));
+    let nested = app.get(&format!("/v2/todo?q={}", encode(&deep))).await;
+    assert_json_include(&nested.json(), &json!({ "errors": [{ "code": "syntax", "position": 16 }] }));
+    assert!(query::parse(&"NOT ".repeat(17)).unwrap_err().message.contains("nested more than 16"));
+
+    // The database is still usable
+    app.get("/v2/todo").await.assert_status(StatusCode::OK);
+    let within = format!("{}a{}", "(".repeat(16), ")".repeat(16));
+    app.get(&format!("/v2/todo?q={}", encode(&within)))
+        .await
+        .assert_status(StatusCode::OK);
+}
+
+#[tokio::test]
+async fn smart_lists_save_queries() {
+    let app = TestApp::new();
+    seed(&app).await;
+
+    let created = app
+        .post("/v2/smart-lists", json!({ "name": "Open work", "query": "completed:false tag:work" }))
+        .await;
+
+    created.assert_status(StatusCode::CREATED);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/smart-lists", &created);
+    let id = created.json()["id"].as_i64().unwrap();
+    assert_json_include(&created.json(), &json!({ "name": "Open work", "query": "completed:false tag:work" }));
+
+    let todos = app.get(&format!("/v2/smart-lists/{}/todos", id)).await;
+    todos.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/smart-lists/{list_id}/todos", &todos);
+    assert_eq!(titles(&todos.json()), ["Write release notes", "Review release plan"]);
+    assert_eq!(todos.json()["total"], 2);
+
+    // Lists are run when read, so they follow changes to the items
+    create_todo(&app, "Plan sprint", &["work"]).await;
+    let todos = app.get(&format!("/v2/smart-lists/{}/todos", id)).await.json();
+    assert_eq!(titles(&todos).len(), 3);
+
+    let updated = app
+        .put(&format!("/v2/smart-lists/{}", id), json!({ "name": "Urgent", "query": "tag:urgent" }))
+        .await;
+    updated.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::PUT, "/smart-lists/{list_id}", &updated);
+    let todos = app.get(&format!("/v2/smart-lists/{}/todos", id)).await.json();
+    assert_eq!(titles(&todos), ["Review release plan"]);
+
+    let listed = app.get("/v2/smart-lists").await;
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/smart-lists", &listed);
+    assert_json_include(&listed.json(), &json!([{ "id": id, "name": "Urgent", "query": "tag:urgent" }]));
+    let fetched = app.get(&format!("/v2/smart-lists/{}", id)).await;
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/smart-lists/{list_id}", &fetched);
+    fetched.assert_status(StatusCode::OK);
+
+    let deleted = app.delete(&format!("/v2/smart-lists/{}", id)).await;
+    deleted.assert_status(StatusCode::NO_CONTENT);
+    app.assert_documented_in(ApiVersion::V2, Method::DELETE, "/smart-lists/{list_id}", &deleted);
+    app.get(&format!("/v2/smart-lists/{}/todos", id))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(titles(&search(&app, "").await).len(), 5);
+}
+
+#[tokio::test]
+async fn smart_lists_reject_bad_queries_and_taken_names() {
+    let app = TestApp::new();
+    app.post("/v2/smart-lists", json!({ "name": "Work", "query": "tag:work" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let invalid = app
+        .post("/v2/smart-lists", json!({ "name": "Later", "query": "due>next-week" }))
+        .await;
+    invalid.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/smart-lists", &invalid);
+    assert_json_include(
+        &invalid.json(),
+        &json!({ "errors": [{ "field": "query", "code": "syntax", "position": 4 }] }),
+    );
+
+    let taken = app.post("/v2/smart-lists", json!({ "name": "WORK", "query": "" })).await;
+    taken.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_json_include(&taken.json(), &json!({ "errors": [{ "field": "name", "code": "taken" }] }));
+
+    app.put("/v2/smart-lists/99", json!({ "name": "Other", "query": "" }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
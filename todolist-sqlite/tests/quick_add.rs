@@ -0,0 +1,374 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::{
+    config::api_version::ApiVersion,
+    quick_add::{self, Frequency, MatchKind, ParseOptions, Priority, QuickAdd},
+};
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use common::{TestApp, assert_json_include};
+use serde_json::json;
+
+/// Monday 2026-10-19, 14:00 UTC
+const NOW: &str = "2026-10-19T14:00:00Z";
+
+fn at(instant: &str) -> DateTime<Utc> {
+    instant.parse().unwrap()
+}
+
+fn parse_in(text: &str, timezone: Tz, now: &str) -> QuickAdd {
+    quick_add::parse(
+        text,
+        &ParseOptions {
+            timezone,
+            default_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            now: at(now),
+        },
+    )
+}
+
+fn parse(text: &str) -> QuickAdd {
+    parse_in(text, Tz::UTC, NOW)
+}
+
+/// Title and due date of each text
+fn assert_due(cases: &[(&str, &str, &str)]) {
+    for (text, title, due_at) in cases {
+        let parsed = parse(text);
+        assert_eq!(parsed.title, *title, "title of {:?}", text);
+        assert_eq!(parsed.due_at, Some(at(due_at)), "due date of {:?}", text);
+    }
+}
+
+#[test]
+fn reads_every_part_of_the_example() {
+    let parsed = parse("Pay rent tomorrow 9am #home !high every month");
+
+    assert_eq!(parsed.title, "Pay rent");
+    assert_eq!(parsed.due_at, Some(at("2026-10-20T09:00:00Z")));
+    assert_eq!(parsed.tags, ["home"]);
+    assert_eq!(parsed.priority, Some(Priority::High));
+    let recurrence = parsed.recurrence.unwrap();
+    assert_eq!(recurrence.frequency, Frequency::Monthly);
+    assert_eq!(recurrence.interval, 1);
+    assert_eq!(recurrence.rule, "FREQ=MONTHLY");
+    assert_eq!(
+        parsed.matches.iter().map(|m| (m.kind, m.text.as_str())).collect::<Vec<_>>(),
+        [
+            (MatchKind::Date, "tomorrow"),
+            (MatchKind::Time, "9am"),
+            (MatchKind::Tag, "#home"),
+            (MatchKind::Priority, "!high"),
+            (MatchKind::Recurrence, "every month"),
+        ]
+    );
+}
+
+#[test]
+fn plain_text_is_only_a_title() {
+    let parsed = parse("Buy 2 apples and a pear");
+
+    assert_eq!(parsed.title, "Buy 2 apples and a pear");
+    assert_eq!(parsed.due_at, None);
+    assert!(parsed.tags.is_empty());
+    assert_eq!(parsed.priority, None);
+    assert_eq!(parsed.recurrence, None);
+    assert!(parsed.matches.is_empty());
+}
+
+#[test]
+fn reads_relative_days() {
+    assert_due(&[
+        ("Water plants today", "Water plants", "2026-10-19T09:00:00Z"),
+        ("Water plants tomorrow", "Water plants", "2026-10-20T09:00:00Z"),
+        ("Water plants tmrw", "Water plants", "2026-10-20T09:00:00Z"),
+        ("Water plants day after tomorrow", "Water plants", "2026-10-21T09:00:00Z"),
+        ("Watch a movie tonight", "Watch a movie", "2026-10-19T20:00:00Z"),
+        ("Watch a movie tonight at 10pm", "Watch a movie", "2026-10-19T22:00:00Z"),
+        ("Hike weekend", "Hike", "2026-10-24T09:00:00Z"),
+        ("Hike this weekend", "Hike", "2026-10-24T09:00:00Z"),
+    ]);
+}
+
+#[test]
+fn weekdays_are_the_next_one_after_today() {
+    assert_due(&[
+        ("Call mom friday", "Call mom", "2026-10-23T09:00:00Z"),
+        ("Call mom on Friday", "Call mom", "2026-10-23T09:00:00Z"),
+        ("Team sync monday", "Team sync", "2026-10-26T09:00:00Z"),
+        ("Team sync next mon", "Team sync", "2026-10-26T09:00:00Z"),
+        ("Team sync this wed", "Team sync", "2026-10-21T09:00:00Z"),
+    ]);
+}
+
+#[test]
+fn reads_next_periods_and_offsets() {
+    assert_due(&[
+        ("Plan sprint next week", "Plan sprint", "2026-10-26T09:00:00Z"),
+        ("Pay invoice next month", "Pay invoice", "2026-11-01T09:00:00Z"),
+        ("Renew domain next year", "Renew domain", "2027-01-01T09:00:00Z"),
+        ("Follow up in 3 days", "Follow up", "2026-10-22T09:00:00Z"),
+        ("Follow up in two weeks", "Follow up", "2026-11-02T09:00:00Z"),
+        ("Follow up in a month", "Follow up", "2026-11-19T09:00:00Z"),
+        ("Follow up in 1 year", "Follow up", "2027-10-19T09:00:00Z"),
+    ]);
+}
+
+#[test]
+fn hours_and_minutes_from_now_are_exact() {
+    assert_due(&[
+        ("Check the oven in 20 minutes", "Check the oven", "2026-10-19T14:20:00Z"),
+        ("Stretch in an hour", "Stretch", "2026-10-19T15:00:00Z"),
+        ("Stretch in 2 hours", "Stretch", "2026-10-19T16:00:00Z"),
+    ]);
+}
+
+#[test]
+fn reads_calendar_dates() {
+    assert_due(&[
+        ("Release 2026-12-24", "Release", "2026-12-24T09:00:00Z"),
+        ("Release nov 1", "Release", "2026-11-01T09:00:00Z"),
+        ("Release November 1st 2027", "Release", "2027-11-01T09:00:00Z"),
+        ("Release 1 nov", "Release", "2026-11-01T09:00:00Z"),
+        ("Release the 3rd of December", "Release the", "2026-12-03T09:00:00Z"),
+        ("Release due by dec 3", "Release", "2026-12-03T09:00:00Z"),
+        // Dates already past this year are next year's
+        ("Release march 3", "Release", "2027-03-03T09:00:00Z"),
+        ("Release oct 19", "Release", "2026-10-19T09:00:00Z"),
+    ]);
+}
+
+#[test]
+fn reads_times_of_day() {
+    assert_due(&[
+        ("Dentist friday 9:30pm", "Dentist", "2026-10-23T21:30:00Z"),
+        ("Dentist friday 9 pm", "Dentist", "2026-10-23T21:00:00Z"),
+        ("Dentist friday at 21:00", "Dentist", "2026-10-23T21:00:00Z"),
+        ("Dentist friday @ 8:15", "Dentist", "2026-10-23T08:15:00Z"),
+        ("Lunch friday noon", "Lunch", "2026-10-23T12:00:00Z"),
+        ("Deploy friday midnight", "Deploy", "2026-10-23T00:00:00Z"),
+        ("Deploy friday 12am", "Deploy", "2026-10-23T00:00:00Z"),
+        ("Lunch friday 12pm", "Lunch", "2026-10-23T12:00:00Z"),
+        ("Dentist 5pm friday", "Dentist", "2026-10-23T17:00:00Z"),
+    ]);
+}
+
+#[test]
+fn a_time_alone_is_its_next_occurrence() {
+    assert_due(&[
+        ("Standup at 3pm", "Standup", "2026-10-19T15:00:00Z"),
+        ("Coffee 8am", "Coffee", "2026-10-20T08:00:00Z"),
+    ]);
+}
+
+#[test]
+fn impossible_times_stay_in_the_title() {
+    for text in ["Call 13pm", "Call 25:00", "Call 9:5", "Call 0am", "Room 12"] {
+        let parsed = parse(text);
+        assert_eq!(parsed.title, text);
+        assert_eq!(parsed.due_at, None, "due date of {:?}", text);
+    }
+}
+
+#[test]
+fn dates_are_read_in_the_timezone() {
+    let madrid: Tz = "Europe/Madrid".parse().unwrap();
+
+    // Summer time, UTC+2
+    let parsed = parse_in("Pay rent tomorrow 9am", madrid, NOW);
+    assert_eq!(parsed.due_at, Some(at("2026-10-20T07:00:00Z")));
+
+    // Winter time from 2026-10-25, UTC+1
+    let parsed = parse_in("Pay rent oct 26 9am", madrid, NOW);
+    assert_eq!(parsed.due_at, Some(at("2026-10-26T08:00:00Z")));
+
+    // Already Tuesday in Madrid
+    let parsed = parse_in("Pay rent today", madrid, "2026-10-19T23:30:00Z");
+    assert_eq!(parsed.due_at, Some(at("2026-10-20T07:00:00Z")));
+}
+
+#[test]
+fn times_skipped_by_daylight_saving_move_forward() {
+    let madrid: Tz = "Europe/Madrid".parse().unwrap();
+
+    // Clocks jump from 02:00 to 03:00 on 2027-03-28
+    let parsed = parse_in("Night shift 2027-03-28 2:30am", madrid, NOW);
+
+    assert_eq!(parsed.due_at, Some(at("2027-03-28T01:30:00Z")));
+}
+
+#[test]
+fn reads_tags() {
+    let parsed = parse("Fix #Backend bug #123 #backend #on-call #día");
+
+    assert_eq!(parsed.title, "Fix bug #123");
+    assert_eq!(parsed.tags, ["backend", "on-call", "día"]);
+}
+
+#[test]
+fn the_last_priority_wins() {
+    let parsed = parse("Ship it !low !urgent !nope");
+
+    assert_eq!(parsed.title, "Ship it !nope");
+    assert_eq!(parsed.priority, Some(Priority::Urgent));
+    assert_eq!(parse("Ship it !med").priority, Some(Priority::Medium));
+}
+
+#[test]
+fn reads_recurrences() {
+    let cases = [
+        ("Stand up daily", Frequency::Daily, 1, "FREQ=DAILY"),
+        ("Stand up every day", Frequency::Daily, 1, "FREQ=DAILY"),
+        ("Review weekly", Frequency::Weekly, 1, "FREQ=WEEKLY"),
+        ("Review every other week", Frequency::Weekly, 2, "FREQ=WEEKLY;INTERVAL=2"),
+        ("Water every 3 days", Frequency::Daily, 3, "FREQ=DAILY;INTERVAL=3"),
+        ("Service every six months", Frequency::Monthly, 6, "FREQ=MONTHLY;INTERVAL=6"),
+        ("Renew annually", Frequency::Yearly, 1, "FREQ=YEARLY"),
+    ];
+    for (text, frequency, interval, rule) in cases {
+        let recurrence = parse(text).recurrence.unwrap();
+        assert_eq!(recurrence.frequency, frequency, "{:?}", text);
+        assert_eq!(recurrence.interval, interval, "{:?}", text);
+        assert_eq!(recurrence.rule, rule, "{:?}", text);
+    }
+}
+
+#[test]
+fn weekly_recurrences_name_their_days() {
+    let cases = [
+        ("Gym every monday and thursday", "FREQ=WEEKLY;BYDAY=MO,TH"),
+        ("Gym every fri, mon, wed", "FREQ=WEEKLY;BYDAY=MO,WE,FR"),
+        ("Gym every weekday", "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR"),
+        ("Gym every weekend", "FREQ=WEEKLY;BYDAY=SA,SU"),
+    ];
+    for (text, rule) in cases {
+        let parsed = parse(text);
+        assert_eq!(parsed.title, "Gym", "{:?}", text);
+        assert_eq!(parsed.recurrence.unwrap().rule, rule, "{:?}", text);
+    }
+}
+
+#[test]
+fn recurring_weekdays_are_due_on_the_first_one_ahead() {
+    assert_due(&[
+        // Monday's 9am has passed
+        ("Gym every monday and thursday", "Gym", "2026-10-22T09:00:00Z"),
+        ("Gym every monday 6pm", "Gym", "2026-10-19T18:00:00Z"),
+        ("Gym every monday starting nov 2", "Gym starting", "2026-11-02T09:00:00Z"),
+    ]);
+    assert_eq!(parse("Stand up daily").due_at, None);
+}
+
+#[test]
+fn only_the_first_date_time_and_recurrence_are_taken() {
+    let parsed = parse("Call Bob tomorrow about friday at 3pm at 5pm daily weekly");
+
+    assert_eq!(parsed.title, "Call Bob about friday at 5pm weekly");
+    assert_eq!(parsed.due_at, Some(at("2026-10-20T15:00:00Z")));
+    assert_eq!(parsed.recurrence.unwrap().rule, "FREQ=DAILY");
+}
+
+#[test]
+fn quoted_words_are_kept() {
+    let parsed = parse("Read \"next week\" tomorrow \"#notes\"");
+
+    assert_eq!(parsed.title, "Read next week #notes");
+    assert_eq!(parsed.due_at, Some(at("2026-10-20T09:00:00Z")));
+    assert!(parsed.tags.is_empty());
+}
+
+#[test]
+fn connectors_stay_unless_a_phrase_follows() {
+    let parsed = parse("Stand by me on repeat, by friday");
+
+    assert_eq!(parsed.title, "Stand by me on repeat");
+    assert_eq!(parsed.due_at, Some(at("2026-10-23T09:00:00Z")));
+    assert_eq!(parse("May the force be with you").title, "May the force be with you");
+}
+
+#[tokio::test]
+async fn quick_add_returns_the_parse_for_confirmation() {
+    let app = TestApp::new();
+
+    let response = app
+        .post(
+            "/v2/todo/quick",
+            json!({ "text": "Pay rent next year 9am #home !high every month", "timezone": "Europe/Madrid" }),
+        )
+        .await;
+
+    response.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/quick", &response);
+    assert_json_include(
+        &response.json(),
+        &json!({
+            "title": "Pay rent",
+            "due_at": "2027-01-01T08:00:00Z",
+            "tags": ["home"],
+            "priority": "high",
+            "recurrence": { "frequency": "monthly", "interval": 1, "by_day": [], "rule": "FREQ=MONTHLY" },
+            "matches": [
+                { "kind": "date", "text": "next year" },
+                { "kind": "time", "text": "9am" },
+                { "kind": "tag", "text": "#home" },
+                { "kind": "priority", "text": "!high" },
+                { "kind": "recurrence", "text": "every month" }
+            ],
+            "timezone": "Europe/Madrid",
+            "todo": { "title": "Pay rent", "completed": false, "due_at": "2027-01-01T08:00:00Z", "tags": ["home"] }
+        }),
+    );
+
+    // Nothing is saved until the client confirms
+    assert_eq!(app.get("/v2/todo").await.json()["total"], 0);
+    let todo = response.json()["todo"].clone();
+    let created = app.post("/v2/todo", todo.clone()).await;
+    created.assert_status(StatusCode::CREATED);
+    let id = created.json()["id"].as_i64().unwrap();
+    let tagged = app
+        .post(&format!("/v2/todo/{}/tags", id), json!({ "tags": todo["tags"] }))
+        .await;
+    tagged.assert_status(StatusCode::OK);
+    assert_json_include(&tagged.json(), &json!({ "tags": [{ "name": "home" }] }));
+}
+
+#[tokio::test]
+async fn quick_add_uses_the_configured_timezone() {
+    let app = TestApp::with_settings(|settings| {
+        settings.quick_add.timezone = "America/New_York".parse().unwrap();
+        settings.quick_add.default_time = NaiveTime::from_hms_opt(8, 0, 0).unwrap();
+    });
+
+    let response = app.post("/v2/todo/quick", json!({ "text": "Call mom 2027-01-15" })).await;
+
+    response.assert_status(StatusCode::OK);
+    assert_json_include(
+        &response.json(),
+        &json!({ "due_at": "2027-01-15T13:00:00Z", "timezone": "America/New_York" }),
+    );
+}
+
+#[tokio::test]
+async fn quick_add_rejects_what_it_cannot_turn_into_a_todo() {
+    let app = TestApp::new();
+    let long_title = "a".repeat(201);
+    let cases = [
+        (json!({ "text": "" }), "text", "length"),
+        (json!({ "text": "x".repeat(501) }), "text", "length"),
+        (json!({ "text": "tomorrow 9am #home" }), "text", "no_title"),
+        (json!({ "text": format!("{} tomorrow", long_title) }), "text", "length"),
+        (json!({ "text": "Pay rent", "timezone": "Mars/Olympus" }), "timezone", "unknown_timezone"),
+    ];
+
+    for (body, field, code) in cases {
+        let response = app.post("/v2/todo/quick", body).await;
+
+        response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/quick", &response);
+        assert_json_include(
+            &response.json(),
+            &json!({ "errors": [{ "field": field, "code": code }] }),
+        );
+    }
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::config::api_version::ApiVersion;
+use chrono::{DateTime, Utc};
+use common::{TestApp, assert_json_include};
+use serde_json::json;
+
+/// Inserts a todo with fixed timestamps, which the API always sets to the current time
+fn insert_todo(app: &TestApp, created_at: &str, completed_at: Option<&str>) {
+    let timestamp = |at: &str| at.parse::<DateTime<Utc>>().unwrap().timestamp();
+    let connection = app.app_state.db.lock().unwrap();
+    let mut statement = connection
+        .prepare(
+            "INSERT INTO todos (title, completed, position, created_at, completed_at)
+            VALUES ('Imported', ?, 0, ?, ?)",
+        )
+        .unwrap();
+    statement.bind((1, completed_at.is_some() as i64)).unwrap();
+    statement.bind((2, timestamp(created_at))).unwrap();
+    statement.bind((3, completed_at.map(timestamp))).unwrap();
+    statement.next().unwrap();
+}
+
+#[tokio::test]
+async fn counts_the_list_by_status_and_due_date() {
+    let app = TestApp::new();
+    let overdue = app
+        .post(
+            "/v2/todo",
+            json!({ "title": "File taxes", "due_at": "2020-04-15T00:00:00Z" }),
+        )
+        .await;
+    overdue.assert_status(StatusCode::CREATED);
+    assert_eq!(overdue.json()["due_at"], "2020-04-15T00:00:00Z");
+    app.post(
+        "/v2/todo",
+        json!({ "title": "Renew passport", "due_at": "2999-01-01T00:00:00Z" }),
+    )
+    .await;
+    app.post("/v2/todo", json!({ "title": "Done", "completed": true }))
+        .await;
+    app.post(
+        "/v2/todo",
+        json!({ "title": "Done late", "completed": true, "due_at": "2020-01-01T00:00:00Z" }),
+    )
+    .await;
+
+    let stats = app.get("/v2/todo/stats").await;
+
+    stats.assert_status(StatusCode::OK);
+    assert_json_include(
+        &stats.json(),
+        &json!({
+            "total": 4,
+            "open": 2,
+            "completed": 2,
+            "overdue": 1,
+            "completion_rate": 0.5,
+            "interval": "day",
+            "to": Utc::now().date_naive().to_string()
+        }),
+    );
+    let periods = stats.json()["periods"].as_array().unwrap().clone();
+    assert_eq!(periods.len(), 30);
+    assert_json_include(&periods[29], &json!({ "created": 4, "completed": 2 }));
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo/stats", &stats);
+}
+
+#[tokio::test]
+async fn aggregates_weekly_periods_and_completion_time() {
+    let app = TestApp::new();
+    insert_todo(&app, "2026-10-05T10:00:00Z", Some("2026-10-06T10:00:00Z"));
+    insert_todo(&app, "2026-10-06T00:00:00Z", Some("2026-10-06T12:00:00Z"));
+    insert_todo(&app, "2026-10-13T09:00:00Z", None);
+    // Completed after the range, so not part of the average
+    insert_todo(&app, "2026-10-14T09:00:00Z", Some("2026-10-30T09:00:00Z"));
+    // Created before creation times were recorded, so counted as completed only
+    insert_todo(&app, "1970-01-01T00:00:00Z", Some("2026-10-07T10:00:00Z"));
+
+    let stats = app
+        .get("/v2/todo/stats?from=2026-10-07&to=2026-10-18&interval=week")
+        .await;
+
+    stats.assert_status(StatusCode::OK);
+    assert_json_include(
+        &stats.json(),
+        &json!({
+            "from": "2026-10-05",
+            "to": "2026-10-18",
+            "average_completion_secs": 64800.0,
+            "periods": [
+                { "start": "2026-10-05", "created": 2, "completed": 3 },
+                { "start": "2026-10-12", "created": 2, "completed": 0 }
+            ]
+        }),
+    );
+}
+
+#[tokio::test]
+async fn reopening_clears_the_completion() {
+    let app = TestApp::new();
+    let id = app
+        .post("/v2/todo", json!({ "title": "Flip flop", "completed": true }))
+        .await
+        .json()["id"]
+        .as_i64()
+        .unwrap();
+    app.put(
+        &format!("/v2/todo/{}", id),
+        json!({ "title": "Flip flop", "completed": false }),
+    )
+    .await;
+
+    let stats = app.get("/v2/todo/stats").await.json();
+
+    assert_json_include(&stats, &json!({ "completed": 0, "average_completion_secs": null }));
+    assert_eq!(stats["periods"][29]["completed"], 0);
+}
+
+#[tokio::test]
+async fn downloads_periods_as_csv() {
+    let app = TestApp::new();
+    insert_todo(&app, "2026-10-05T10:00:00Z", Some("2026-10-06T10:00:00Z"));
+
+    let csv = app
+        .get("/v2/todo/stats?from=2026-10-05&to=2026-10-07&format=csv")
+        .await;
+
+    csv.assert_status(StatusCode::OK);
+    assert_eq!(csv.header("content-type"), Some("text/csv; charset=utf-8"));
+    assert_eq!(
+        csv.header("content-disposition"),
+        Some("attachment; filename=\"todo-stats-2026-10-05-2026-10-07.csv\"")
+    );
+    assert_eq!(
+        csv.text(),
+        "period_start,created,completed\n2026-10-05,1,0\n2026-10-06,0,1\n2026-10-07,0,0\n"
+    );
+}
+
+#[tokio::test]
+async fn rejects_invalid_ranges() {
+    let app = TestApp::new();
+
+    for (query, field) in [
+        ("from=2026-10-10&to=2026-10-01", "from"),
+        ("from=2020-01-01&to=2026-10-01", "to"),
+    ] {
+        let response = app.get(&format!("/v2/todo/stats?{}", query)).await;
+        response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        assert_json_include(
+            &response.json(),
+            &json!({ "errors": [{ "field": field, "code": "range" }] }),
+        );
+        app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo/stats", &response);
+    }
+}
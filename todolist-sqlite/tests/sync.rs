@@ -0,0 +1,250 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::{config::api_version::ApiVersion, model::todo_sync::encode_token};
+use common::{TestApp, TestResponse, assert_json_include, create_todo};
+use serde_json::{Value, json};
+
+async fn pull(app: &TestApp, query: &str) -> TestResponse {
+    let response = app.get(&format!("/v2/sync{}", query)).await;
+    response.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/sync", &response);
+    response
+}
+
+async fn push(app: &TestApp, changes: Value) -> Value {
+    let response = app.post("/v2/sync", json!({ "changes": changes })).await;
+    response.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/sync", &response);
+    response.json()["results"].clone()
+}
+
+fn titles(changes: &Value) -> Vec<&str> {
+    changes
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|todo| todo["title"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn pulls_changes_and_tombstones_since_the_token() {
+    let app = TestApp::new();
+    let milk = create_todo(&app, "Buy milk", &[]).await;
+    let bread = create_todo(&app, "Buy bread", &[]).await;
+
+    let first = pull(&app, "").await.json();
+    assert_eq!(titles(&first["changes"]), ["Buy milk", "Buy bread"]);
+    assert_eq!(first["has_more"], false);
+    let token = first["token"].as_str().unwrap().to_string();
+
+    app.put(
+        &format!("/v2/todo/{}", milk),
+        json!({ "title": "Buy oat milk", "completed": false }),
+    )
+    .await
+    .assert_status(StatusCode::OK);
+    app.delete(&format!("/v2/todo/{}", bread))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    create_todo(&app, "Buy eggs", &[]).await;
+
+    let second = pull(&app, &format!("?since={}", token)).await.json();
+    assert_eq!(titles(&second["changes"]), ["Buy oat milk", "Buy eggs"]);
+    assert_json_include(&second, &json!({ "deleted": [{ "id": bread }] }));
+    let modified_at = &second["changes"][0]["modified_at"];
+    assert_ne!(modified_at["title"], modified_at["completed"]);
+
+    let third = pull(&app, &format!("?since={}", second["token"].as_str().unwrap())).await.json();
+    assert_eq!(third["changes"], json!([]));
+    assert_eq!(third["deleted"], json!([]));
+    assert_eq!(third["token"], second["token"]);
+}
+
+#[tokio::test]
+async fn pages_through_changes_with_a_limit() {
+    let app = TestApp::new();
+    for title in ["A", "B", "C"] {
+        create_todo(&app, title, &[]).await;
+    }
+
+    let page = pull(&app, "?limit=2").await.json();
+    assert_eq!(titles(&page["changes"]), ["A", "B"]);
+    assert_eq!(page["has_more"], true);
+
+    let rest = pull(&app, &format!("?since={}&limit=2", page["token"].as_str().unwrap()))
+        .await
+        .json();
+    assert_eq!(titles(&rest["changes"]), ["C"]);
+    assert_eq!(rest["has_more"], false);
+}
+
+#[tokio::test]
+async fn rejects_invalid_and_unknown_tokens() {
+    let app = TestApp::new();
+
+    for (query, field) in [("since=yesterday", "since"), ("limit=0", "limit")] {
+        let response = app.get(&format!("/v2/sync?{}", query)).await;
+        response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        assert_json_include(&response.json(), &json!({ "errors": [{ "field": field }] }));
+        app.assert_documented_in(ApiVersion::V2, Method::GET, "/sync", &response);
+    }
+
+    // A token from a history the server doesn't have, like after restoring a backup
+    let gone = app
+        .get(&format!("/v2/sync?since={}", encode_token(10_000)))
+        .await;
+    gone.assert_status(StatusCode::GONE);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/sync", &gone);
+}
+
+#[tokio::test]
+async fn pushes_offline_creates_updates_and_deletes() {
+    let app = TestApp::new();
+    let milk = create_todo(&app, "Buy milk", &[]).await;
+    let bread = create_todo(&app, "Buy bread", &[]).await;
+    let later = "2999-01-01T00:00:00Z";
+
+    let results = push(
+        &app,
+        json!([
+            {
+                "client_ref": "local-1",
+                "title": { "value": "Call mum", "modified_at": "2026-10-19T08:00:00Z" },
+                "due_at": { "value": "2026-10-20T18:00:00Z", "modified_at": "2026-10-19T08:00:00Z" }
+            },
+            { "id": milk, "completed": { "value": true, "modified_at": later } },
+            { "id": bread, "deleted_at": later }
+        ]),
+    )
+    .await;
+
+    assert_json_include(
+        &results,
+        &json!([
+            {
+                "client_ref": "local-1",
+                "status": "applied",
+                "todo": { "title": "Call mum", "completed": false, "due_at": "2026-10-20T18:00:00Z" }
+            },
+            { "id": milk, "status": "applied", "todo": { "title": "Buy milk", "completed": true } },
+            { "id": bread, "status": "applied", "todo": null }
+        ]),
+    );
+    assert!(results[0]["id"].is_i64());
+
+    let todos = app.get("/v2/todo").await.json();
+    assert_eq!(titles(&todos["items"]), ["Buy milk", "Call mum"]);
+    let thread = app.get(&format!("/v2/todo/{}/comments", milk)).await.json();
+    assert_json_include(&thread, &json!([{ "type": "activity", "kind": "completed" }]));
+}
+
+#[tokio::test]
+async fn keeps_the_newest_value_of_each_field() {
+    let app = TestApp::new();
+    let created = push(
+        &app,
+        json!([{
+            "title": { "value": "Draft", "modified_at": "2026-01-01T10:00:00Z" },
+            "completed": { "value": false, "modified_at": "2026-01-01T10:00:00Z" }
+        }]),
+    )
+    .await;
+    let id = created[0]["id"].as_i64().unwrap();
+
+    let results = push(
+        &app,
+        json!([{
+            "id": id,
+            "client_ref": "edit",
+            "title": { "value": "Final", "modified_at": "2026-01-02T10:00:00Z" },
+            "completed": { "value": true, "modified_at": "2025-12-31T10:00:00Z" }
+        }]),
+    )
+    .await;
+    assert_json_include(
+        &results[0],
+        &json!({
+            "status": "conflict",
+            "conflicts": [{
+                "field": "completed",
+                "server_value": false,
+                "server_modified_at": "2026-01-01T10:00:00Z"
+            }],
+            "todo": {
+                "title": "Final",
+                "completed": false,
+                "modified_at": { "title": "2026-01-02T10:00:00Z" }
+            }
+        }),
+    );
+
+    // Edits through the REST API happen now, after any of those offline changes
+    app.put(&format!("/v2/todo/{}", id), json!({ "title": "Published", "completed": false }))
+        .await
+        .assert_status(StatusCode::OK);
+    let results = push(
+        &app,
+        json!([{ "id": id, "title": { "value": "Final v2", "modified_at": "2026-01-03T10:00:00Z" } }]),
+    )
+    .await;
+    assert_json_include(
+        &results[0],
+        &json!({ "status": "conflict", "conflicts": [{ "field": "title", "server_value": "Published" }] }),
+    );
+
+    // Sending the server's value again is not a conflict, whatever its time
+    let results = push(
+        &app,
+        json!([{ "id": id, "title": { "value": "Published", "modified_at": "2020-01-01T00:00:00Z" } }]),
+    )
+    .await;
+    assert_json_include(&results[0], &json!({ "status": "applied", "conflicts": [] }));
+}
+
+#[tokio::test]
+async fn reports_deletes_that_lose_and_rejects_invalid_changes() {
+    let app = TestApp::new();
+    let edited = create_todo(&app, "Edited on the server", &[]).await;
+    let deleted = create_todo(&app, "Deleted on the server", &[]).await;
+    app.delete(&format!("/v2/todo/{}", deleted)).await;
+
+    let results = push(
+        &app,
+        json!([
+            { "id": edited, "deleted_at": "2020-01-01T00:00:00Z" },
+            { "id": deleted, "title": { "value": "Too late", "modified_at": "2999-01-01T00:00:00Z" } },
+            { "id": deleted, "deleted_at": "2999-01-01T00:00:00Z" },
+            { "id": 999, "completed": { "value": true, "modified_at": "2026-10-19T08:00:00Z" } },
+            { "id": edited, "title": { "value": " padded ", "modified_at": "2999-01-01T00:00:00Z" } },
+            { "client_ref": "untitled", "completed": { "value": true, "modified_at": "2026-10-19T08:00:00Z" } }
+        ]),
+    )
+    .await;
+
+    assert_json_include(
+        &results,
+        &json!([
+            {
+                "status": "conflict",
+                "conflicts": [{ "field": "title" }, { "field": "completed" }, { "field": "due_at" }],
+                "todo": { "title": "Edited on the server" }
+            },
+            { "status": "conflict", "conflicts": [{ "field": "deleted", "server_value": true }] },
+            { "status": "applied" },
+            { "status": "rejected", "errors": [{ "field": "id", "code": "not_found" }] },
+            {
+                "status": "rejected",
+                "errors": [{ "field": "title", "code": "trimmed" }],
+                "todo": { "title": "Edited on the server" }
+            },
+            { "status": "rejected", "errors": [{ "field": "title", "code": "required" }] }
+        ]),
+    );
+    assert_eq!(titles(&app.get("/v2/todo").await.json()["items"]), ["Edited on the server"]);
+
+    let empty = app.post("/v2/sync", json!({ "changes": [] })).await;
+    empty.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/sync", &empty);
+}
@@ -0,0 +1,296 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::config::api_version::ApiVersion;
+use common::{TestApp, assert_json_include, create_todo};
+use serde_json::{Value, json};
+
+async fn create_tag(app: &TestApp, name: &str) -> i64 {
+    let response = app.post("/v2/tags", json!({ "name": name })).await;
+    response.assert_status(StatusCode::CREATED);
+    response.json()["id"].as_i64().unwrap()
+}
+
+fn tag_names(todo: &Value) -> Vec<&str> {
+    todo["tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tag| tag["name"].as_str().unwrap())
+        .collect()
+}
+
+async fn titles(app: &TestApp, query: &str) -> Vec<String> {
+    let response = app.get(&format!("/v2/todo{}", query)).await;
+    response.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo", &response);
+    response.json()["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|todo| todo["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn tagging_a_todo_creates_missing_tags() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Write report", &[]).await;
+    app.post("/v2/tags", json!({ "name": "work", "color": "#2563EB" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let tagged = app
+        .post(&format!("/v2/todo/{}/tags", id), json!({ "tags": ["urgent", "Work", "WORK"] }))
+        .await;
+
+    tagged.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/tags", &tagged);
+    assert_json_include(
+        &tagged.json(),
+        &json!({
+            "title": "Write report",
+            "tags": [{ "name": "urgent", "color": "#6b7280" }, { "name": "work", "color": "#2563eb" }]
+        }),
+    );
+    let tags = app.get("/v2/tags").await;
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/tags", &tags);
+    assert_eq!(tags.json().as_array().unwrap().len(), 2);
+
+    // Lists and the v1 API return the tags too
+    let list = app.get("/v2/todo").await.json();
+    assert_eq!(tag_names(&list["items"][0]), ["urgent", "work"]);
+    let v1 = app.get(&format!("/v1/todo/{}", id)).await.json();
+    assert_eq!(tag_names(&v1), ["urgent", "work"]);
+
+    // Tagging is recorded in the activity thread
+    let thread = app.get(&format!("/v2/todo/{}/comments", id)).await.json();
+    assert_json_include(
+        &thread,
+        &json!([
+            { "type": "activity", "kind": "tagged", "to": "urgent" },
+            { "type": "activity", "kind": "tagged", "to": "work" }
+        ]),
+    );
+}
+
+#[tokio::test]
+async fn removing_a_tag_keeps_the_tag() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Write report", &["work", "urgent"]).await;
+
+    let removed = app.delete(&format!("/v2/todo/{}/tags/WORK", id)).await;
+
+    removed.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::DELETE, "/todo/{id}/tags/{name}", &removed);
+    assert_eq!(tag_names(&removed.json()), ["urgent"]);
+    assert_eq!(app.get("/v2/tags").await.json().as_array().unwrap().len(), 2);
+
+    let again = app.delete(&format!("/v2/todo/{}/tags/work", id)).await;
+    again.assert_status(StatusCode::NOT_FOUND);
+    app.assert_documented_in(ApiVersion::V2, Method::DELETE, "/todo/{id}/tags/{name}", &again);
+    app.delete("/v2/todo/999/tags/work")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn tagging_rejects_invalid_requests() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Write report", &[]).await;
+    let cases = [
+        (json!({ "tags": [] }), "length"),
+        (json!({ "tags": ["two words"] }), "tag_name"),
+        (json!({ "tags": ["123"] }), "tag_name"),
+        (json!({ "tags": ["#work"] }), "tag_name"),
+    ];
+
+    for (body, code) in cases {
+        let response = app.post(&format!("/v2/todo/{}/tags", id), body).await;
+
+        response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/tags", &response);
+        assert_json_include(&response.json(), &json!({ "errors": [{ "field": "tags", "code": code }] }));
+    }
+    app.post("/v2/todo/999/tags", json!({ "tags": ["work"] }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn filters_the_list_by_any_or_all_tags() {
+    let app = TestApp::new();
+    create_todo(&app, "Write report", &["work"]).await;
+    create_todo(&app, "Fix outage", &["work", "urgent"]).await;
+    create_todo(&app, "Water plants", &["home"]).await;
+    create_todo(&app, "Call mom", &[]).await;
+
+    assert_eq!(titles(&app, "?tag=work").await, ["Write report", "Fix outage"]);
+    assert_eq!(titles(&app, "?tag=URGENT").await, ["Fix outage"]);
+    assert_eq!(
+        titles(&app, "?tag=urgent&tag=home").await,
+        ["Fix outage", "Water plants"]
+    );
+    assert_eq!(
+        titles(&app, "?tag=work&tag=urgent&tag_match=all").await,
+        ["Fix outage"]
+    );
+    assert_eq!(
+        titles(&app, "?tag=work&tag=Work&tag_match=all").await,
+        ["Write report", "Fix outage"]
+    );
+    assert!(titles(&app, "?tag=work&tag=missing&tag_match=all").await.is_empty());
+    assert!(titles(&app, "?tag=missing").await.is_empty());
+    assert_eq!(titles(&app, "?tag=work&completed=false&search=fix").await, ["Fix outage"]);
+    assert_eq!(titles(&app, "?tag_match=all").await.len(), 4);
+
+    let v1 = app.get("/v1/todo?tag=home").await.json();
+    assert_eq!(v1.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn creates_renames_and_deletes_tags() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Write report", &["work"]).await;
+    let work = app.get("/v2/tags").await.json()[0]["id"].as_i64().unwrap();
+
+    let created = app.post("/v2/tags", json!({ "name": "Home", "color": "#16A34A" })).await;
+    created.assert_status(StatusCode::CREATED);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/tags", &created);
+    assert_json_include(&created.json(), &json!({ "name": "Home", "color": "#16a34a" }));
+
+    let renamed = app.put(&format!("/v2/tags/{}", work), json!({ "name": "job" })).await;
+    renamed.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::PUT, "/tags/{tag_id}", &renamed);
+    assert_json_include(&renamed.json(), &json!({ "id": work, "name": "job", "color": "#6b7280" }));
+    let todo = app.get(&format!("/v2/todo/{}", id)).await.json();
+    assert_eq!(tag_names(&todo), ["job"]);
+
+    // Only the case of its own name
+    app.put(&format!("/v2/tags/{}", work), json!({ "name": "Job" }))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let deleted = app.delete(&format!("/v2/tags/{}", work)).await;
+    deleted.assert_status(StatusCode::NO_CONTENT);
+    app.assert_documented_in(ApiVersion::V2, Method::DELETE, "/tags/{tag_id}", &deleted);
+    let todo = app.get(&format!("/v2/todo/{}", id)).await.json();
+    assert!(tag_names(&todo).is_empty());
+    app.delete(&format!("/v2/tags/{}", work))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    app.put(&format!("/v2/tags/{}", work), json!({ "name": "job" }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn tag_names_are_unique_and_checked() {
+    let app = TestApp::new();
+    create_tag(&app, "work").await;
+    let home = create_tag(&app, "home").await;
+    let cases = [
+        (Method::POST, "/v2/tags".to_string(), json!({ "name": "WORK" }), "name", "taken"),
+        (Method::PUT, format!("/v2/tags/{}", home), json!({ "name": "Work" }), "name", "taken"),
+        (Method::POST, "/v2/tags".to_string(), json!({ "name": "" }), "name", "length"),
+        (Method::POST, "/v2/tags".to_string(), json!({ "name": "a/b" }), "name", "tag_name"),
+        (Method::POST, "/v2/tags".to_string(), json!({ "name": "ok", "color": "blue" }), "color", "color"),
+    ];
+
+    for (method, path, body, field, code) in cases {
+        let response = app.request(method.clone(), &path, Some(body)).await;
+
+        response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        let template = if method == Method::POST { "/tags" } else { "/tags/{tag_id}" };
+        app.assert_documented_in(ApiVersion::V2, method, template, &response);
+        assert_json_include(&response.json(), &json!({ "errors": [{ "field": field, "code": code }] }));
+    }
+}
+
+#[tokio::test]
+async fn names_beyond_ascii_are_compared_ignoring_case_too() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Buy fruit", &["Äpfel"]).await;
+
+    let taken = app.post("/v2/tags", json!({ "name": "äpfel" })).await;
+    taken.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_json_include(&taken.json(), &json!({ "errors": [{ "field": "name", "code": "taken" }] }));
+
+    app.post(&format!("/v2/todo/{}/tags", id), json!({ "tags": ["ÄPFEL"] }))
+        .await
+        .assert_status(StatusCode::OK);
+    assert_eq!(app.get("/v2/tags").await.json().as_array().unwrap().len(), 1);
+    // äpfel, percent-encoded
+    assert_eq!(titles(&app, "?tag=%C3%A4pfel").await, ["Buy fruit"]);
+    assert_eq!(titles(&app, "?q=tag:%C3%A4pfel").await, ["Buy fruit"]);
+    app.delete(&format!("/v2/todo/{}/tags/%C3%A4pfel", id))
+        .await
+        .assert_status(StatusCode::OK);
+}
+
+#[tokio::test]
+async fn merging_moves_the_todos_over() {
+    let app = TestApp::new();
+    let report = create_todo(&app, "Write report", &["work"]).await;
+    let outage = create_todo(&app, "Fix outage", &["job", "work"]).await;
+    let invoice = create_todo(&app, "Send invoice", &["job"]).await;
+    let tags = app.get("/v2/tags").await.json();
+    let job = tags[0]["id"].as_i64().unwrap();
+    let work = tags[1]["id"].as_i64().unwrap();
+
+    let merged = app.post(&format!("/v2/tags/{}/merge", job), json!({ "into": work })).await;
+
+    merged.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/tags/{tag_id}/merge", &merged);
+    assert_json_include(&merged.json(), &json!({ "id": work, "name": "work" }));
+    for id in [report, outage, invoice] {
+        let todo = app.get(&format!("/v2/todo/{}", id)).await.json();
+        assert_eq!(tag_names(&todo), ["work"]);
+    }
+    assert_eq!(app.get("/v2/tags").await.json().as_array().unwrap().len(), 1);
+    app.post(&format!("/v2/tags/{}/merge", job), json!({ "into": work }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    for (into, code) in [(work, "self"), (999, "not_found")] {
+        let response = app.post(&format!("/v2/tags/{}/merge", work), json!({ "into": into })).await;
+
+        response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        app.assert_documented_in(ApiVersion::V2, Method::POST, "/tags/{tag_id}/merge", &response);
+        assert_json_include(&response.json(), &json!({ "errors": [{ "field": "into", "code": code }] }));
+    }
+}
+
+#[tokio::test]
+async fn tag_changes_are_pulled_by_sync() {
+    let app = TestApp::new();
+    let report = create_todo(&app, "Write report", &["work"]).await;
+    create_todo(&app, "Call mom", &[]).await;
+    let token = app.get("/v2/sync").await.json()["token"].as_str().unwrap().to_string();
+
+    let work = app.get("/v2/tags").await.json()[0]["id"].as_i64().unwrap();
+    app.put(&format!("/v2/tags/{}", work), json!({ "name": "job" }))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let pulled = app.get(&format!("/v2/sync?since={}", token)).await.json();
+    let changes = pulled["changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0]["id"], report);
+    assert_eq!(tag_names(&changes[0]), ["job"]);
+}
+
+#[tokio::test]
+async fn deleting_a_todo_takes_its_tags_off() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Write report", &["work"]).await;
+
+    app.delete(&format!("/v2/todo/{}", id))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let connection = app.app_state.db.lock().unwrap();
+    let mut statement = connection.prepare("SELECT COUNT(*) FROM todo_tags").unwrap();
+    statement.next().unwrap();
+    assert_eq!(statement.read::<i64, _>(0).unwrap(), 0);
+}
@@ -0,0 +1,278 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::{config::api_version::ApiVersion, service::time_service::TimerError};
+use common::{TestApp, TestResponse, assert_json_include, create_todo};
+use serde_json::{Value, json};
+
+async fn as_user(app: &TestApp, method: Method, path: &str, user: &str, body: Option<Value>) -> TestResponse {
+    app.request_with_headers(method, &format!("/v2/todo{}", path), body, &[("x-user", user)])
+        .await
+}
+
+async fn track(app: &TestApp, id: i64, user: &str, started_at: &str, ended_at: &str) -> Value {
+    let response = as_user(
+        app,
+        Method::POST,
+        &format!("/{}/time-entries", id),
+        user,
+        Some(json!({ "started_at": started_at, "ended_at": ended_at })),
+    )
+    .await;
+    response.assert_status(StatusCode::CREATED);
+    response.json()
+}
+
+fn rows(report: &Value) -> Vec<(&str, i64)> {
+    report["rows"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| (row["label"].as_str().unwrap(), row["seconds"].as_i64().unwrap()))
+        .collect()
+}
+
+#[tokio::test]
+async fn each_user_runs_at_most_one_timer() {
+    let app = TestApp::new();
+    let write = create_todo(&app, "Write docs", &[]).await;
+    let review = create_todo(&app, "Review PR", &[]).await;
+
+    let started = as_user(&app, Method::POST, &format!("/{}/timer/start", write), "Alice", None).await;
+    started.assert_status(StatusCode::CREATED);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/timer/start", &started);
+    assert_json_include(
+        &started.json(),
+        &json!({ "todo_id": write, "user": "alice", "ended_at": null, "note": null }),
+    );
+
+    let second = as_user(&app, Method::POST, &format!("/{}/timer/start", review), "alice", None).await;
+    second.assert_status(StatusCode::CONFLICT);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/timer/start", &second);
+    assert_eq!(
+        second.text(),
+        format!("alice already has a timer running on todo {}; stop it first", write)
+    );
+
+    // Other users keep their own timer
+    as_user(&app, Method::POST, &format!("/{}/timer/start", review), "bob", None)
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let wrong_todo = as_user(&app, Method::POST, &format!("/{}/timer/stop", review), "alice", None).await;
+    wrong_todo.assert_status(StatusCode::NOT_FOUND);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/timer/stop", &wrong_todo);
+
+    let stopped = as_user(&app, Method::POST, &format!("/{}/timer/stop", write), "alice", None).await;
+    stopped.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/timer/stop", &stopped);
+    assert_eq!(stopped.json()["id"], started.json()["id"]);
+    assert!(stopped.json()["ended_at"].is_string());
+
+    as_user(&app, Method::POST, &format!("/{}/timer/start", review), "alice", None)
+        .await
+        .assert_status(StatusCode::CREATED);
+    as_user(&app, Method::POST, "/99/timer/start", "carol", None)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    app.post(&format!("/v2/todo/{}/timer/start", write), json!({}))
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn time_can_be_entered_by_hand() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Call client", &[]).await;
+
+    let entry = as_user(
+        &app,
+        Method::POST,
+        &format!("/{}/time-entries", id),
+        "alice",
+        Some(json!({ "started_at": "2026-10-01T09:00:00Z", "ended_at": "2026-10-01T10:30:00Z", "note": "Kick-off" })),
+    )
+    .await;
+    entry.assert_status(StatusCode::CREATED);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/time-entries", &entry);
+    assert_json_include(
+        &entry.json(),
+        &json!({
+            "todo_id": id,
+            "user": "alice",
+            "started_at": "2026-10-01T09:00:00Z",
+            "ended_at": "2026-10-01T10:30:00Z",
+            "duration_secs": 5400,
+            "note": "Kick-off"
+        }),
+    );
+    track(&app, id, "bob", "2026-10-02T09:00:00Z", "2026-10-02T09:15:00Z").await;
+
+    let list = app.get(&format!("/v2/todo/{}/time-entries", id)).await;
+    list.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo/{id}/time-entries", &list);
+    assert_json_include(&list.json(), &json!([{ "user": "bob" }, { "user": "alice" }]));
+
+    let reversed = as_user(
+        &app,
+        Method::POST,
+        &format!("/{}/time-entries", id),
+        "alice",
+        Some(json!({ "started_at": "2026-10-01T10:00:00Z", "ended_at": "2026-10-01T10:00:00Z" })),
+    )
+    .await;
+    reversed.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/time-entries", &reversed);
+    assert_json_include(&reversed.json(), &json!({ "errors": [{ "field": "ended_at", "code": "range" }] }));
+    let future = as_user(
+        &app,
+        Method::POST,
+        &format!("/{}/time-entries", id),
+        "alice",
+        Some(json!({ "started_at": "2026-10-01T10:00:00Z", "ended_at": "2999-01-01T00:00:00Z" })),
+    )
+    .await;
+    assert_json_include(&future.json(), &json!({ "errors": [{ "field": "ended_at", "code": "future" }] }));
+
+    let entry_id = entry.json()["id"].as_i64().unwrap();
+    let path = format!("/{}/time-entries/{}", id, entry_id);
+    let forbidden = as_user(&app, Method::DELETE, &path, "bob", None).await;
+    forbidden.assert_status(StatusCode::FORBIDDEN);
+    app.assert_documented_in(ApiVersion::V2, Method::DELETE, "/todo/{id}/time-entries/{entry_id}", &forbidden);
+    let deleted = as_user(&app, Method::DELETE, &path, "alice", None).await;
+    deleted.assert_status(StatusCode::NO_CONTENT);
+    app.assert_documented_in(ApiVersion::V2, Method::DELETE, "/todo/{id}/time-entries/{entry_id}", &deleted);
+    as_user(&app, Method::DELETE, &path, "alice", None)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(app.get(&format!("/v2/todo/{}/time-entries", id)).await.json().as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn reports_add_up_time_per_todo_tag_and_day() {
+    let app = TestApp::new();
+    let design = create_todo(&app, "Design", &["client-a", "ux"]).await;
+    let build = create_todo(&app, "Build", &["client-a"]).await;
+    let chores = create_todo(&app, "Chores", &[]).await;
+    track(&app, design, "alice", "2026-10-01T09:00:00Z", "2026-10-01T10:00:00Z").await;
+    // Runs past midnight, and starts before the range
+    track(&app, build, "alice", "2026-09-30T23:00:00Z", "2026-10-01T01:00:00Z").await;
+    track(&app, build, "bob", "2026-10-02T22:00:00Z", "2026-10-03T02:00:00Z").await;
+    track(&app, chores, "bob", "2026-10-02T08:00:00Z", "2026-10-02T08:30:00Z").await;
+
+    let by_todo = app.get("/v2/todo/time-report?from=2026-10-01&to=2026-10-02").await;
+    by_todo.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo/time-report", &by_todo);
+    assert_json_include(
+        &by_todo.json(),
+        &json!({ "from": "2026-10-01", "to": "2026-10-02", "group_by": "todo", "total_secs": 16200 }),
+    );
+    assert_eq!(rows(&by_todo.json()), [("Build", 10800), ("Design", 3600), ("Chores", 1800)]);
+    assert_eq!(by_todo.json()["rows"][0]["key"], build.to_string());
+
+    let by_tag = app
+        .get("/v2/todo/time-report?from=2026-10-01&to=2026-10-02&group_by=tag")
+        .await
+        .json();
+    assert_eq!(by_tag["total_secs"], 16200);
+    // Time on items with several tags counts for each; the total counts it once
+    assert_eq!(rows(&by_tag), [("client-a", 14400), ("ux", 3600), ("No tag", 1800)]);
+    assert_eq!(by_tag["rows"][2]["key"], "");
+
+    let by_day = app
+        .get("/v2/todo/time-report?from=2026-10-01&to=2026-10-03&group_by=day")
+        .await
+        .json();
+    assert_eq!(
+        rows(&by_day),
+        [("2026-10-01", 7200), ("2026-10-02", 9000), ("2026-10-03", 7200)]
+    );
+
+    let bob = app
+        .get("/v2/todo/time-report?from=2026-10-01&to=2026-10-03&user=Bob")
+        .await
+        .json();
+    assert_eq!(rows(&bob), [("Build", 14400), ("Chores", 1800)]);
+}
+
+#[tokio::test]
+async fn reports_export_as_csv() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Invoice, October", &[]).await;
+    track(&app, id, "alice", "2026-10-01T09:00:00Z", "2026-10-01T10:30:00Z").await;
+
+    let csv = app
+        .get("/v2/todo/time-report?from=2026-10-01&to=2026-10-31&format=csv")
+        .await;
+
+    csv.assert_status(StatusCode::OK);
+    assert_eq!(csv.header("content-type"), Some("text/csv; charset=utf-8"));
+    assert_eq!(
+        csv.header("content-disposition"),
+        Some("attachment; filename=\"time-report-2026-10-01-2026-10-31.csv\"")
+    );
+    assert_eq!(
+        csv.text(),
+        format!("key,label,seconds,hours\n{},\"Invoice, October\",5400,1.50\n", id)
+    );
+
+    let reversed = app.get("/v2/todo/time-report?from=2026-10-31&to=2026-10-01").await;
+    reversed.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo/time-report", &reversed);
+    app.get("/v2/todo/time-report?from=2025-01-01&to=2026-10-01")
+        .await
+        .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn deleting_a_todo_deletes_its_time() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Scrapped", &[]).await;
+    track(&app, id, "alice", "2026-10-01T09:00:00Z", "2026-10-01T10:00:00Z").await;
+    as_user(&app, Method::POST, &format!("/{}/timer/start", id), "alice", None)
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    app.delete(&format!("/v2/todo/{}", id))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let report = app.get("/v2/todo/time-report?from=2026-10-01&to=2026-10-01").await.json();
+    assert_eq!(report["total_secs"], 0);
+    // The running timer went with it, so a new one can start
+    let other = create_todo(&app, "Next", &[]).await;
+    as_user(&app, Method::POST, &format!("/{}/timer/start", other), "alice", None)
+        .await
+        .assert_status(StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn time_is_only_tracked_on_todos_that_exist() {
+    let app = TestApp::new();
+    let gone = create_todo(&app, "Gone", &[]).await;
+    app.delete(&format!("/v2/todo/{}", gone)).await;
+    let (db, service) = (&app.app_state.db, &app.app_state.time_service);
+
+    assert!(matches!(service.start(db, gone, "alice").await, Err(TimerError::NotFound(_))));
+    let entry = serde_json::from_value(json!({
+        "started_at": "2026-10-01T09:00:00Z",
+        "ended_at": "2026-10-01T10:00:00Z"
+    }))
+    .unwrap();
+    assert_eq!(service.create(db, gone, "alice", entry).await, Ok(None));
+    assert_eq!(service.stop(db, gone, "alice").await, Ok(None));
+    assert!(service.list(db, gone).await.unwrap().is_empty());
+
+    // A timer left running on a deleted todo doesn't block the next one
+    db.lock()
+        .unwrap()
+        .execute(format!(
+            "INSERT INTO time_entries (todo_id, user, started_at, created_at) VALUES ({}, 'alice', 0, 0)",
+            gone
+        ))
+        .unwrap();
+    let next = create_todo(&app, "Next", &[]).await;
+    let started = as_user(&app, Method::POST, &format!("/{}/timer/start", next), "alice", None).await;
+    started.assert_status(StatusCode::CREATED);
+    assert_eq!(service.running(db, "alice").await.unwrap().todo_id, next);
+}
@@ -0,0 +1,549 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::{
+    config::{
+        api_version::ApiVersion,
+        settings::{CorsConfig, DatabaseConfig, IdempotencyConfig, RateLimitConfig, TlsConfig},
+    },
+    middleware::rate_limit::{Decision, RateLimiter},
+};
+use common::{TestApp, assert_json_include};
+use serde_json::{Value, json};
+
+async fn create(app: &TestApp, title: &str, completed: bool) -> i64 {
+    let response = app
+        .post("/todo", json!({ "title": title, "completed": completed }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    response.json()["id"].as_i64().unwrap()
+}
+
+#[tokio::test]
+async fn create_todo_returns_created_item() {
+    let app = TestApp::new();
+
+    let response = app
+        .post("/todo", json!({ "title": "Buy groceries", "completed": false }))
+        .await;
+
+    response.assert_status(StatusCode::CREATED);
+    assert_json_include(&response.json(), &json!({ "title": "Buy groceries", "completed": false }));
+    assert!(response.json()["id"].is_i64());
+    app.assert_documented(Method::POST, "/todo", &response);
+}
+
+#[tokio::test]
+async fn create_todo_rejects_invalid_fields() {
+    let app = TestApp::new();
+
+    let response = app
+        .post("/todo", json!({ "id": 7, "title": " padded ", "completed": false }))
+        .await;
+
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_json_include(
+        &response.json(),
+        &json!({
+            "errors": [
+                { "field": "id", "code": "read_only" },
+                { "field": "title", "code": "trimmed" }
+            ]
+        }),
+    );
+    app.assert_documented(Method::POST, "/todo", &response);
+}
+
+#[tokio::test]
+async fn create_todo_rejects_out_of_range_and_control_characters() {
+    let app = TestApp::new();
+
+    let empty = app.post("/todo", json!({ "title": "", "completed": false })).await;
+    empty.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_json_include(&empty.json(), &json!({ "errors": [{ "field": "title", "code": "length" }] }));
+
+    let too_long = app
+        .post("/todo", json!({ "title": "x".repeat(201), "completed": false }))
+        .await;
+    too_long.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+
+    let control = app
+        .post("/todo", json!({ "title": "bell\u{7}", "completed": false }))
+        .await;
+    control.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_json_include(
+        &control.json(),
+        &json!({ "errors": [{ "field": "title", "code": "control_characters" }] }),
+    );
+}
+
+#[tokio::test]
+async fn create_todo_rejects_malformed_json() {
+    let app = TestApp::new();
+
+    let response = app
+        .request(Method::POST, "/todo", Some(json!({ "title": 42 })))
+        .await;
+
+    assert!(response.status.is_client_error(), "got {}", response.status);
+}
+
+#[tokio::test]
+async fn create_todo_replays_idempotent_retries() {
+    let app = TestApp::new();
+    let body = json!({ "title": "Pay rent", "completed": false });
+    let key = [("Idempotency-Key", "retry-1")];
+
+    let first = app
+        .request_with_headers(Method::POST, "/todo", Some(body.clone()), &key)
+        .await;
+    let retry = app
+        .request_with_headers(Method::POST, "/todo", Some(body), &key)
+        .await;
+
+    first.assert_status(StatusCode::CREATED);
+    retry.assert_status(StatusCode::CREATED);
+    assert_eq!(first.json(), retry.json());
+    assert_eq!(retry.header("idempotent-replayed"), Some("true"));
+    assert_eq!(app.get("/todo").await.json().as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn create_todo_rejects_reused_idempotency_key_with_other_payload() {
+    let app = TestApp::new();
+    let key = [("Idempotency-Key", "retry-2")];
+
+    app.request_with_headers(
+        Method::POST,
+        "/todo",
+        Some(json!({ "title": "Pay rent", "completed": false })),
+        &key,
+    )
+    .await
+    .assert_status(StatusCode::CREATED);
+
+    let response = app
+        .request_with_headers(
+            Method::POST,
+            "/todo",
+            Some(json!({ "title": "Pay bills", "completed": false })),
+            &key,
+        )
+        .await;
+
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn idempotency_keys_are_scoped_to_the_client() {
+    let app = TestApp::with_settings(|settings| {
+        settings.limits.rate_limit.api_keys = vec!["team-a".to_string(), "team-b".to_string()];
+    });
+    let body = json!({ "title": "Pay rent", "completed": false });
+    let post_as = async |team: &str| {
+        app.request_with_headers(
+            Method::POST,
+            "/todo",
+            Some(body.clone()),
+            &[("Idempotency-Key", "shared-key"), ("x-api-key", team)],
+        )
+        .await
+    };
+
+    let first = post_as("team-a").await;
+    let other_client = post_as("team-b").await;
+
+    first.assert_status(StatusCode::CREATED);
+    other_client.assert_status(StatusCode::CREATED);
+    assert_eq!(other_client.header("idempotent-replayed"), None);
+    assert_ne!(first.json()["id"], other_client.json()["id"]);
+    assert_eq!(post_as("team-a").await.header("idempotent-replayed"), Some("true"));
+}
+
+#[tokio::test]
+async fn get_all_lists_every_item() {
+    let app = TestApp::new();
+    create(&app, "First", false).await;
+    create(&app, "Second", true).await;
+
+    let response = app.get("/todo").await;
+
+    response.assert_status(StatusCode::OK);
+    assert_json_include(
+        &response.json(),
+        &json!([
+            { "title": "First", "completed": false },
+            { "title": "Second", "completed": true }
+        ]),
+    );
+    app.assert_documented(Method::GET, "/todo", &response);
+}
+
+#[tokio::test]
+async fn get_all_returns_empty_list() {
+    let app = TestApp::new();
+
+    let response = app.get("/todo").await;
+
+    response.assert_status(StatusCode::OK);
+    assert_eq!(response.json(), json!([]));
+    app.assert_documented(Method::GET, "/todo", &response);
+}
+
+#[tokio::test]
+async fn get_all_filters_by_completion() {
+    let app = TestApp::new();
+    create(&app, "Open", false).await;
+    create(&app, "Closed", true).await;
+
+    let response = app.get("/todo?completed=false").await;
+
+    response.assert_status(StatusCode::OK);
+    assert_json_include(&response.json(), &json!([{ "title": "Open", "completed": false }]));
+    app.assert_documented(Method::GET, "/todo", &response);
+}
+
+#[tokio::test]
+async fn get_all_reports_query_failures_without_poisoning_the_database() {
+    let app = TestApp::new();
+    create(&app, "First", false).await;
+    // A view whose rows fail to read once the query runs, after it was prepared
+    app.app_state
+        .db
+        .lock()
+        .unwrap()
+        .execute(
+            "ALTER TABLE todos RENAME TO stored_todos;
+            CREATE VIEW todos AS SELECT * FROM stored_todos
+                WHERE json_extract('not json', '$') IS NULL;",
+        )
+        .unwrap();
+
+    let response = app.get("/todo").await;
+
+    response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response.text().contains("malformed JSON"));
+    assert!(!app.app_state.db.is_poisoned());
+    app.app_state
+        .db
+        .lock()
+        .unwrap()
+        .execute("DROP VIEW todos; ALTER TABLE stored_todos RENAME TO todos;")
+        .unwrap();
+    assert_json_include(&app.get("/todo").await.json(), &json!([{ "title": "First" }]));
+}
+
+#[tokio::test]
+async fn get_by_id_returns_item() {
+    let app = TestApp::new();
+    let id = create(&app, "Read a book", false).await;
+
+    let response = app.get(&format!("/todo/{}", id)).await;
+
+    response.assert_status(StatusCode::OK);
+    assert_json_include(&response.json(), &json!({ "id": id, "title": "Read a book" }));
+    app.assert_documented(Method::GET, "/todo/{id}", &response);
+}
+
+#[tokio::test]
+async fn get_by_id_returns_not_found() {
+    let app = TestApp::new();
+
+    let response = app.get("/todo/999").await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+    app.assert_documented(Method::GET, "/todo/{id}", &response);
+}
+
+#[tokio::test]
+async fn get_by_id_rejects_non_numeric_id() {
+    let app = TestApp::new();
+
+    app.get("/todo/abc").await.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn update_todo_changes_item() {
+    let app = TestApp::new();
+    let id = create(&app, "Draft", false).await;
+
+    let response = app
+        .put(&format!("/todo/{}", id), json!({ "title": "Final", "completed": true }))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+    assert_json_include(&response.json(), &json!({ "id": id, "title": "Final", "completed": true }));
+    app.assert_documented(Method::PUT, "/todo/{id}", &response);
+
+    let stored = app.get(&format!("/todo/{}", id)).await.json();
+    assert_json_include(&stored, &json!({ "title": "Final", "completed": true }));
+}
+
+#[tokio::test]
+async fn update_todo_of_a_missing_item_is_not_found() {
+    let app = TestApp::new();
+
+    let response = app
+        .put("/todo/999", json!({ "title": "Final", "completed": true }))
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+    app.assert_documented(Method::PUT, "/todo/{id}", &response);
+    assert!(app.get("/todo").await.json().as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn update_todo_without_changes_records_nothing() {
+    let app = TestApp::new();
+    let id = create(&app, "Draft", false).await;
+    let token = app.get("/v2/sync").await.json()["token"].clone();
+    let thread = app.get(&format!("/v2/todo/{}/comments", id)).await.json();
+
+    app.put(&format!("/todo/{}", id), json!({ "title": "Draft", "completed": false }))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let changes = app.get(&format!("/v2/sync?since={}", token.as_str().unwrap())).await.json();
+    assert_eq!(changes["changes"], json!([]));
+    assert_eq!(app.get(&format!("/v2/todo/{}/comments", id)).await.json(), thread);
+}
+
+#[tokio::test]
+async fn update_todo_rejects_invalid_title() {
+    let app = TestApp::new();
+    let id = create(&app, "Draft", false).await;
+
+    let response = app
+        .put(&format!("/todo/{}", id), json!({ "title": "", "completed": true }))
+        .await;
+
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    app.assert_documented(Method::PUT, "/todo/{id}", &response);
+}
+
+#[tokio::test]
+async fn delete_todo_removes_item() {
+    let app = TestApp::new();
+    let id = create(&app, "Temporary", false).await;
+
+    let response = app.delete(&format!("/todo/{}", id)).await;
+
+    response.assert_status(StatusCode::NO_CONTENT);
+    app.assert_documented(Method::DELETE, "/todo/{id}", &response);
+    app.get(&format!("/todo/{}", id)).await.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn rejects_bodies_over_the_configured_limit() {
+    let app = TestApp::with_settings(|settings| settings.limits.body_max_bytes = 64);
+
+    let response = app
+        .post("/todo", json!({ "title": "x".repeat(100), "completed": false }))
+        .await;
+
+    response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn rate_limits_clients_over_their_budget() {
+    let app = TestApp::with_settings(|settings| {
+        settings.limits.rate_limit.enabled = true;
+        settings.limits.rate_limit.burst = 1;
+    });
+
+    let allowed = app.get("/todo").await;
+    let limited = app.get("/todo").await;
+
+    allowed.assert_status(StatusCode::OK);
+    assert_eq!(allowed.header("ratelimit-remaining"), Some("0"));
+    limited.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    assert!(limited.header("retry-after").is_some());
+}
+
+#[tokio::test]
+async fn only_configured_api_keys_get_a_bucket_of_their_own() {
+    let app = TestApp::with_settings(|settings| {
+        settings.limits.rate_limit.enabled = true;
+        settings.limits.rate_limit.burst = 1;
+        settings.limits.rate_limit.api_keys = vec!["team-key".to_string()];
+    });
+    let with_key = async |key: &str| app.request_with_headers(Method::GET, "/todo", None, &[("x-api-key", key)]).await;
+
+    with_key("made-up-1").await.assert_status(StatusCode::OK);
+    // A fresh unknown key still shares the IP's bucket
+    with_key("made-up-2").await.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    with_key("team-key").await.assert_status(StatusCode::OK);
+    with_key("team-key").await.assert_status(StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[test]
+fn rate_limits_without_refill_are_refused() {
+    let config = |burst: u64, refill_per_sec: f64| {
+        serde_json::from_value::<RateLimitConfig>(json!({
+            "enabled": true,
+            "burst": burst,
+            "refill_per_sec": refill_per_sec,
+            "identity_header": "x-api-key"
+        }))
+    };
+
+    assert!(config(60, 1.0).is_ok());
+    assert!(config(60, 0.0).is_err());
+    assert!(config(60, -1.0).is_err());
+    assert!(config(0, 1.0).is_err());
+}
+
+#[test]
+fn any_origin_is_refused_with_credentials() {
+    let config = |origin: &str, allow_credentials: bool| {
+        serde_json::from_value::<CorsConfig>(json!({
+            "enabled": true,
+            "allowed_origins": [origin],
+            "allowed_methods": ["GET"],
+            "allowed_headers": ["content-type"],
+            "allow_credentials": allow_credentials
+        }))
+    };
+
+    assert!(config("*", false).is_ok());
+    assert!(config("http://localhost:3000", true).is_ok());
+    assert!(config("*", true).is_err());
+}
+
+#[test]
+fn cors_entries_are_checked_when_loading() {
+    let config = |origins: Value, methods: Value, headers: Value, allow_credentials: bool| {
+        serde_json::from_value::<CorsConfig>(json!({
+            "enabled": true,
+            "allowed_origins": origins,
+            "allowed_methods": methods,
+            "allowed_headers": headers,
+            "allow_credentials": allow_credentials
+        }))
+        .map_err(|e| e.to_string())
+    };
+    let origin = json!(["https://app.example.com"]);
+
+    let loaded = config(origin.clone(), json!(["GET", "POST"]), json!(["content-type"]), true).unwrap();
+    assert_eq!(loaded.allowed_methods, [Method::GET, Method::POST]);
+    assert_eq!(loaded.allowed_origins.unwrap(), ["https://app.example.com"]);
+
+    // Wildcard headers and methods can't be combined with credentials either
+    let headers = config(origin.clone(), json!(["GET"]), json!(["*"]), true).unwrap_err();
+    assert!(headers.contains("needs allowed_headers listed"), "{}", headers);
+    let methods = config(origin.clone(), json!(["*"]), json!(["content-type"]), true).unwrap_err();
+    assert!(methods.contains("needs allowed_methods listed"), "{}", methods);
+    assert!(config(origin.clone(), json!(["*"]), json!(["*"]), false).is_ok());
+
+    // Malformed entries are config errors instead of panics when the layer is built
+    for (origins, methods, headers) in [
+        (json!(["localhost:3000"]), json!(["GET"]), json!(["content-type"])),
+        (json!(["https://app.example.com/"]), json!(["GET"]), json!(["content-type"])),
+        (origin.clone(), json!(["GE T"]), json!(["content-type"])),
+        (origin.clone(), json!(["GET"]), json!(["content type"])),
+    ] {
+        let error = config(origins, methods, headers, false).unwrap_err();
+        assert!(error.contains("has an invalid entry"), "{}", error);
+    }
+}
+
+fn database_config(checkpoint_interval_secs: u64, rebalance_interval_secs: u64) -> Result<DatabaseConfig, serde_json::Error> {
+    serde_json::from_value(json!({
+        "path": "todo.db",
+        "checkpoint_interval_secs": checkpoint_interval_secs,
+        "rebalance_interval_secs": rebalance_interval_secs
+    }))
+}
+
+#[test]
+fn checkpoints_need_an_interval() {
+    assert!(database_config(300, 3600).is_ok());
+    let error = database_config(0, 3600).unwrap_err().to_string();
+    assert!(error.contains("database.checkpoint_interval_secs must be at least 1"), "{}", error);
+}
+
+#[test]
+fn rebalancing_needs_an_interval() {
+    let error = database_config(300, 0).unwrap_err().to_string();
+    assert!(error.contains("database.rebalance_interval_secs must be at least 1"), "{}", error);
+}
+
+#[test]
+fn idempotency_purges_need_an_interval() {
+    let config = |purge_interval_secs: u64| {
+        serde_json::from_value::<IdempotencyConfig>(json!({
+            "ttl_secs": 86400,
+            "purge_interval_secs": purge_interval_secs
+        }))
+    };
+
+    assert!(config(3600).is_ok());
+    let error = config(0).unwrap_err().to_string();
+    assert!(error.contains("idempotency.purge_interval_secs must be at least 1"), "{}", error);
+}
+
+#[test]
+fn certificate_reloads_need_an_interval() {
+    let config = |enabled: bool, reload_interval_secs: u64| {
+        serde_json::from_value::<TlsConfig>(json!({
+            "enabled": enabled,
+            "cert_path": "certs/cert.pem",
+            "key_path": "certs/key.pem",
+            "reload_interval_secs": reload_interval_secs,
+            "redirect_http_port": null
+        }))
+    };
+
+    assert!(config(true, 60).is_ok());
+    assert!(config(false, 0).is_ok());
+    let error = config(true, 0).unwrap_err().to_string();
+    assert!(error.contains("server.tls.reload_interval_secs must be at least 1"), "{}", error);
+}
+
+#[tokio::test]
+async fn openapi_document_lists_every_todo_route() {
+    let app = TestApp::new();
+
+    let response = app.get(&app.settings.openapi.json_path_for(ApiVersion::V1)).await;
+
+    response.assert_status(StatusCode::OK);
+    let paths = &response.json()["paths"];
+    for (path, method) in [
+        ("/todo", "post"),
+        ("/todo", "get"),
+        ("/todo/{id}", "get"),
+        ("/todo/{id}", "put"),
+        ("/todo/{id}", "delete"),
+    ] {
+        assert!(paths[path][method].is_object(), "{} {} is not documented", method, path);
+    }
+}
+
+#[test]
+fn rate_limiter_tracks_a_bounded_number_of_clients() {
+    let config: RateLimitConfig = serde_json::from_value(json!({
+        "enabled": true,
+        "burst": 5,
+        "refill_per_sec": 0.001,
+        "identity_header": "x-api-key"
+    }))
+    .unwrap();
+    let limiter = RateLimiter::with_max_clients(config, 100);
+
+    // Every bucket is left partially drained, as by a client spraying source addresses
+    for i in 0..1_000 {
+        limiter.check(&format!("ip:10.0.{}.{}", i / 256, i % 256));
+        assert!(limiter.tracked_clients() <= 100);
+    }
+    assert_eq!(limiter.tracked_clients(), 100);
+
+    // The clients seen most recently keep their buckets
+    let Decision::Allowed { remaining, .. } = limiter.check("ip:10.0.3.231") else {
+        panic!("expected the request to be allowed");
+    };
+    assert_eq!(remaining, 3);
+    let Decision::Allowed { remaining, .. } = limiter.check("ip:10.0.0.0") else {
+        panic!("expected the request to be allowed");
+    };
+    assert_eq!(remaining, 4, "the oldest client's bucket was dropped and starts full");
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use common::{TestApp, TestResponse, create_todo, create_todo_with};
+use serde_json::json;
+
+/// The web UI lives at `/`, outside of `app.prefix`
+async fn page(app: &TestApp, path: &str, partial: bool) -> TestResponse {
+    let mut request = Request::builder().method(Method::GET).uri(path);
+    if partial {
+        request = request.header("hx-request", "true");
+    }
+    app.send(request.body(Body::empty()).unwrap()).await
+}
+
+async fn submit(app: &TestApp, path: &str, form: &str, partial: bool) -> TestResponse {
+    let mut request = Request::builder()
+        .method(Method::POST)
+        .uri(path)
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+    if partial {
+        request = request.header("hx-request", "true");
+    }
+    app.send(request.body(Body::from(form.to_string())).unwrap()).await
+}
+
+async fn titles(app: &TestApp) -> Vec<String> {
+    app.get("/v2/todo").await.json()["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|todo| todo["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn renders_the_list_with_tabs_and_search() {
+    let app = TestApp::new();
+    create_todo(&app, "Buy milk", &[]).await;
+    create_todo_with(&app, json!({ "title": "Call <mum>", "completed": true }), &[]).await;
+
+    let home = page(&app, "/", false).await;
+    home.assert_status(StatusCode::OK);
+    assert_eq!(home.header("content-type"), Some("text/html; charset=utf-8"));
+    let html = home.text();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("Buy milk"));
+    assert!(html.contains("Call &#60;mum&#62;"));
+
+    let done = page(&app, "/?show=done", false).await.text();
+    assert!(done.contains("Call &#60;mum&#62;") && !done.contains("Buy milk"));
+
+    let searched = page(&app, "/?search=MILK", true).await;
+    searched.assert_status(StatusCode::OK);
+    assert!(searched.text().starts_with("<section id=\"todo-app\">"));
+    assert!(searched.text().contains("Buy milk") && !searched.text().contains("mum"));
+    assert_eq!(searched.header("hx-push-url"), Some("/?search=MILK"));
+
+    let script = page(&app, "/assets/ui.js", false).await;
+    script.assert_status(StatusCode::OK);
+    assert_eq!(script.header("content-type"), Some("text/javascript; charset=utf-8"));
+}
+
+#[tokio::test]
+async fn forms_work_without_javascript() {
+    let app = TestApp::new();
+
+    let created = submit(&app, "/todos", "title=++Water+plants+&due_at=2026-11-01T17%3A00", false).await;
+    created.assert_status(StatusCode::SEE_OTHER);
+    assert_eq!(created.header("location"), Some("/"));
+    let todo = app.get("/v2/todo").await.json()["items"][0].clone();
+    assert_eq!(todo["title"], "Water plants");
+    assert_eq!(todo["due_at"], "2026-11-01T17:00:00Z");
+    let id = todo["id"].as_i64().unwrap();
+
+    let toggled = submit(&app, &format!("/todos/{}/toggle", id), "show=open&search=", false).await;
+    toggled.assert_status(StatusCode::SEE_OTHER);
+    assert_eq!(toggled.header("location"), Some("/?show=open"));
+    assert_eq!(app.get(&format!("/v2/todo/{}", id)).await.json()["completed"], true);
+
+    let editing = page(&app, &format!("/todos/{}/edit", id), false).await;
+    editing.assert_status(StatusCode::OK);
+    assert!(editing.text().contains("value=\"2026-11-01T17:00\""));
+    assert!(editing.text().contains("checked"));
+
+    submit(&app, &format!("/todos/{}", id), "title=Water+the+plants&due_at=", false)
+        .await
+        .assert_status(StatusCode::SEE_OTHER);
+    let updated = app.get(&format!("/v2/todo/{}", id)).await.json();
+    assert_eq!(updated, json!({ "id": id, "title": "Water the plants", "completed": false, "state": "todo", "due_at": null, "tags": [], "is_blocked": false }));
+
+    submit(&app, &format!("/todos/{}/delete", id), "", false)
+        .await
+        .assert_status(StatusCode::SEE_OTHER);
+    assert!(titles(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn partial_requests_get_fragments_to_swap() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Walk the dog", &[]).await;
+
+    let created = submit(&app, "/todos", "title=Feed+the+cat&show=all", true).await;
+    created.assert_status(StatusCode::OK);
+    let html = created.text();
+    assert!(html.starts_with("<section id=\"todo-app\">"));
+    assert!(html.contains("Walk the dog") && html.contains("Feed the cat"));
+
+    let row = submit(&app, &format!("/todos/{}/toggle", id), "show=all", true).await;
+    row.assert_status(StatusCode::OK);
+    assert!(row.text().starts_with(&format!("<li class=\"todo completed\" id=\"todo-{}\">", id)));
+
+    // Reopened items leave the list of done ones
+    let left = submit(&app, &format!("/todos/{}/toggle", id), "show=done", true).await;
+    left.assert_status(StatusCode::OK);
+    assert_eq!(left.text(), "");
+
+    let editor = page(&app, &format!("/todos/{}/edit?show=open", id), true).await;
+    assert!(editor.text().starts_with("<li class=\"todo editing\""));
+    assert!(editor.text().contains(&format!("href=\"/todos/{}?show=open\"", id)));
+    let cancelled = page(&app, &format!("/todos/{}?show=open", id), true).await;
+    assert!(cancelled.text().starts_with("<li class=\"todo\""));
+
+    let deleted = submit(&app, &format!("/todos/{}/delete", id), "", true).await;
+    deleted.assert_status(StatusCode::OK);
+    assert_eq!(deleted.text(), "");
+    assert_eq!(titles(&app).await, ["Feed the cat"]);
+}
+
+#[tokio::test]
+async fn shows_validation_errors_next_to_the_fields() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Keep me", &[]).await;
+
+    let empty = submit(&app, "/todos", "title=+++", false).await;
+    empty.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(empty.text().contains("<!DOCTYPE html>"));
+    assert!(empty.text().contains("class=\"error\""));
+
+    let bad_date = submit(&app, "/todos", "title=Later&due_at=tomorrow", true).await;
+    bad_date.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(bad_date.text().contains("due date must be a date and time"));
+    assert!(bad_date.text().contains("value=\"Later\""));
+
+    let long_title = format!("title={}", "x".repeat(201));
+    let rejected = submit(&app, &format!("/todos/{}", id), &long_title, true).await;
+    rejected.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(rejected.text().starts_with("<li class=\"todo editing\""));
+    assert_eq!(titles(&app).await, ["Keep me"]);
+
+    page(&app, "/todos/999/edit", false)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    submit(&app, "/todos/999", "title=Ghost", false)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(titles(&app).await, ["Keep me"]);
+}
+
+#[tokio::test]
+async fn can_be_turned_off() {
+    let app = TestApp::with_settings(|settings| settings.web.enabled = false);
+
+    page(&app, "/", false)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    app.get("/v2/todo").await.assert_status(StatusCode::OK);
+}
@@ -0,0 +1,285 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use axum_tutorial::{
+    config::{api_version::ApiVersion, settings::WorkflowConfig},
+    service::todo_service::UpdateError,
+};
+use common::{TestApp, assert_json_include, create_todo};
+use serde_json::{Value, json};
+
+async fn transition(app: &TestApp, id: i64, to: &str) -> Value {
+    let response = app
+        .post(&format!("/v2/todo/{}/transition", id), json!({ "to": to }))
+        .await;
+    response.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/transition", &response);
+    response.json()
+}
+
+fn columns(board: &Value) -> Vec<(&str, Vec<&str>)> {
+    board["columns"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|column| {
+            let titles = column["items"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|todo| todo["title"].as_str().unwrap())
+                .collect();
+            (column["state"].as_str().unwrap(), titles)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn transitions_move_items_through_the_workflow() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Ship release", &[]).await;
+    assert_eq!(app.get(&format!("/v2/todo/{}", id)).await.json()["state"], "todo");
+
+    let started = transition(&app, id, "in_progress").await;
+    assert_json_include(&started, &json!({ "state": "in_progress", "completed": false }));
+    transition(&app, id, "in_review").await;
+    let done = transition(&app, id, "done").await;
+    assert_json_include(&done, &json!({ "state": "done", "completed": true }));
+
+    // completed is derived from the state, in both API versions
+    let v1 = app.get(&format!("/v1/todo/{}", id)).await.json();
+    assert_json_include(&v1, &json!({ "completed": true, "state": "done" }));
+
+    // Staying put is allowed and changes nothing
+    assert_eq!(transition(&app, id, "done").await, done);
+
+    let thread = app.get(&format!("/v2/todo/{}/comments", id)).await;
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo/{id}/comments", &thread);
+    assert_json_include(
+        &thread.json(),
+        &json!([
+            { "kind": "transitioned", "from": "todo", "to": "in_progress" },
+            { "kind": "transitioned", "from": "in_progress", "to": "in_review" },
+            { "kind": "completed", "from": null, "to": null }
+        ]),
+    );
+}
+
+#[tokio::test]
+async fn transitions_not_in_the_workflow_are_refused() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Write tests", &[]).await;
+
+    let skipped = app
+        .post(&format!("/v2/todo/{}/transition", id), json!({ "to": "in_review" }))
+        .await;
+    skipped.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/transition", &skipped);
+    assert_json_include(
+        &skipped.json(),
+        &json!({ "errors": [{
+            "field": "to",
+            "code": "transition",
+            "message": "can't move from todo to in_review, only to: in_progress, blocked, done"
+        }] }),
+    );
+
+    let unknown = app
+        .post(&format!("/v2/todo/{}/transition", id), json!({ "to": "archived" }))
+        .await;
+    unknown.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_json_include(&unknown.json(), &json!({ "errors": [{ "field": "to", "code": "unknown_state" }] }));
+
+    let missing = app.post("/v2/todo/99/transition", json!({ "to": "done" })).await;
+    missing.assert_status(StatusCode::NOT_FOUND);
+    app.assert_documented_in(ApiVersion::V2, Method::POST, "/todo/{id}/transition", &missing);
+    assert_eq!(app.get(&format!("/v2/todo/{}", id)).await.json()["state"], "todo");
+}
+
+#[tokio::test]
+async fn transitions_are_checked_against_the_stored_state() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Deploy", &[]).await;
+    // As if a concurrent request moved it after the caller last read it
+    app.app_state
+        .db
+        .lock()
+        .unwrap()
+        .execute(format!("UPDATE todos SET state = 'blocked' WHERE id = {}", id))
+        .unwrap();
+
+    let service = &app.app_state.todo_service;
+    let refused = service.transition(&app.app_state.db, id, "done").await;
+
+    let Err(UpdateError::Transition(errors)) = refused else {
+        panic!("expected the move from blocked to be refused, got {:?}", refused);
+    };
+    assert_eq!(
+        errors.field_errors()["to"][0].message.as_deref(),
+        Some("can't move from blocked to done, only to: todo, in_progress")
+    );
+    assert_eq!(app.get(&format!("/v2/todo/{}", id)).await.json()["state"], "blocked");
+    assert!(matches!(
+        service.transition(&app.app_state.db, 999, "done").await,
+        Err(UpdateError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn completed_writes_follow_the_workflow() {
+    let app = TestApp::new();
+    let id = create_todo(&app, "Review PR", &[]).await;
+    transition(&app, id, "blocked").await;
+
+    // Changes that keep completed leave the state alone
+    let renamed = app
+        .put(&format!("/v2/todo/{}", id), json!({ "title": "Review the PR", "completed": false }))
+        .await
+        .json();
+    assert_eq!(renamed["state"], "blocked");
+
+    // Blocked items can't be done without moving on first, whichever way they are completed
+    let refused = app
+        .put(&format!("/v2/todo/{}", id), json!({ "title": "Review the PR", "completed": true }))
+        .await;
+    refused.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    app.assert_documented_in(ApiVersion::V2, Method::PUT, "/todo/{id}", &refused);
+    assert_json_include(
+        &refused.json(),
+        &json!({ "errors": [{
+            "field": "completed",
+            "code": "transition",
+            "message": "can't complete an item in blocked, which only moves to: todo, in_progress"
+        }] }),
+    );
+    let synced = app
+        .post(
+            "/v2/sync",
+            json!({ "changes": [{ "id": id, "completed": { "value": true, "modified_at": "2999-01-01T00:00:00Z" } }] }),
+        )
+        .await
+        .json();
+    assert_json_include(
+        &synced["results"],
+        &json!([{ "status": "rejected", "errors": [{ "field": "completed", "code": "transition" }] }]),
+    );
+    assert_json_include(
+        &app.get(&format!("/v2/todo/{}", id)).await.json(),
+        &json!({ "state": "blocked", "completed": false }),
+    );
+
+    transition(&app, id, "in_progress").await;
+    let completed = app
+        .put(&format!("/v2/todo/{}", id), json!({ "title": "Review the PR", "completed": true }))
+        .await
+        .json();
+    assert_json_include(&completed, &json!({ "state": "done", "completed": true }));
+    let reopened = app
+        .put(&format!("/v1/todo/{}", id), json!({ "title": "Review the PR", "completed": false }))
+        .await
+        .json();
+    assert_eq!(reopened["state"], "todo");
+
+    let created_done = app
+        .post("/v2/todo", json!({ "title": "Already done", "completed": true }))
+        .await
+        .json();
+    assert_eq!(created_done["state"], "done");
+}
+
+#[tokio::test]
+async fn the_board_groups_items_by_state() {
+    let app = TestApp::new();
+    let design = create_todo(&app, "Design", &[]).await;
+    let build = create_todo(&app, "Build", &[]).await;
+    create_todo(&app, "Test", &[]).await;
+    let docs = create_todo(&app, "Docs", &[]).await;
+    transition(&app, design, "in_progress").await;
+    transition(&app, build, "blocked").await;
+    transition(&app, docs, "done").await;
+    app.post(&format!("/v2/todo/{}/tags", build), json!({ "tags": ["backend"] }))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let board = app.get("/v2/todo/board").await;
+
+    board.assert_status(StatusCode::OK);
+    app.assert_documented_in(ApiVersion::V2, Method::GET, "/todo/board", &board);
+    assert_eq!(
+        columns(&board.json()),
+        [
+            ("todo", vec!["Test"]),
+            ("in_progress", vec!["Design"]),
+            ("blocked", vec!["Build"]),
+            ("in_review", vec![]),
+            ("done", vec!["Docs"]),
+        ]
+    );
+    assert_json_include(
+        &board.json()["columns"][1],
+        &json!({ "label": "In progress", "completed": false, "to": ["todo", "blocked", "in_review", "done"], "total": 1 }),
+    );
+
+    // The list filters apply, and q can pick states
+    let tagged = app.get("/v2/todo/board?tag=backend").await.json();
+    assert_eq!(columns(&tagged)[2], ("blocked", vec!["Build"]));
+    assert_eq!(columns(&tagged)[0], ("todo", vec![]));
+    let blocked = app.get("/v2/todo?q=state%3Ablocked").await.json();
+    assert_eq!(blocked["items"][0]["title"], "Build");
+    assert_eq!(blocked["total"], 1);
+    app.get("/v2/todo/board?q=%28")
+        .await
+        .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn items_in_states_no_longer_configured_are_moved_back() {
+    let app = TestApp::new();
+    let open = create_todo(&app, "Open", &[]).await;
+    let done = create_todo(&app, "Done", &[]).await;
+    transition(&app, done, "done").await;
+    {
+        let connection = app.app_state.db.lock().unwrap();
+        connection
+            .execute(format!("UPDATE todos SET state = 'archived' WHERE id = {}", open))
+            .unwrap();
+        connection
+            .execute(format!("UPDATE todos SET state = NULL WHERE id = {}", done))
+            .unwrap();
+    }
+
+    let moved = app.app_state.todo_service.align_states(&app.app_state.db).await.unwrap();
+
+    assert_eq!(moved, 2);
+    assert_eq!(app.get(&format!("/v2/todo/{}", open)).await.json()["state"], "todo");
+    assert_eq!(app.get(&format!("/v2/todo/{}", done)).await.json()["state"], "done");
+}
+
+#[tokio::test]
+async fn workflows_are_configurable() {
+    let workflow: WorkflowConfig = serde_json::from_value(json!({ "states": [
+        { "name": "open", "label": "Open", "to": ["closed"] },
+        { "name": "closed", "label": "Closed", "completed": true }
+    ] }))
+    .unwrap();
+    let app = TestApp::with_settings(|settings| settings.workflow = workflow);
+    let response = app.post("/v2/todo", json!({ "title": "One way" })).await.json();
+    let id = response["id"].as_i64().unwrap();
+    assert_eq!(response["state"], "open");
+
+    transition(&app, id, "closed").await;
+    let back = app
+        .post(&format!("/v2/todo/{}/transition", id), json!({ "to": "open" }))
+        .await;
+    back.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_json_include(&back.json(), &json!({ "errors": [{ "message": "can't move from closed to open, only to: none" }] }));
+
+    let invalid = [
+        json!({ "states": [{ "name": "open", "label": "Open", "to": ["gone"] }, { "name": "done", "label": "Done", "completed": true }] }),
+        json!({ "states": [{ "name": "open", "label": "Open" }] }),
+        json!({ "states": [{ "name": "a", "label": "A" }, { "name": "a", "label": "A", "completed": true }] }),
+    ];
+    for states in invalid {
+        assert!(serde_json::from_value::<WorkflowConfig>(states).is_err());
+    }
+}